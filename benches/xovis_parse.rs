@@ -0,0 +1,70 @@
+//! Xovis parse benchmark: allocations and time per message
+//!
+//! Run with `cargo bench --bench xovis_parse`. A counting global allocator
+//! reports heap allocations of `XovisAdapter::parse` per message.
+
+use gateway::io::sensor_adapter::SensorAdapter;
+use gateway::io::xovis::XovisAdapter;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const ITERATIONS: u64 = 100_000;
+
+/// Typical gate-area frame: four people, one group, two events
+const FRAME: &str = r#"{"live_data":{"frames":[{"time":"2026-01-05T16:41:30.048+00:00",
+"tracked_objects":[
+{"track_id":101,"type":"PERSON","position":[1.52,2.01,1.71]},
+{"track_id":102,"type":"PERSON","position":[2.10,3.40,1.65]},
+{"track_id":103,"type":"CHILD","position":[2.30,3.55,1.12]},
+{"track_id":104,"type":"PERSON","position":[0.80,5.90,1.80]},
+{"track_id":2147483749,"type":"GROUP","position":[2.20,3.47,1.40]}],
+"events":[
+{"type":"ZONE_ENTRY","attributes":{"track_id":101,"geometry_id":1001}},
+{"type":"LINE_CROSS_FORWARD","attributes":{"track_id":104,"geometry_id":1006,"direction":"forward"}}]}]}}"#;
+
+fn main() {
+    let mut adapter = XovisAdapter::default();
+    let received_at = Instant::now();
+
+    // Warm up
+    for _ in 0..1000 {
+        black_box(adapter.parse(black_box(FRAME), received_at));
+    }
+
+    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(adapter.parse(black_box(FRAME), received_at));
+    }
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations_before;
+
+    println!("xovis_parse: {} bytes, 5 objects, 2 events per frame", FRAME.len());
+    println!("  allocations/frame: {:.1}", allocations as f64 / ITERATIONS as f64);
+    println!("  time/frame:        {:.2} us", elapsed.as_secs_f64() * 1e6 / ITERATIONS as f64);
+}
@@ -1853,15 +1853,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     match state_guard.phase {
                         AppPhase::ConfigMenu => match key.code {
                             KeyCode::Char('q') => break 'main,
-                            KeyCode::Up => {
-                                if state_guard.menu_selection > 0 {
-                                    state_guard.menu_selection -= 1;
-                                }
+                            KeyCode::Up if state_guard.menu_selection > 0 => {
+                                state_guard.menu_selection -= 1;
                             }
-                            KeyCode::Down => {
-                                if state_guard.menu_selection < 3 {
-                                    state_guard.menu_selection += 1;
-                                }
+                            KeyCode::Down if state_guard.menu_selection < 3 => {
+                                state_guard.menu_selection += 1;
                             }
                             KeyCode::Char(' ') => match state_guard.menu_selection {
                                 0 => {
@@ -2012,28 +2008,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             // Scenario view controls
                             if state_guard.view_mode == ViewMode::Scenarios {
                                 match key.code {
-                                    KeyCode::Up => {
-                                        if state_guard.scenario_selection > 0 {
-                                            state_guard.scenario_selection -= 1;
-                                        }
+                                    KeyCode::Up if state_guard.scenario_selection > 0 => {
+                                        state_guard.scenario_selection -= 1;
                                     }
-                                    KeyCode::Down => {
-                                        if state_guard.scenario_selection < SCENARIOS.len() - 1 {
-                                            state_guard.scenario_selection += 1;
-                                        }
+                                    KeyCode::Down
+                                        if state_guard.scenario_selection < SCENARIOS.len() - 1 =>
+                                    {
+                                        state_guard.scenario_selection += 1;
                                     }
-                                    KeyCode::Enter => {
-                                        if !state_guard.scenario_runner.is_running() {
-                                            let scenario =
-                                                &SCENARIOS[state_guard.scenario_selection];
-                                            state_guard.scenario_runner.start(scenario);
-                                            state_guard.tracks.clear();
-                                            state_guard.next_track_id = 100;
-                                            state_guard.log(
-                                                LogSource::Scenario,
-                                                format!("Starting scenario: {}", scenario.name),
-                                            );
-                                        }
+                                    KeyCode::Enter if !state_guard.scenario_runner.is_running() => {
+                                        let scenario = &SCENARIOS[state_guard.scenario_selection];
+                                        state_guard.scenario_runner.start(scenario);
+                                        state_guard.tracks.clear();
+                                        state_guard.next_track_id = 100;
+                                        state_guard.log(
+                                            LogSource::Scenario,
+                                            format!("Starting scenario: {}", scenario.name),
+                                        );
                                     }
                                     _ => {}
                                 }
@@ -818,13 +818,11 @@ impl DashboardState {
                             GateZoneStatus::Blocked { tid: event.tid }
                         };
                     }
-                    "zone_exit" => {
-                        if self.gate_zone.occupant_tid == Some(event.tid) {
-                            self.gate_zone.occupant_tid = None;
-                            self.gate_zone.occupant_auth = false;
-                            self.gate_zone.entered_at = None;
-                            self.gate_zone_status = GateZoneStatus::Empty;
-                        }
+                    "zone_exit" if self.gate_zone.occupant_tid == Some(event.tid) => {
+                        self.gate_zone.occupant_tid = None;
+                        self.gate_zone.occupant_auth = false;
+                        self.gate_zone.entered_at = None;
+                        self.gate_zone_status = GateZoneStatus::Empty;
                     }
                     _ => {}
                 }
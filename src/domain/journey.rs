@@ -1,7 +1,7 @@
 //! Journey data model for tracking customer paths through the store
 
 use crate::domain::types::TrackId;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use smallvec::{smallvec, SmallVec};
 use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
@@ -18,13 +18,14 @@ pub fn epoch_ms() -> u64 {
 }
 
 /// Journey outcome
-#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum JourneyOutcome {
     InProgress,
     Completed,       // crossed EXIT line forward
     ReturnedToStore, // went back into store (backward entry cross or zone entry to STORE)
     Lost,            // track disappeared between ENTRY and EXIT (true loss)
     PassThrough,     // reached exit area but no zone engagement
+    Interrupted,     // still in progress when the gateway shut down
 }
 
 impl JourneyOutcome {
@@ -36,12 +37,13 @@ impl JourneyOutcome {
             JourneyOutcome::ReturnedToStore => "returned",
             JourneyOutcome::Lost => "lost",
             JourneyOutcome::PassThrough => "pass_through",
+            JourneyOutcome::Interrupted => "interrupted",
         }
     }
 }
 
 /// Event types that can occur in a journey
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum JourneyEventType {
     TrackCreate,
     ZoneEntry,
@@ -54,6 +56,7 @@ pub enum JourneyEventType {
     Stitch,
     GateCmd,
     GateOpen,
+    GateTrigger,
     Acc,
 }
 
@@ -72,23 +75,25 @@ impl JourneyEventType {
             JourneyEventType::Stitch => "stitch",
             JourneyEventType::GateCmd => "gate_cmd",
             JourneyEventType::GateOpen => "gate_open",
+            JourneyEventType::GateTrigger => "gate_trigger",
             JourneyEventType::Acc => "acc",
         }
     }
 }
 
 /// A single event in a journey
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JourneyEvent {
     pub t: JourneyEventType,   // event type
     pub z: Option<String>,     // zone or line name
     pub ts: u64,               // epoch ms
     pub extra: Option<String>, // additional data
+    pub derived: bool,         // synthesized from positions, not a sensor event
 }
 
 impl JourneyEvent {
     pub fn new(event_type: JourneyEventType, ts: u64) -> Self {
-        Self { t: event_type, z: None, ts, extra: None }
+        Self { t: event_type, z: None, ts, extra: None, derived: false }
     }
 
     pub fn with_zone(mut self, zone: &str) -> Self {
@@ -101,6 +106,11 @@ impl JourneyEvent {
         self
     }
 
+    pub fn with_derived(mut self, derived: bool) -> Self {
+        self.derived = derived;
+        self
+    }
+
     /// Convert to JSON value for short-key format
     fn to_json_value(&self) -> serde_json::Value {
         let mut obj = serde_json::Map::new();
@@ -112,12 +122,17 @@ impl JourneyEvent {
         if let Some(x) = &self.extra {
             obj.insert("x".to_string(), serde_json::Value::String(x.clone()));
         }
+        if self.derived {
+            obj.insert("d".to_string(), serde_json::Value::Bool(true));
+        }
         serde_json::Value::Object(obj)
     }
 }
 
 /// Complete journey for a tracked person
-#[derive(Debug, Clone)]
+///
+/// Serde derives are for tracker snapshots; egress uses the short-key `to_json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Journey {
     pub jid: String,                  // UUIDv7 journey ID
     pub pid: String,                  // UUIDv7 person ID (stable across stitches)
@@ -129,15 +144,21 @@ pub struct Journey {
     pub acc_matched: bool,
     pub acc_group_size: u8, // 1 = solo, 2+ = group (people at POS together)
     pub acc_group_tids: SmallVec<[TrackId; 4]>, // Track IDs of all group members
+    pub group: Option<TrackId>, // Xovis GROUP track this person walked with (last joined)
+    pub ingress_gap_ms: u64, // sensor time lost to MQTT reconnects during the journey
     pub gate_cmd_at: Option<u64>, // epoch ms (first gate command)
     pub gate_opened_at: Option<u64>, // epoch ms from RS485
     pub gate_was_open: bool,
-    pub gate_open_count: u8,    // number of gate opens issued (max 2)
-    pub gate_zone_exited: bool, // true if exited GATE zone without exiting store
-    pub started_at: u64,        // epoch ms
-    pub ended_at: Option<u64>,  // epoch ms
+    pub gate_open_count: u8,          // number of gate opens issued (max 2)
+    pub gate_zone_exited: bool,       // true if exited GATE zone without exiting store
+    pub gate_trigger_at: Option<u64>, // epoch ms (first virtual pre-gate trigger)
+    pub gate_entry_at: Option<u64>,   // epoch ms (first GATE zone entry)
+    pub started_at: u64,              // epoch ms
+    pub ended_at: Option<u64>,        // epoch ms
     pub crossed_entry: bool,
     pub exit_inferred: bool, // true if exit was inferred (track lost in exit corridor)
+    #[serde(default)]
+    pub restored_at: Option<u64>, // epoch ms the journey was restored from a snapshot
     pub events: Vec<JourneyEvent>,
 }
 
@@ -158,7 +179,11 @@ impl Journey {
     /// assert!(!journey.authorized);
     /// ```
     pub fn new(track_id: TrackId) -> Self {
-        let now = epoch_ms();
+        Self::new_at(track_id, epoch_ms())
+    }
+
+    /// Create a new journey started at `started_at` (epoch ms)
+    pub fn new_at(track_id: TrackId, started_at: u64) -> Self {
         Self {
             jid: new_uuid_v7(),
             pid: new_uuid_v7(),
@@ -170,22 +195,32 @@ impl Journey {
             acc_matched: false,
             acc_group_size: 1,
             acc_group_tids: SmallVec::new(),
+            group: None,
+            ingress_gap_ms: 0,
             gate_cmd_at: None,
             gate_opened_at: None,
             gate_was_open: false,
             gate_open_count: 0,
             gate_zone_exited: false,
-            started_at: now,
+            gate_trigger_at: None,
+            gate_entry_at: None,
+            started_at,
             ended_at: None,
             crossed_entry: false,
             exit_inferred: false,
+            restored_at: None,
             events: Vec::with_capacity(16),
         }
     }
 
     /// Create a new journey that continues from a previous one (re-entry)
-    pub fn new_with_parent(track_id: TrackId, parent_jid: &str, parent_pid: &str) -> Self {
-        let mut journey = Self::new(track_id);
+    pub fn new_with_parent(
+        track_id: TrackId,
+        parent_jid: &str,
+        parent_pid: &str,
+        started_at: u64,
+    ) -> Self {
+        let mut journey = Self::new_at(track_id, started_at);
         journey.parent = Some(parent_jid.to_string());
         journey.pid = parent_pid.to_string();
         journey
@@ -203,8 +238,13 @@ impl Journey {
 
     /// Mark the journey as completed
     pub fn complete(&mut self, outcome: JourneyOutcome) {
+        self.complete_at(outcome, epoch_ms());
+    }
+
+    /// Mark the journey as completed at `ended_at` (epoch ms)
+    pub fn complete_at(&mut self, outcome: JourneyOutcome, ended_at: u64) {
         self.outcome = outcome;
-        self.ended_at = Some(epoch_ms());
+        self.ended_at = Some(ended_at);
     }
 
     /// Get the current/last track ID
@@ -269,6 +309,10 @@ impl Journey {
             obj.insert("acc_group_tids".to_string(), serde_json::json!(group_tids));
         }
 
+        if let Some(group) = self.group {
+            obj.insert("group".to_string(), serde_json::Value::Number(group.0.into()));
+        }
+
         if let Some(gate_cmd) = self.gate_cmd_at {
             obj.insert("gate_cmd".to_string(), serde_json::Value::Number(gate_cmd.into()));
         }
@@ -276,9 +320,21 @@ impl Journey {
             obj.insert("gate_open".to_string(), serde_json::Value::Number(gate_open.into()));
         }
         obj.insert("gate_was_open".to_string(), serde_json::Value::Bool(self.gate_was_open));
+        if let Some(trigger) = self.gate_trigger_at {
+            obj.insert("gate_trigger".to_string(), serde_json::Value::Number(trigger.into()));
+        }
+        if let Some(entry) = self.gate_entry_at {
+            obj.insert("gate_entry".to_string(), serde_json::Value::Number(entry.into()));
+        }
         if self.exit_inferred {
             obj.insert("exit_inferred".to_string(), serde_json::Value::Bool(true));
         }
+        if self.ingress_gap_ms > 0 {
+            obj.insert("gap_ms".to_string(), serde_json::Value::Number(self.ingress_gap_ms.into()));
+        }
+        if let Some(restored) = self.restored_at {
+            obj.insert("restored".to_string(), serde_json::Value::Number(restored.into()));
+        }
 
         obj.insert("t0".to_string(), serde_json::Value::Number(self.started_at.into()));
         if let Some(ended) = self.ended_at {
@@ -315,7 +371,8 @@ mod tests {
 
     #[test]
     fn test_journey_with_parent() {
-        let journey = Journey::new_with_parent(TrackId(200), "parent-jid-123", "pid-456");
+        let journey =
+            Journey::new_with_parent(TrackId(200), "parent-jid-123", "pid-456", epoch_ms());
 
         assert_eq!(journey.tids.as_slice(), &[TrackId(200)]);
         assert_eq!(journey.parent, Some("parent-jid-123".to_string()));
@@ -353,6 +410,7 @@ mod tests {
         journey.crossed_entry = true;
         journey.gate_cmd_at = Some(1736012345678);
         journey.gate_opened_at = Some(1736012345890);
+        journey.gate_trigger_at = Some(1736012345600);
 
         journey.add_event(JourneyEvent::new(JourneyEventType::EntryCross, 1736012340000));
         journey.add_event(
@@ -378,6 +436,8 @@ mod tests {
         assert_eq!(parsed["acc"], true);
         assert_eq!(parsed["gate_cmd"], 1736012345678_u64);
         assert_eq!(parsed["gate_open"], 1736012345890_u64);
+        assert_eq!(parsed["gate_trigger"], 1736012345600_u64);
+        assert!(parsed.get("gate_entry").is_none());
 
         let events = parsed["ev"].as_array().unwrap();
         assert_eq!(events.len(), 3);
@@ -386,6 +446,22 @@ mod tests {
         assert_eq!(events[1]["z"], "POS_1");
         assert_eq!(events[2]["t"], "zone_exit");
         assert_eq!(events[2]["x"], "dwell=7500");
+        // Sensor events carry no derived marker
+        assert!(events.iter().all(|e| e.get("d").is_none()));
+    }
+
+    #[test]
+    fn test_derived_event_to_json() {
+        let mut journey = Journey::new(TrackId(100));
+        journey.add_event(
+            JourneyEvent::new(JourneyEventType::ZoneExit, 1736012348500)
+                .with_zone("POS_1")
+                .with_derived(true),
+        );
+
+        let parsed: serde_json::Value = serde_json::from_str(&journey.to_json()).unwrap();
+        assert_eq!(parsed["ev"][0]["t"], "zone_exit");
+        assert_eq!(parsed["ev"][0]["d"], true);
     }
 
     #[test]
@@ -407,5 +483,6 @@ mod tests {
         assert_eq!(JourneyOutcome::ReturnedToStore.as_str(), "returned");
         assert_eq!(JourneyOutcome::Lost.as_str(), "lost");
         assert_eq!(JourneyOutcome::PassThrough.as_str(), "pass_through");
+        assert_eq!(JourneyOutcome::Interrupted.as_str(), "interrupted");
     }
 }
@@ -1,6 +1,9 @@
 //! Shared types for the gateway PoC
 
 use serde::{Deserialize, Deserializer, Serialize};
+use smallvec::SmallVec;
+use std::borrow::Cow;
+use std::sync::Arc;
 use std::time::Instant;
 
 /// Newtype wrapper for track IDs to provide type safety
@@ -8,6 +11,35 @@ use std::time::Instant;
 #[repr(transparent)]
 pub struct TrackId(pub i64);
 
+impl TrackId {
+    /// Bit offset of the sensor number in a namespaced track ID
+    ///
+    /// Raw Xovis track IDs use the low 32 bits (including the GROUP bit), so
+    /// sensor 0 IDs are identical to the raw IDs.
+    pub const SENSOR_SHIFT: u32 = 32;
+
+    /// Xovis GROUP bit: track IDs with it set are group aggregates, not individuals
+    pub const GROUP_BIT: i64 = 0x8000_0000;
+
+    /// Track ID from sensor `sensor_id`, unique across sensors
+    #[inline]
+    pub fn namespaced(sensor_id: u8, raw: i64) -> Self {
+        TrackId(((sensor_id as i64) << Self::SENSOR_SHIFT) | (raw & 0xFFFF_FFFF))
+    }
+
+    /// Sensor number this track ID belongs to
+    #[inline]
+    pub fn sensor_id(self) -> u8 {
+        (self.0 >> Self::SENSOR_SHIFT) as u8
+    }
+
+    /// Whether this is a Xovis GROUP aggregate track
+    #[inline]
+    pub fn is_group(self) -> bool {
+        self.0 & Self::GROUP_BIT != 0
+    }
+}
+
 impl std::fmt::Display for TrackId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
@@ -25,39 +57,89 @@ impl std::fmt::Display for GeometryId {
     }
 }
 
+/// Role of a geometry (zone or line) in the gateway flow
+///
+/// Resolved once per `GeometryId` by `Config` from the `[zones]` role
+/// settings, so logic never depends on how a site names its zones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ZoneKind {
+    /// POS zone (dwell counts toward ACC authorization)
+    Pos,
+    /// Dwell zone (auto-authorizes after min dwell)
+    Dwell,
+    /// Gate zone (opens the gate when authorized)
+    Gate,
+    /// Store zone (back in the shop floor)
+    Store,
+    /// Exit line
+    Exit,
+    /// Approach line (in front of the gate)
+    Approach,
+    /// Entry line (between store and checkout area)
+    Entry,
+    /// Any other geometry
+    Other,
+}
+
+impl ZoneKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ZoneKind::Pos => "pos",
+            ZoneKind::Dwell => "dwell",
+            ZoneKind::Gate => "gate",
+            ZoneKind::Store => "store",
+            ZoneKind::Exit => "exit",
+            ZoneKind::Approach => "approach",
+            ZoneKind::Entry => "entry",
+            ZoneKind::Other => "other",
+        }
+    }
+
+    /// Zones where dwell is tracked in `PosOccupancyState`
+    #[inline]
+    pub fn tracks_dwell(&self) -> bool {
+        matches!(self, ZoneKind::Pos | ZoneKind::Dwell)
+    }
+}
+
 /// Xovis message structure for parsing
+///
+/// Borrows strings from the payload (`Cow` only allocates for JSON escapes), so
+/// a frame parses without per-field allocations.
 #[derive(Debug, Deserialize)]
-pub struct XovisMessage {
-    pub live_data: Option<LiveData>,
+pub struct XovisMessage<'a> {
+    #[serde(borrow)]
+    pub live_data: Option<LiveData<'a>>,
 }
 
 #[derive(Debug, Deserialize)]
-pub struct LiveData {
-    pub frames: Vec<Frame>,
+pub struct LiveData<'a> {
+    #[serde(borrow)]
+    pub frames: Vec<Frame<'a>>,
 }
 
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-pub struct Frame {
+pub struct Frame<'a> {
     /// Timestamp - can be ISO 8601 string or epoch milliseconds integer
-    #[serde(default, deserialize_with = "deserialize_timestamp")]
-    pub time: TimestampValue,
-    #[serde(default)]
-    pub tracked_objects: Vec<TrackedObject>,
-    #[serde(default)]
-    pub events: Vec<XovisEvent>,
+    #[serde(borrow, default, deserialize_with = "deserialize_timestamp")]
+    pub time: TimestampValue<'a>,
+    #[serde(borrow, default)]
+    pub tracked_objects: Vec<XovisObject<'a>>,
+    #[serde(borrow, default)]
+    pub events: Vec<XovisEvent<'a>>,
 }
 
 /// Timestamp that can be either ISO 8601 string or epoch milliseconds
 #[derive(Debug, Clone, Default)]
-pub enum TimestampValue {
+pub enum TimestampValue<'a> {
     #[default]
     None,
-    IsoString(String),
+    IsoString(Cow<'a, str>),
     EpochMs(u64),
 }
 
-fn deserialize_timestamp<'de, D>(deserializer: D) -> Result<TimestampValue, D::Error>
+fn deserialize_timestamp<'de, D>(deserializer: D) -> Result<TimestampValue<'de>, D::Error>
 where
     D: Deserializer<'de>,
 {
@@ -66,34 +148,34 @@ where
     struct TimestampVisitor;
 
     impl<'de> Visitor<'de> for TimestampVisitor {
-        type Value = TimestampValue;
+        type Value = TimestampValue<'de>;
 
         fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
             formatter.write_str("a string or integer timestamp")
         }
 
-        fn visit_str<E>(self, value: &str) -> Result<TimestampValue, E>
+        fn visit_borrowed_str<E>(self, value: &'de str) -> Result<TimestampValue<'de>, E>
         where
             E: de::Error,
         {
-            Ok(TimestampValue::IsoString(value.to_string()))
+            Ok(TimestampValue::IsoString(Cow::Borrowed(value)))
         }
 
-        fn visit_string<E>(self, value: String) -> Result<TimestampValue, E>
+        fn visit_str<E>(self, value: &str) -> Result<TimestampValue<'de>, E>
         where
             E: de::Error,
         {
-            Ok(TimestampValue::IsoString(value))
+            Ok(TimestampValue::IsoString(Cow::Owned(value.to_string())))
         }
 
-        fn visit_u64<E>(self, value: u64) -> Result<TimestampValue, E>
+        fn visit_u64<E>(self, value: u64) -> Result<TimestampValue<'de>, E>
         where
             E: de::Error,
         {
             Ok(TimestampValue::EpochMs(value))
         }
 
-        fn visit_i64<E>(self, value: i64) -> Result<TimestampValue, E>
+        fn visit_i64<E>(self, value: i64) -> Result<TimestampValue<'de>, E>
         where
             E: de::Error,
         {
@@ -106,28 +188,73 @@ where
     deserializer.deserialize_any(TimestampVisitor)
 }
 
+/// Tracked object as sent by the sensor
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-pub struct TrackedObject {
+pub struct XovisObject<'a> {
     pub track_id: i64,
-    #[serde(rename = "type")]
-    pub obj_type: String,
+    #[serde(borrow, rename = "type")]
+    pub obj_type: Cow<'a, str>,
     #[serde(default)]
-    pub position: Vec<f64>,
+    pub position: SmallVec<[f64; 3]>,
 }
 
 #[derive(Debug, Deserialize)]
-pub struct XovisEvent {
-    #[serde(rename = "type")]
-    pub event_type: String,
-    pub attributes: Option<EventAttributes>,
+pub struct XovisEvent<'a> {
+    #[serde(borrow, rename = "type")]
+    pub event_type: Cow<'a, str>,
+    #[serde(borrow)]
+    pub attributes: Option<EventAttributes<'a>>,
 }
 
 #[derive(Debug, Deserialize)]
-pub struct EventAttributes {
+pub struct EventAttributes<'a> {
     pub track_id: Option<i64>,
     pub geometry_id: Option<i32>,
-    pub direction: Option<String>,
+    #[serde(borrow)]
+    pub direction: Option<Cow<'a, str>>,
+}
+
+/// Tracked object of a sensor frame (positions for streaming and derived events)
+#[derive(Debug, Clone)]
+pub struct TrackedObject {
+    pub track_id: i64,
+    /// Object type (e.g. "PERSON", "CHILD", "GROUP")
+    pub obj_type: Arc<str>,
+    /// [x, y, height] (may be shorter if the sensor sent fewer coordinates)
+    pub position: SmallVec<[f64; 3]>,
+}
+
+/// Line crossing direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Forward,
+    Backward,
+}
+
+impl Direction {
+    /// Parse a Xovis direction attribute ("forward" / "backward", any case)
+    pub fn parse(s: &str) -> Option<Self> {
+        if s.eq_ignore_ascii_case("forward") {
+            Some(Direction::Forward)
+        } else if s.eq_ignore_ascii_case("backward") {
+            Some(Direction::Backward)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Forward => "forward",
+            Direction::Backward => "backward",
+        }
+    }
+}
+
+impl std::fmt::Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 /// Parsed event for internal processing
@@ -137,10 +264,14 @@ pub struct ParsedEvent {
     pub event_type: EventType,
     pub track_id: TrackId,
     pub geometry_id: Option<GeometryId>,
-    pub direction: Option<String>,
+    pub direction: Option<Direction>,
     pub event_time: u64,
     pub received_at: Instant,
     pub position: Option<[f64; 3]>, // [x, y, height] for stitching
+    /// Synthesized by the position zone engine rather than reported by the sensor
+    pub derived: bool,
+    /// Xovis object type of the track (e.g. "PERSON", "CHILD") if in the frame
+    pub obj_type: Option<Arc<str>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -156,6 +287,15 @@ pub enum EventType {
     AccEvent(String),
     /// Simulated ACC event with POS zone name directly (e.g., "POS_1")
     AccEventSimulated(String),
+    /// Track reached a virtual pre-gate trigger (geometry_id = gate zone)
+    GateTrigger,
+    /// Track joined the Xovis GROUP track (derived from positions)
+    GroupJoin(TrackId),
+    /// Track left the Xovis GROUP track
+    GroupLeave(TrackId),
+    /// Frames were lost across an MQTT reconnect since this frame time (epoch ms);
+    /// event_time is the first frame time after the reconnect
+    IngressGap(u64),
     Unknown(String),
 }
 
@@ -188,23 +328,32 @@ impl EventType {
             EventType::DoorStateChange(_) => "door_state_change",
             EventType::AccEvent(_) => "acc_event",
             EventType::AccEventSimulated(_) => "acc_event_simulated",
+            EventType::GateTrigger => "gate_trigger",
+            EventType::GroupJoin(_) => "group_join",
+            EventType::GroupLeave(_) => "group_leave",
+            EventType::IngressGap(_) => "ingress_gap",
             EventType::Unknown(s) => s,
         }
     }
 }
 
 /// Tracked person state
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct Person {
     pub track_id: TrackId,
     pub current_zone: Option<GeometryId>,
     pub authorized: bool,
     pub last_position: Option<[f64; 3]>, // [x, y, height] for stitching
+    /// When `last_position` was reported (epoch ms)
+    #[serde(default)]
+    pub last_position_at: Option<u64>,
     /// Maximum y-position observed during journey (for observability)
     pub max_y: f32,
     /// Whether any zone events occurred (ZoneEntry/ZoneExit)
     pub has_zone_events: bool,
+    /// Xovis object type (from the latest event that carried it)
+    pub obj_type: Option<Arc<str>>,
 }
 
 impl Person {
@@ -215,8 +364,10 @@ impl Person {
             current_zone: None,
             authorized: false,
             last_position: None,
+            last_position_at: None,
             max_y: 0.0,
             has_zone_events: false,
+            obj_type: None,
         }
     }
 
@@ -252,10 +403,49 @@ impl DoorStatus {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_zone_kind_tracks_dwell() {
+        assert!(ZoneKind::Pos.tracks_dwell());
+        assert!(ZoneKind::Dwell.tracks_dwell());
+        assert!(!ZoneKind::Gate.tracks_dwell());
+        assert!(!ZoneKind::Other.tracks_dwell());
+        assert_eq!(ZoneKind::Approach.as_str(), "approach");
+    }
+
+    #[test]
+    fn test_track_id_namespacing() {
+        let id = TrackId::namespaced(3, 1234);
+        assert_eq!(id.sensor_id(), 3);
+        assert_eq!(id.0 & 0xFFFF_FFFF, 1234);
+        assert_ne!(id, TrackId::namespaced(4, 1234));
+        // Sensor 0 keeps raw IDs unchanged
+        assert_eq!(TrackId::namespaced(0, 1234), TrackId(1234));
+        assert_eq!(TrackId(1234).sensor_id(), 0);
+    }
+
     #[test]
     fn test_event_type_from_str() {
         assert_eq!("ZONE_ENTRY".parse::<EventType>().unwrap(), EventType::ZoneEntry);
         assert_eq!("TRACK_DELETE".parse::<EventType>().unwrap(), EventType::TrackDelete);
         assert!(matches!("UNKNOWN_TYPE".parse::<EventType>().unwrap(), EventType::Unknown(_)));
     }
+
+    #[test]
+    fn test_frame_borrows_from_payload() {
+        let json = r#"{"live_data": {"frames": [{
+            "time": "2026-01-05T16:41:30.048+00:00",
+            "tracked_objects": [{"track_id": 1, "type": "PERSON", "position": [1.0, 2.0, 1.7]}],
+            "events": [{"type": "LINE_CROSS_FORWARD",
+                        "attributes": {"track_id": 1, "direction": "Forward"}}]
+        }]}}"#;
+        let message: XovisMessage = serde_json::from_str(json).unwrap();
+        let frame = &message.live_data.unwrap().frames[0];
+
+        assert!(matches!(frame.time, TimestampValue::IsoString(Cow::Borrowed(_))));
+        assert!(matches!(frame.tracked_objects[0].obj_type, Cow::Borrowed("PERSON")));
+        assert!(matches!(frame.events[0].event_type, Cow::Borrowed("LINE_CROSS_FORWARD")));
+        let direction = frame.events[0].attributes.as_ref().unwrap().direction.as_deref();
+        assert_eq!(direction.and_then(Direction::parse), Some(Direction::Forward));
+        assert_eq!(Direction::parse("sideways"), None);
+    }
 }
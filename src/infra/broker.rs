@@ -1,9 +1,17 @@
 //! Embedded MQTT broker using rumqttd
+//!
+//! With `[[broker.users]]` configured the broker listens on loopback
+//! (`internal_port`) and the ACL proxy in `broker_acl` serves the public
+//! address, checking credentials and topic permissions. The loopback listener
+//! only accepts the proxy's own randomly generated login.
 
+use crate::infra::broker_acl::{self, BrokerAcl};
 use crate::infra::config::Config as AppConfig;
+use crate::infra::tls;
 use rumqttd::{Broker, Config, ConnectionSettings, RouterConfig, ServerSettings};
 use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use std::thread;
 use tracing::{info, warn};
 
@@ -31,19 +39,33 @@ pub fn start_embedded_broker(app_config: &AppConfig) {
         }
     };
 
+    // Behind the ACL proxy: loopback only, the proxy handles TLS
+    let users = app_config.broker_users();
+    let (broker_addr, broker_tls, auth) = if users.is_empty() {
+        warn!(addr = %listen_addr, "broker_anonymous_access: no [[broker.users]] configured");
+        (listen_addr, tls::broker_tls(app_config.broker_tls()), None)
+    } else {
+        let acl = Arc::new(BrokerAcl::new(users));
+        let auth = acl.upstream_auth();
+        if !start_acl_proxy(app_config, listen_addr, acl) {
+            return;
+        }
+        (SocketAddr::from(([127, 0, 0, 1], app_config.broker_internal_port())), None, Some(auth))
+    };
+
     let mut servers = HashMap::new();
     servers.insert(
         "v4".to_string(),
         ServerSettings {
             name: "v4".to_string(),
-            listen: listen_addr,
-            tls: None,
+            listen: broker_addr,
+            tls: broker_tls,
             next_connection_delay_ms: 1,
             connections: ConnectionSettings {
                 connection_timeout_ms: 5000,
                 max_payload_size: 262144,
                 max_inflight_count: 200,
-                auth: None,
+                auth,
                 dynamic_filters: false,
                 external_auth: None,
             },
@@ -77,5 +99,35 @@ pub fn start_embedded_broker(app_config: &AppConfig) {
 
     // Give broker time to start
     thread::sleep(std::time::Duration::from_millis(100));
-    info!(bind_address = %bind_address, port = %port, "broker_started");
+    info!(
+        bind_address = %bind_address,
+        port = %port,
+        tls = %app_config.broker_tls().enabled,
+        users = %users.len(),
+        "broker_started"
+    );
+}
+
+/// Bind the public listener and spawn the ACL proxy (false on failure)
+fn start_acl_proxy(app_config: &AppConfig, listen_addr: SocketAddr, acl: Arc<BrokerAcl>) -> bool {
+    let acceptor = match tls::broker_acceptor(app_config.broker_tls()) {
+        Ok(acceptor) => acceptor,
+        Err(e) => {
+            warn!(error = %e, "broker_tls_failed");
+            return false;
+        }
+    };
+    let listener = std::net::TcpListener::bind(listen_addr)
+        .and_then(|l| l.set_nonblocking(true).map(|_| l))
+        .and_then(tokio::net::TcpListener::from_std);
+    let listener = match listener {
+        Ok(listener) => listener,
+        Err(e) => {
+            warn!(error = %e, addr = %listen_addr, "broker_bind_failed");
+            return false;
+        }
+    };
+    let upstream = SocketAddr::from(([127, 0, 0, 1], app_config.broker_internal_port()));
+    tokio::spawn(broker_acl::serve(listener, upstream, acl, acceptor));
+    true
 }
@@ -0,0 +1,298 @@
+//! Authentication and topic ACLs for the embedded broker
+//!
+//! rumqttd checks credentials at CONNECT but has no per-topic permissions.
+//! With `[[broker.users]]` configured, rumqttd listens on loopback only and
+//! this proxy owns the public listener:
+//! - CONNECT is refused unless username and password match a user
+//! - every PUBLISH (and the will topic) must match one of the user's
+//!   `publish` filters, every SUBSCRIBE filter must lie within `subscribe`
+//! - allowed packets are forwarded unchanged, except that CONNECT carries the
+//!   proxy's own login; broker-to-client traffic is copied as is
+//!
+//! rumqttd only accepts that login, a random password generated at startup,
+//! so local processes can't skip the ACLs by connecting to it directly.
+//!
+//! MQTT 3.1.1 can't refuse a single PUBLISH, so a denied packet closes the
+//! connection.
+
+use crate::infra::config::BrokerUser;
+use bytes::{Bytes, BytesMut};
+use rumqttc::mqttbytes::v4::{self, ConnAck, ConnectReturnCode, Login, Packet};
+use rumqttc::mqttbytes::{self, matches};
+use rustc_hash::FxHashMap;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use subtle::ConstantTimeEq;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::TlsAcceptor;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// Largest packet accepted from clients (matches the broker's max_payload_size)
+const MAX_PACKET_SIZE: usize = 262144;
+
+/// Username the proxy logs in to the broker with
+const UPSTREAM_USERNAME: &str = "gateway-acl-proxy";
+
+/// Broker users by username, plus the proxy's own broker login
+pub struct BrokerAcl {
+    users: FxHashMap<String, BrokerUser>,
+    upstream_login: Login,
+}
+
+impl BrokerAcl {
+    pub fn new(users: &[BrokerUser]) -> Self {
+        Self {
+            users: users.iter().map(|u| (u.username.clone(), u.clone())).collect(),
+            upstream_login: Login::new(UPSTREAM_USERNAME, Uuid::new_v4().simple().to_string()),
+        }
+    }
+
+    /// Credentials for the broker listener behind the proxy (rumqttd `auth`)
+    pub fn upstream_auth(&self) -> HashMap<String, String> {
+        let login = &self.upstream_login;
+        HashMap::from([(login.username.clone(), login.password.clone())])
+    }
+
+    /// Password compared in constant time
+    fn authenticate(&self, username: &str, password: &str) -> Option<&BrokerUser> {
+        self.users.get(username).filter(|u| {
+            u.password
+                .as_deref()
+                .is_some_and(|expected| bool::from(expected.as_bytes().ct_eq(password.as_bytes())))
+        })
+    }
+}
+
+fn may_publish(user: &BrokerUser, topic: &str) -> bool {
+    user.publish.iter().any(|filter| matches(topic, filter))
+}
+
+/// A requested filter is allowed if an allowed filter covers all of it
+fn may_subscribe(user: &BrokerUser, filter: &str) -> bool {
+    user.subscribe.iter().any(|allowed| matches(filter, allowed))
+}
+
+/// Accept clients on `listener` and proxy them to the broker at `upstream`
+pub async fn serve(
+    listener: TcpListener,
+    upstream: SocketAddr,
+    acl: Arc<BrokerAcl>,
+    tls: Option<TlsAcceptor>,
+) {
+    info!(upstream = %upstream, users = %acl.users.len(), "broker_acl_started");
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                warn!(error = %e, "broker_acl_accept_failed");
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        let acl = acl.clone();
+        let tls = tls.clone();
+        tokio::spawn(async move {
+            let result = match tls {
+                Some(acceptor) => match acceptor.accept(stream).await {
+                    Ok(stream) => proxy(stream, peer, upstream, &acl).await,
+                    Err(e) => Err(e.into()),
+                },
+                None => proxy(stream, peer, upstream, &acl).await,
+            };
+            if let Err(e) = result {
+                debug!(peer = %peer, error = %e, "broker_acl_connection_closed");
+            }
+        });
+    }
+}
+
+async fn proxy<S>(
+    client: S,
+    peer: SocketAddr,
+    upstream: SocketAddr,
+    acl: &BrokerAcl,
+) -> anyhow::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Send + 'static,
+{
+    let (mut client_rx, mut client_tx) = tokio::io::split(client);
+    let mut buf = BytesMut::with_capacity(4096);
+
+    let (packet, _) = next_packet(&mut client_rx, &mut buf).await?;
+    let Packet::Connect(connect) = packet else {
+        anyhow::bail!("expected CONNECT");
+    };
+    let user = connect.login.as_ref().and_then(|l| acl.authenticate(&l.username, &l.password));
+    let Some(user) = user else {
+        warn!(peer = %peer, client_id = %connect.client_id, "broker_auth_failed");
+        return refuse(&mut client_tx, ConnectReturnCode::BadUserNamePassword).await;
+    };
+    if let Some(will) = connect.last_will.as_ref().filter(|w| !may_publish(user, &w.topic)) {
+        warn!(user = %user.username, topic = %will.topic, "broker_acl_denied");
+        return refuse(&mut client_tx, ConnectReturnCode::NotAuthorized).await;
+    }
+    debug!(
+        peer = %peer,
+        user = %user.username,
+        client_id = %connect.client_id,
+        "broker_client_connected"
+    );
+
+    // The broker only knows the proxy's login
+    let mut upstream_connect = connect.clone();
+    upstream_connect.login = Some(acl.upstream_login.clone());
+    let mut raw = BytesMut::new();
+    upstream_connect.write(&mut raw)?;
+
+    let (mut server_rx, mut server_tx) = TcpStream::connect(upstream).await?.into_split();
+    server_tx.write_all(&raw).await?;
+    let mut downstream =
+        tokio::spawn(async move { tokio::io::copy(&mut server_rx, &mut client_tx).await });
+
+    let upstream_loop = async {
+        loop {
+            let (packet, raw) = next_packet(&mut client_rx, &mut buf).await?;
+            let denied = match &packet {
+                Packet::Publish(publish) => {
+                    Some(publish.topic.as_str()).filter(|t| !may_publish(user, t))
+                }
+                Packet::Subscribe(subscribe) => subscribe
+                    .filters
+                    .iter()
+                    .map(|f| f.path.as_str())
+                    .find(|f| !may_subscribe(user, f)),
+                _ => None,
+            };
+            if let Some(topic) = denied {
+                warn!(
+                    user = %user.username,
+                    client_id = %connect.client_id,
+                    topic = %topic,
+                    "broker_acl_denied"
+                );
+                return Ok(());
+            }
+            server_tx.write_all(&raw).await?;
+        }
+    };
+    // Either side closing ends the connection
+    let result = tokio::select! {
+        result = upstream_loop => result,
+        _ = &mut downstream => Ok(()),
+    };
+    downstream.abort();
+    result
+}
+
+/// Read the next packet, returning it parsed and as raw bytes for forwarding
+async fn next_packet<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    buf: &mut BytesMut,
+) -> anyhow::Result<(Packet, Bytes)> {
+    loop {
+        match mqttbytes::check(buf.iter(), MAX_PACKET_SIZE) {
+            Ok(header) => {
+                let raw = buf.split_to(header.frame_length()).freeze();
+                let packet = v4::read(&mut BytesMut::from(&raw[..]), MAX_PACKET_SIZE)?;
+                return Ok((packet, raw));
+            }
+            Err(mqttbytes::Error::InsufficientBytes(_)) => {}
+            Err(e) => return Err(e.into()),
+        }
+        if reader.read_buf(buf).await? == 0 {
+            anyhow::bail!("connection closed");
+        }
+    }
+}
+
+async fn refuse<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    code: ConnectReturnCode,
+) -> anyhow::Result<()> {
+    let mut out = BytesMut::new();
+    ConnAck::new(code, false).write(&mut out)?;
+    writer.write_all(&out).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(publish: &[&str], subscribe: &[&str]) -> BrokerUser {
+        BrokerUser {
+            username: "gateway".to_string(),
+            password: Some("secret".to_string()),
+            publish: publish.iter().map(|s| s.to_string()).collect(),
+            subscribe: subscribe.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_authenticate() {
+        let acl = BrokerAcl::new(&[user(&[], &[])]);
+        assert!(acl.authenticate("gateway", "secret").is_some());
+        assert!(acl.authenticate("gateway", "wrong").is_none());
+        assert!(acl.authenticate("xovis", "secret").is_none());
+    }
+
+    #[test]
+    fn test_publish_and_subscribe_filters() {
+        let sensor = user(&["xovis/#"], &[]);
+        assert!(may_publish(&sensor, "xovis/sensor"));
+        assert!(!may_publish(&sensor, "gateway/journeys"));
+        assert!(!may_subscribe(&sensor, "xovis/#"));
+
+        let gateway = user(&["gateway/#"], &["xovis/#", "gateway/+"]);
+        assert!(may_subscribe(&gateway, "xovis/sensor"));
+        assert!(may_subscribe(&gateway, "xovis/+"));
+        assert!(may_subscribe(&gateway, "gateway/journeys"));
+        // Wider than any allowed filter
+        assert!(!may_subscribe(&gateway, "#"));
+        assert!(!may_subscribe(&gateway, "gateway/#"));
+    }
+
+    #[tokio::test]
+    async fn test_denied_publish_closes_connection() {
+        let upstream = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let upstream_addr = upstream.local_addr().unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_addr = listener.local_addr().unwrap();
+        let acl = Arc::new(BrokerAcl::new(&[user(&["xovis/#"], &[])]));
+        tokio::spawn(serve(listener, upstream_addr, acl, None));
+
+        let mut out = BytesMut::new();
+        let mut connect = v4::Connect::new("sensor");
+        connect.set_login("gateway", "secret");
+        connect.write(&mut out).unwrap();
+        let publish = |topic: &str, out: &mut BytesMut| {
+            v4::Publish::new(topic, rumqttc::QoS::AtMostOnce, "{}").write(out).unwrap();
+        };
+        publish("xovis/sensor", &mut out);
+        publish("gateway/gate", &mut out);
+        publish("xovis/sensor", &mut out);
+
+        let mut client = TcpStream::connect(proxy_addr).await.unwrap();
+        client.write_all(&out).await.unwrap();
+
+        // The broker sees CONNECT and the allowed publish, then the client is cut off
+        let (mut server, _) = upstream.accept().await.unwrap();
+        let mut forwarded = BytesMut::new();
+        let mut received = Vec::new();
+        while let Ok((packet, _)) = next_packet(&mut server, &mut forwarded).await {
+            received.push(packet);
+        }
+        assert_eq!(received.len(), 2);
+        let Packet::Connect(connect) = &received[0] else {
+            panic!("expected CONNECT, got {:?}", received[0]);
+        };
+        assert_eq!(connect.client_id, "sensor");
+        assert_eq!(connect.login.as_ref().unwrap().username, UPSTREAM_USERNAME);
+        assert_ne!(connect.login.as_ref().unwrap().password, "secret");
+        assert!(matches!(&received[1], Packet::Publish(p) if p.topic == "xovis/sensor"));
+    }
+}
@@ -0,0 +1,108 @@
+//! Time source for the tracker and its services
+//!
+//! The tracker, journey manager, stitcher, re-entry detector and door
+//! correlator read the current time through a `Clock` instead of calling
+//! `Instant::now()` and `epoch_ms()` themselves. Production uses
+//! `SystemClock`; tests and replays use `ManualClock`, which only moves when
+//! advanced, so timing windows can be hit exactly and faster than real time.
+
+use crate::domain::journey::epoch_ms;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Source of monotonic and wall-clock time
+pub trait Clock: Send + Sync {
+    /// Monotonic time, for durations and matching windows
+    fn now(&self) -> Instant;
+    /// Wall-clock time in epoch ms, for journey and event timestamps
+    fn epoch_ms(&self) -> u64;
+}
+
+/// Clock shared by the tracker and its services
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The real clock
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl SystemClock {
+    /// A `SharedClock` reading the real time
+    pub fn shared() -> SharedClock {
+        Arc::new(SystemClock)
+    }
+}
+
+impl Clock for SystemClock {
+    #[inline]
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    #[inline]
+    fn epoch_ms(&self) -> u64 {
+        epoch_ms()
+    }
+}
+
+/// Clock that only moves when advanced (tests, replay)
+///
+/// Both readings move together: after `advance(d)`, `now()` is `d` later and
+/// `epoch_ms()` is `d` larger.
+#[derive(Debug)]
+pub struct ManualClock {
+    /// `now()` at construction
+    origin: Instant,
+    /// `epoch_ms()` at construction
+    origin_ms: u64,
+    /// Time advanced since construction (ms)
+    elapsed_ms: AtomicU64,
+}
+
+impl ManualClock {
+    /// Create a clock reading `epoch_ms`
+    pub fn new(epoch_ms: u64) -> Self {
+        Self { origin: Instant::now(), origin_ms: epoch_ms, elapsed_ms: AtomicU64::new(0) }
+    }
+
+    /// Move the clock forward by `by`
+    pub fn advance(&self, by: Duration) {
+        self.elapsed_ms.fetch_add(by.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// Move the clock forward to `epoch_ms`; earlier times are ignored
+    pub fn advance_to(&self, epoch_ms: u64) {
+        self.elapsed_ms.fetch_max(epoch_ms.saturating_sub(self.origin_ms), Ordering::Relaxed);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.origin + Duration::from_millis(self.elapsed_ms.load(Ordering::Relaxed))
+    }
+
+    fn epoch_ms(&self) -> u64 {
+        self.origin_ms + self.elapsed_ms.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock_moves_only_forward() {
+        let clock = ManualClock::new(1_000_000);
+        let start = clock.now();
+
+        clock.advance(Duration::from_millis(1500));
+        assert_eq!(clock.epoch_ms(), 1_001_500);
+        assert_eq!(clock.now() - start, Duration::from_millis(1500));
+
+        clock.advance_to(1_000_200);
+        assert_eq!(clock.epoch_ms(), 1_001_500);
+        clock.advance_to(1_005_000);
+        assert_eq!(clock.epoch_ms(), 1_005_000);
+        assert_eq!(clock.now() - start, Duration::from_secs(5));
+    }
+}
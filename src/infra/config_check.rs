@@ -0,0 +1,246 @@
+//! Semantic config checks
+//!
+//! `Config::from_file` catches structural mistakes (TOML syntax, types,
+//! missing zone roles, unknown geometry IDs) and stops at the first one. The
+//! checks here run on the loaded config and report every semantic mistake with
+//! its TOML path, e.g. a gate zone that is also a POS zone or an ACC terminal
+//! mapped to a POS name no zone has. Run by `gateway check-config`, at startup
+//! and before a config reload is applied.
+
+use crate::domain::types::GeometryId;
+use crate::infra::config::Config;
+use std::collections::HashSet;
+use std::fmt;
+
+/// A config mistake at a TOML path
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigProblem {
+    /// TOML path, e.g. `zones.pos_zones[2]`
+    pub path: String,
+    pub message: String,
+}
+
+impl ConfigProblem {
+    fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { path: path.into(), message: message.into() }
+    }
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Load `path` and run all checks
+///
+/// A structural error is returned as a single problem at the file path,
+/// since loading stops at the first one.
+pub fn check_file(path: &str) -> Result<Config, Vec<ConfigProblem>> {
+    let config =
+        Config::from_file(path).map_err(|e| vec![ConfigProblem::new(path, format!("{e:#}"))])?;
+    let problems = check(&config);
+    if problems.is_empty() {
+        Ok(config)
+    } else {
+        Err(problems)
+    }
+}
+
+/// Semantic checks on a loaded config
+pub fn check(config: &Config) -> Vec<ConfigProblem> {
+    let mut problems = Vec::new();
+    check_zones(config, &mut problems);
+    check_acc(config, &mut problems);
+    check_exit_detection(config, &mut problems);
+    check_stitcher(config, &mut problems);
+    check_groups(config, &mut problems);
+    problems
+}
+
+fn check_zones(config: &Config, problems: &mut Vec<ConfigProblem>) {
+    let gate_zone = config.gate_zone().0;
+    let exit_line = config.exit_line();
+    let pos_zones = config.pos_zones();
+
+    let mut seen = HashSet::new();
+    for (i, &id) in pos_zones.iter().enumerate() {
+        let path = format!("zones.pos_zones[{i}]");
+        if !seen.insert(id) {
+            problems.push(ConfigProblem::new(path, format!("zone {id} is listed twice")));
+        } else if id == gate_zone {
+            problems.push(ConfigProblem::new(
+                path,
+                format!("zone {id} is the gate zone (zones.gate_zone)"),
+            ));
+        }
+    }
+
+    for (i, &id) in config.dwell_zones().iter().enumerate() {
+        let path = format!("zones.dwell_zones[{i}]");
+        if pos_zones.contains(&id) {
+            problems.push(ConfigProblem::new(path, format!("zone {id} is also a POS zone")));
+        } else if id == gate_zone {
+            problems.push(ConfigProblem::new(
+                path,
+                format!("zone {id} is the gate zone (zones.gate_zone)"),
+            ));
+        }
+    }
+
+    if let Some(entry_line) = config.entry_line() {
+        if entry_line == exit_line {
+            problems.push(ConfigProblem::new(
+                "zones.entry_line",
+                format!("line {entry_line} is also the exit line (zones.exit_line)"),
+            ));
+        }
+    }
+    if let Some(approach_line) = config.approach_line() {
+        for (key, line) in [("exit_line", Some(exit_line)), ("entry_line", config.entry_line())] {
+            if line == Some(approach_line) {
+                problems.push(ConfigProblem::new(
+                    "zones.approach_line",
+                    format!("line {approach_line} is also zones.{key}"),
+                ));
+            }
+        }
+    }
+    if let Some(store_zone) = config.store_zone() {
+        if store_zone == gate_zone || pos_zones.contains(&store_zone) {
+            problems.push(ConfigProblem::new(
+                "zones.store_zone",
+                format!("zone {store_zone} is also the gate zone or a POS zone"),
+            ));
+        }
+    }
+}
+
+fn check_acc(config: &Config, problems: &mut Vec<ConfigProblem>) {
+    // ACC events are matched against POS occupancy, keyed by zone name
+    let zone_names: Vec<String> = config
+        .pos_zones()
+        .iter()
+        .chain(config.dwell_zones())
+        .map(|&id| config.zone_name(GeometryId(id)).to_string())
+        .collect();
+
+    let shadow_ip_to_pos = config.shadow().ip_to_pos.iter().map(|m| ("shadow.ip_to_pos", m));
+    for (key, ip_to_pos) in
+        std::iter::once(("acc.ip_to_pos", config.acc_ip_to_pos())).chain(shadow_ip_to_pos)
+    {
+        let mut mappings: Vec<(&String, &String)> = ip_to_pos.iter().collect();
+        mappings.sort();
+        for (ip, pos) in mappings {
+            if !zone_names.contains(pos) {
+                problems.push(ConfigProblem::new(
+                    format!("{key}.\"{ip}\""),
+                    format!(
+                        "\"{pos}\" is not the name of a POS or dwell zone (known: {})",
+                        zone_names.join(", ")
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+fn check_exit_detection(config: &Config, problems: &mut Vec<ConfigProblem>) {
+    let exit = config.exit_detection();
+    if exit.position_threshold_x_min_m >= exit.position_threshold_x_max_m {
+        problems.push(ConfigProblem::new(
+            "exit_detection.position_threshold_x_min_m",
+            format!(
+                "{} must be below position_threshold_x_max_m ({})",
+                exit.position_threshold_x_min_m, exit.position_threshold_x_max_m
+            ),
+        ));
+    }
+}
+
+fn check_stitcher(config: &Config, problems: &mut Vec<ConfigProblem>) {
+    let stitcher = config.stitcher();
+    if stitcher.max_time_pos_zone_ms < stitcher.max_time_ms {
+        problems.push(ConfigProblem::new(
+            "stitcher.max_time_pos_zone_ms",
+            format!(
+                "{} must not be below max_time_ms ({})",
+                stitcher.max_time_pos_zone_ms, stitcher.max_time_ms
+            ),
+        ));
+    }
+    if stitcher.max_distance_same_zone_cm < stitcher.max_distance_cm {
+        problems.push(ConfigProblem::new(
+            "stitcher.max_distance_same_zone_cm",
+            format!(
+                "{} must not be below max_distance_cm ({})",
+                stitcher.max_distance_same_zone_cm, stitcher.max_distance_cm
+            ),
+        ));
+    }
+}
+
+fn check_groups(config: &Config, problems: &mut Vec<ConfigProblem>) {
+    let groups = config.groups();
+    if groups.enabled && groups.leave_radius_m < groups.member_radius_m {
+        problems.push(ConfigProblem::new(
+            "groups.leave_radius_m",
+            format!(
+                "{} must not be below member_radius_m ({})",
+                groups.leave_radius_m, groups.member_radius_m
+            ),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::config::{ShadowConfig, StitcherConfig};
+    use std::collections::HashMap;
+
+    fn paths(problems: &[ConfigProblem]) -> Vec<&str> {
+        problems.iter().map(|p| p.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_default_config_has_no_problems() {
+        assert!(check(&Config::default()).is_empty());
+    }
+
+    #[test]
+    fn test_acc_mapping_to_unknown_pos() {
+        let ip_to_pos = HashMap::from([
+            ("10.0.0.1".to_string(), "POS_1".to_string()),
+            ("10.0.0.2".to_string(), "POS_9".to_string()),
+        ]);
+        let problems = check(&Config::default().with_acc_ip_to_pos(ip_to_pos));
+        assert_eq!(paths(&problems), vec!["acc.ip_to_pos.\"10.0.0.2\""]);
+        assert!(problems[0].message.contains("POS_9"));
+    }
+
+    #[test]
+    fn test_shadow_mapping_to_unknown_pos() {
+        let ip_to_pos = HashMap::from([("10.0.0.1".to_string(), "POS_9".to_string())]);
+        let shadow = ShadowConfig { ip_to_pos: Some(ip_to_pos), ..ShadowConfig::default() };
+        let problems = check(&Config::default().with_shadow(shadow));
+        assert_eq!(paths(&problems), vec!["shadow.ip_to_pos.\"10.0.0.1\""]);
+    }
+
+    #[test]
+    fn test_stitcher_pos_zone_limits_extend_base() {
+        let stitcher = StitcherConfig { max_time_ms: 9000, ..StitcherConfig::default() };
+        let problems = check(&Config::default().with_stitcher(stitcher));
+        assert_eq!(paths(&problems), vec!["stitcher.max_time_pos_zone_ms"]);
+    }
+
+    #[test]
+    fn test_shared_lines_are_reported() {
+        let problems = check(&Config::default().with_approach_line(1006));
+        assert_eq!(paths(&problems), vec!["zones.approach_line"]);
+        assert_eq!(
+            problems[0].to_string(),
+            "zones.approach_line: line 1006 is also zones.exit_line"
+        );
+    }
+}
@@ -0,0 +1,276 @@
+//! Xovis geometry exports
+//!
+//! Loads the scene geometry JSON written by `scripts/fetch-geometries.sh`
+//! (`config/geometries/<site>.json`). Each geometry is a ZONE (polygon) or a
+//! LINE (polyline) with the same ID the sensor reports in `geometry_id`.
+
+use anyhow::Context;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// Geometry shape as reported by the sensor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum GeometryKind {
+    /// Closed polygon (zone entry/exit events)
+    Zone,
+    /// Polyline (line cross events)
+    Line,
+}
+
+/// A single geometry from the export
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Geometry {
+    pub id: i32,
+    #[serde(rename = "type")]
+    pub kind: GeometryKind,
+    pub name: String,
+    /// Vertices in sensor coordinates (meters)
+    #[serde(rename = "geometry")]
+    pub points: Vec<[f64; 2]>,
+}
+
+impl Geometry {
+    /// Whether (x, y) lies inside the polygon (even-odd rule)
+    ///
+    /// Only meaningful for zones; lines and degenerate polygons never contain a point.
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        if self.kind != GeometryKind::Zone || self.points.len() < 3 {
+            return false;
+        }
+        let mut inside = false;
+        let mut j = self.points.len() - 1;
+        for (i, &[xi, yi]) in self.points.iter().enumerate() {
+            let [xj, yj] = self.points[j];
+            if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+                inside = !inside;
+            }
+            j = i;
+        }
+        inside
+    }
+
+    /// Shortest distance from (x, y) to the outline, in meters
+    pub fn distance_to_edge(&self, x: f64, y: f64) -> f64 {
+        let n = self.points.len();
+        let segments = match self.kind {
+            GeometryKind::Zone => n,
+            GeometryKind::Line => n.saturating_sub(1),
+        };
+        (0..segments)
+            .map(|i| segment_distance([x, y], self.points[i], self.points[(i + 1) % n]))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// Whether the movement `from` -> `to` crosses the outline
+    pub fn crosses(&self, from: [f64; 2], to: [f64; 2]) -> bool {
+        let n = self.points.len();
+        let segments = match self.kind {
+            GeometryKind::Zone => n,
+            GeometryKind::Line => n.saturating_sub(1),
+        };
+        (0..segments)
+            .any(|i| segments_intersect(from, to, self.points[i], self.points[(i + 1) % n]))
+    }
+}
+
+/// Whether segments `p1`-`p2` and `q1`-`q2` intersect (touching counts)
+fn segments_intersect(p1: [f64; 2], p2: [f64; 2], q1: [f64; 2], q2: [f64; 2]) -> bool {
+    let orient = |a: [f64; 2], b: [f64; 2], c: [f64; 2]| {
+        (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+    };
+    let (d1, d2) = (orient(q1, q2, p1), orient(q1, q2, p2));
+    let (d3, d4) = (orient(p1, p2, q1), orient(p1, p2, q2));
+    // Collinear overlaps are ignored: a track walking along the line does not cross it
+    d1 * d2 <= 0.0 && d3 * d4 <= 0.0 && !(d1 == 0.0 && d2 == 0.0)
+}
+
+/// Distance from point `p` to the segment `a`-`b`
+fn segment_distance(p: [f64; 2], a: [f64; 2], b: [f64; 2]) -> f64 {
+    let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+    let len_sq = dx * dx + dy * dy;
+    let t = if len_sq > 0.0 {
+        (((p[0] - a[0]) * dx + (p[1] - a[1]) * dy) / len_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (cx, cy) = (a[0] + t * dx, a[1] + t * dy);
+    ((p[0] - cx).powi(2) + (p[1] - cy).powi(2)).sqrt()
+}
+
+/// Geometry export file contents
+#[derive(Debug, Clone, Deserialize)]
+pub struct GeometryExport {
+    #[serde(default)]
+    pub site: Option<String>,
+    pub geometries: Vec<Geometry>,
+}
+
+impl GeometryExport {
+    /// Load a geometry export from a JSON file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read geometry file {}", path.display()))?;
+        Self::from_json(&content)
+            .with_context(|| format!("Failed to parse geometry file {}", path.display()))
+    }
+
+    /// Parse a geometry export from JSON text
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let export: GeometryExport = serde_json::from_str(json)?;
+        let mut seen = std::collections::HashSet::new();
+        for geometry in &export.geometries {
+            if !seen.insert(geometry.id) {
+                anyhow::bail!("duplicate geometry id {}", geometry.id);
+            }
+        }
+        Ok(export)
+    }
+
+    /// Find a geometry by ID
+    pub fn get(&self, id: i32) -> Option<&Geometry> {
+        self.geometries.iter().find(|g| g.id == id)
+    }
+
+    /// IDs of zones whose name starts with `prefix`, in export order
+    pub fn zones_with_prefix(&self, prefix: &str) -> Vec<i32> {
+        self.with_prefix(GeometryKind::Zone, prefix).map(|g| g.id).collect()
+    }
+
+    /// ID of the single geometry of `kind` whose name starts with `prefix`
+    ///
+    /// Returns `Ok(None)` when there is no match and an error when several
+    /// geometries match (the role is ambiguous and must be set in the TOML).
+    pub fn unique_with_prefix(
+        &self,
+        kind: GeometryKind,
+        prefix: &str,
+    ) -> anyhow::Result<Option<i32>> {
+        let mut matches = self.with_prefix(kind, prefix);
+        let Some(first) = matches.next() else {
+            return Ok(None);
+        };
+        if let Some(second) = matches.next() {
+            anyhow::bail!(
+                "geometries {} ({}) and {} ({}) both match {}*, set the role explicitly in [zones]",
+                first.id,
+                first.name,
+                second.id,
+                second.name,
+                prefix
+            );
+        }
+        Ok(Some(first.id))
+    }
+
+    fn with_prefix<'a>(
+        &'a self,
+        kind: GeometryKind,
+        prefix: &'a str,
+    ) -> impl Iterator<Item = &'a Geometry> + 'a {
+        self.geometries.iter().filter(move |g| g.kind == kind && g.name.starts_with(prefix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"{
+        "site": "test",
+        "geometries": [
+            {"id": 1000, "layer_id": 1, "type": "ZONE", "name": "GATE_1",
+             "geometry": [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0]]},
+            {"id": 1001, "layer_id": 1, "type": "ZONE", "name": "POS_1",
+             "geometry": [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0]]},
+            {"id": 1002, "layer_id": 1, "type": "ZONE", "name": "POS_2",
+             "geometry": [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0]]},
+            {"id": 1006, "layer_id": 1, "type": "LINE", "name": "EXIT_1",
+             "geometry": [[0.0, 2.0], [1.0, 2.0]]}
+        ]
+    }"#;
+
+    #[test]
+    fn test_parse_export() {
+        let export = GeometryExport::from_json(EXPORT).unwrap();
+        assert_eq!(export.site.as_deref(), Some("test"));
+        assert_eq!(export.geometries.len(), 4);
+
+        let exit = export.get(1006).unwrap();
+        assert_eq!(exit.kind, GeometryKind::Line);
+        assert_eq!(exit.name, "EXIT_1");
+        assert_eq!(exit.points, vec![[0.0, 2.0], [1.0, 2.0]]);
+        assert!(export.get(9999).is_none());
+    }
+
+    #[test]
+    fn test_role_lookup_by_prefix() {
+        let export = GeometryExport::from_json(EXPORT).unwrap();
+        assert_eq!(export.zones_with_prefix("POS_"), vec![1001, 1002]);
+        assert_eq!(export.unique_with_prefix(GeometryKind::Zone, "GATE").unwrap(), Some(1000));
+        assert_eq!(export.unique_with_prefix(GeometryKind::Line, "EXIT").unwrap(), Some(1006));
+        // Kind must match: EXIT_1 is a line, not a zone
+        assert_eq!(export.unique_with_prefix(GeometryKind::Zone, "EXIT").unwrap(), None);
+        // Ambiguous role
+        assert!(export.unique_with_prefix(GeometryKind::Zone, "POS_").is_err());
+    }
+
+    #[test]
+    fn test_polygon_contains_and_edge_distance() {
+        let square = Geometry {
+            id: 1,
+            kind: GeometryKind::Zone,
+            name: "SQUARE".to_string(),
+            points: vec![[0.0, 0.0], [2.0, 0.0], [2.0, 2.0], [0.0, 2.0]],
+        };
+        assert!(square.contains(1.0, 1.0));
+        assert!(!square.contains(3.0, 1.0));
+        assert!(!square.contains(1.0, -0.1));
+        assert!((square.distance_to_edge(1.0, 1.0) - 1.0).abs() < 1e-9);
+        assert!((square.distance_to_edge(1.0, 0.2) - 0.2).abs() < 1e-9);
+        assert!((square.distance_to_edge(3.0, 1.0) - 1.0).abs() < 1e-9);
+
+        // A line with the same points is not a region
+        let line = Geometry { kind: GeometryKind::Line, ..square };
+        assert!(!line.contains(1.0, 1.0));
+        // ... and has no closing segment from (0, 2) back to (0, 0)
+        assert!((line.distance_to_edge(0.1, 1.0) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_line_crossing() {
+        let line = Geometry {
+            id: 1,
+            kind: GeometryKind::Line,
+            name: "TRIGGER".to_string(),
+            points: vec![[1.0, -0.5], [3.0, -0.5]],
+        };
+        assert!(line.crosses([2.0, -0.8], [2.0, -0.3]));
+        assert!(line.crosses([2.0, -0.3], [2.0, -0.8]));
+        // Same side, or passing beyond the end of the line
+        assert!(!line.crosses([2.0, -0.3], [2.0, 0.5]));
+        assert!(!line.crosses([3.5, -0.8], [3.5, -0.3]));
+    }
+
+    #[test]
+    fn test_duplicate_id_rejected() {
+        let json = r#"{"geometries": [
+            {"id": 1, "type": "ZONE", "name": "A", "geometry": []},
+            {"id": 1, "type": "ZONE", "name": "B", "geometry": []}
+        ]}"#;
+        assert!(GeometryExport::from_json(json).is_err());
+    }
+
+    #[test]
+    fn test_netto_export_parses() {
+        let export = GeometryExport::from_file(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("config/geometries/netto.json"),
+        )
+        .unwrap();
+        assert_eq!(export.site.as_deref(), Some("netto"));
+        assert_eq!(export.get(1000).unwrap().name, "GATE_1");
+        assert_eq!(export.get(1009).unwrap().name, "STORE");
+    }
+}
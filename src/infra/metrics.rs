@@ -8,7 +8,7 @@
 //! counters only. Do NOT use these atomics for coordination or logic decisions.
 
 use rustc_hash::FxHashMap;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tracing::info;
 
@@ -23,6 +23,9 @@ fn epoch_ms() -> u64 {
 const BUCKET_BOUNDS: [u64; 10] = [100, 200, 400, 800, 1600, 3200, 6400, 12800, 25600, 51200];
 const NUM_BUCKETS: usize = 11;
 
+/// Milliseconds per UTC day (shadow policy day counters)
+const MS_PER_DAY: u64 = 86_400_000;
+
 /// Stitch distance bucket boundaries (centimeters)
 /// Buckets: ≤10, ≤20, ≤40, ≤80, ≤160, ≤320, ≤640, ≤1280, ≤2560, ≤5120, >5120 cm
 const STITCH_DIST_BOUNDS: [u64; 10] = [10, 20, 40, 80, 160, 320, 640, 1280, 2560, 5120];
@@ -75,11 +78,7 @@ const BUCKET_UPPER_BOUNDS: [u64; NUM_BUCKETS] =
 /// Compute average, returning 0 if count is zero
 #[inline]
 fn avg_or_zero(sum: u64, count: u64) -> u64 {
-    if count > 0 {
-        sum / count
-    } else {
-        0
-    }
+    sum.checked_div(count).unwrap_or(0)
 }
 
 /// Compute ratio, returning 0.0 if denominator is zero
@@ -120,6 +119,22 @@ pub const GATE_STATE_OPEN: u64 = 2;
 /// Maximum number of POS zones to track
 pub const MAX_POS_ZONES: usize = 10;
 
+/// Maximum number of sensors with liveness gauges
+pub const MAX_SENSORS: usize = 16;
+
+/// Per-sensor liveness snapshot for Prometheus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SensorLiveness {
+    pub sensor_id: u8,
+    /// Time since the last frame (ms)
+    pub frame_age_ms: u64,
+    /// Time since the last frame carrying events (ms)
+    pub event_age_ms: u64,
+    pub online: bool,
+    /// Estimated sensor clock offset: receive time minus event time (ms)
+    pub clock_skew_ms: i64,
+}
+
 /// Lock-free metrics collector
 ///
 /// All recording operations are lock-free using atomics.
@@ -145,8 +160,19 @@ pub struct Metrics {
     gate_latency_max_us: AtomicU64,
     /// Gate commands since last report (reset on report)
     gate_commands_since_report: AtomicU64,
+    /// Virtual pre-gate triggers fired (monotonic)
+    gate_triggers_total: AtomicU64,
+    /// Gate commands started by a virtual trigger (monotonic)
+    gate_trigger_opens_total: AtomicU64,
+    /// Lead time of virtual trigger over the GATE zone entry (ms)
+    /// Same bounds as stitch time: ≤100, ≤200, ... ≤51200, >51200 ms
+    gate_trigger_lead_buckets: [AtomicU64; NUM_BUCKETS],
+    /// Sum of trigger lead times (ms) for average calculation
+    gate_trigger_lead_sum: AtomicU64,
     /// Current gate state (0=closed, 1=moving, 2=open)
     gate_state: AtomicU64,
+    /// 1 while gate commands are logged instead of sent (dry run)
+    gate_dry_run: AtomicU64,
     /// Timestamp (epoch_ms) when gate became open (0 if not open)
     gate_open_since_ms: AtomicU64,
     /// Counter of gate openings that exceeded 30 seconds
@@ -171,6 +197,53 @@ pub struct Metrics {
     stitch_matched_total: AtomicU64,
     /// Tracks truly lost (expired without stitch) (monotonic)
     stitch_expired_total: AtomicU64,
+    /// Tracks handed over to another sensor's live track (monotonic)
+    stitch_handover_total: AtomicU64,
+    /// Zone entry/exit events synthesized from positions (monotonic)
+    zone_events_derived_total: AtomicU64,
+    /// Sensor zone events dropped as duplicates of derived ones (monotonic)
+    zone_events_deduped_total: AtomicU64,
+    /// Sensors going offline (monotonic)
+    sensor_offline_total: AtomicU64,
+    /// Gate opens suppressed by the keep_locked degraded policy (monotonic)
+    gate_opens_suppressed_total: AtomicU64,
+    /// Gate open pulses sent by the free_exit degraded policy (monotonic)
+    free_exit_pulses_total: AtomicU64,
+    /// Sensor lag histogram: receive wall clock minus Xovis event time (ms)
+    /// Same bounds as stitch time: ≤100, ≤200, ... ≤51200, >51200 ms
+    sensor_lag_buckets: [AtomicU64; NUM_BUCKETS],
+    /// Sum of sensor lags (ms) for average calculation
+    sensor_lag_sum: AtomicU64,
+    /// Clock skew alerts raised (monotonic)
+    clock_skew_alerts_total: AtomicU64,
+    /// Frames released out of arrival order by the reorder window (monotonic)
+    frames_reordered_total: AtomicU64,
+    /// Exact duplicate frames dropped by the reorder window (monotonic)
+    frames_duplicate_total: AtomicU64,
+    /// Frames dropped for arriving after newer frames were released (monotonic)
+    frames_too_late_total: AtomicU64,
+    /// Tracks authorized through the payer's Xovis group (monotonic)
+    acc_group_extensions_total: AtomicU64,
+    /// Events dropped for a filtered object type (monotonic)
+    object_events_filtered_total: AtomicU64,
+    /// Tracks inheriting authorization from an adjacent track (monotonic)
+    auth_inherited_total: AtomicU64,
+    /// UTC day (days since epoch) the shadow counters belong to
+    shadow_day: AtomicU64,
+    /// Shadow decisions agreeing with the live policy today
+    shadow_agree_today: AtomicU64,
+    /// Shadow would have opened where the live policy blocked, today
+    shadow_would_open_today: AtomicU64,
+    /// Shadow would have blocked where the live policy opened, today
+    shadow_would_block_today: AtomicU64,
+    /// MQTT ingress reconnects (monotonic)
+    mqtt_reconnects_total: AtomicU64,
+    /// Time the MQTT ingress connection was down (ms, monotonic)
+    mqtt_disconnected_ms_total: AtomicU64,
+    /// Sensor time lost across reconnects by frame time (ms, monotonic)
+    ingress_gap_ms_total: AtomicU64,
+    /// Events estimated to be lost across reconnects (monotonic)
+    ingress_events_missed_total: AtomicU64,
     /// Stitch distance histogram buckets (centimeters)
     /// Bounds: ≤10, ≤20, ≤40, ≤80, ≤160, ≤320, ≤640, ≤1280, ≤2560, ≤5120, >5120 cm
     stitch_distance_buckets: [AtomicU64; NUM_BUCKETS],
@@ -187,6 +260,8 @@ pub struct Metrics {
     acc_no_journey_total: AtomicU64,
     /// MQTT events dropped due to channel full (monotonic)
     mqtt_events_dropped: AtomicU64,
+    /// HTTP ingress bodies rejected due to channel full (monotonic)
+    http_ingress_rejected_bodies: AtomicU64,
     /// ACC events dropped due to channel full (monotonic)
     acc_events_dropped: AtomicU64,
     /// Gate commands dropped due to channel full (monotonic)
@@ -246,6 +321,16 @@ pub struct Metrics {
     pos_zone_ids: parking_lot::Mutex<Vec<i32>>,
     /// Pre-computed zone ID to index mapping (for O(1) lookup without mutex)
     zone_id_to_index: parking_lot::RwLock<FxHashMap<i32, usize>>,
+    /// Sensor IDs for the liveness gauges, in config order (set once at init)
+    sensor_ids: parking_lot::Mutex<Vec<u8>>,
+    /// Time since each sensor's last frame (ms, updated by the watchdog)
+    sensor_frame_age_ms: [AtomicU64; MAX_SENSORS],
+    /// Time since each sensor's last event (ms, updated by the watchdog)
+    sensor_event_age_ms: [AtomicU64; MAX_SENSORS],
+    /// 1 if the sensor is online, 0 if offline
+    sensor_online: [AtomicU64; MAX_SENSORS],
+    /// Estimated clock skew per sensor (ms, updated by the watchdog)
+    sensor_clock_skew_ms: [AtomicI64; MAX_SENSORS],
     /// Last report time (only accessed from reporter, not atomic)
     last_report_time: parking_lot::Mutex<Instant>,
 }
@@ -263,7 +348,12 @@ impl Metrics {
             gate_latency_sum_us: AtomicU64::new(0),
             gate_latency_max_us: AtomicU64::new(0),
             gate_commands_since_report: AtomicU64::new(0),
+            gate_triggers_total: AtomicU64::new(0),
+            gate_trigger_opens_total: AtomicU64::new(0),
+            gate_trigger_lead_buckets: std::array::from_fn(|_| AtomicU64::new(0)),
+            gate_trigger_lead_sum: AtomicU64::new(0),
             gate_state: AtomicU64::new(GATE_STATE_CLOSED),
+            gate_dry_run: AtomicU64::new(0),
             gate_open_since_ms: AtomicU64::new(0),
             gate_long_opens_total: AtomicU64::new(0),
             gate_very_long_opens_total: AtomicU64::new(0),
@@ -276,6 +366,29 @@ impl Metrics {
             acc_matched_total: AtomicU64::new(0),
             stitch_matched_total: AtomicU64::new(0),
             stitch_expired_total: AtomicU64::new(0),
+            stitch_handover_total: AtomicU64::new(0),
+            zone_events_derived_total: AtomicU64::new(0),
+            zone_events_deduped_total: AtomicU64::new(0),
+            sensor_offline_total: AtomicU64::new(0),
+            gate_opens_suppressed_total: AtomicU64::new(0),
+            free_exit_pulses_total: AtomicU64::new(0),
+            sensor_lag_buckets: std::array::from_fn(|_| AtomicU64::new(0)),
+            sensor_lag_sum: AtomicU64::new(0),
+            clock_skew_alerts_total: AtomicU64::new(0),
+            frames_reordered_total: AtomicU64::new(0),
+            frames_duplicate_total: AtomicU64::new(0),
+            frames_too_late_total: AtomicU64::new(0),
+            acc_group_extensions_total: AtomicU64::new(0),
+            object_events_filtered_total: AtomicU64::new(0),
+            auth_inherited_total: AtomicU64::new(0),
+            shadow_day: AtomicU64::new(0),
+            shadow_agree_today: AtomicU64::new(0),
+            shadow_would_open_today: AtomicU64::new(0),
+            shadow_would_block_today: AtomicU64::new(0),
+            mqtt_reconnects_total: AtomicU64::new(0),
+            mqtt_disconnected_ms_total: AtomicU64::new(0),
+            ingress_gap_ms_total: AtomicU64::new(0),
+            ingress_events_missed_total: AtomicU64::new(0),
             stitch_distance_buckets: std::array::from_fn(|_| AtomicU64::new(0)),
             stitch_distance_sum: AtomicU64::new(0),
             stitch_time_buckets: std::array::from_fn(|_| AtomicU64::new(0)),
@@ -283,6 +396,7 @@ impl Metrics {
             acc_late_total: AtomicU64::new(0),
             acc_no_journey_total: AtomicU64::new(0),
             mqtt_events_dropped: AtomicU64::new(0),
+            http_ingress_rejected_bodies: AtomicU64::new(0),
             acc_events_dropped: AtomicU64::new(0),
             gate_cmds_dropped: AtomicU64::new(0),
             mqtt_events_received: AtomicU64::new(0),
@@ -311,10 +425,61 @@ impl Metrics {
             pos_occupancy: std::array::from_fn(|_| AtomicU64::new(0)),
             pos_zone_ids: parking_lot::Mutex::new(Vec::new()),
             zone_id_to_index: parking_lot::RwLock::new(FxHashMap::default()),
+            sensor_ids: parking_lot::Mutex::new(Vec::new()),
+            sensor_frame_age_ms: std::array::from_fn(|_| AtomicU64::new(0)),
+            sensor_event_age_ms: std::array::from_fn(|_| AtomicU64::new(0)),
+            sensor_online: std::array::from_fn(|_| AtomicU64::new(1)),
+            sensor_clock_skew_ms: std::array::from_fn(|_| AtomicI64::new(0)),
             last_report_time: parking_lot::Mutex::new(Instant::now()),
         }
     }
 
+    /// Set the sensor IDs for liveness gauges (call once at initialization)
+    pub fn set_sensors(&self, sensor_ids: &[u8]) {
+        let mut ids = self.sensor_ids.lock();
+        ids.clear();
+        ids.extend(sensor_ids.iter().take(MAX_SENSORS));
+    }
+
+    /// Update the liveness gauges of the sensor at `index` (config order)
+    #[inline]
+    pub fn set_sensor_liveness(
+        &self,
+        index: usize,
+        frame_age_ms: u64,
+        event_age_ms: u64,
+        online: bool,
+    ) {
+        if index < MAX_SENSORS {
+            self.sensor_frame_age_ms[index].store(frame_age_ms, Ordering::Relaxed);
+            self.sensor_event_age_ms[index].store(event_age_ms, Ordering::Relaxed);
+            self.sensor_online[index].store(online as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// Update the clock skew estimate of the sensor at `index` (config order)
+    #[inline]
+    pub fn set_sensor_clock_skew(&self, index: usize, skew_ms: i64) {
+        if index < MAX_SENSORS {
+            self.sensor_clock_skew_ms[index].store(skew_ms, Ordering::Relaxed);
+        }
+    }
+
+    /// Get liveness of all sensors
+    pub fn sensor_liveness(&self) -> Vec<SensorLiveness> {
+        let ids = self.sensor_ids.lock();
+        ids.iter()
+            .enumerate()
+            .map(|(idx, &sensor_id)| SensorLiveness {
+                sensor_id,
+                frame_age_ms: self.sensor_frame_age_ms[idx].load(Ordering::Relaxed),
+                event_age_ms: self.sensor_event_age_ms[idx].load(Ordering::Relaxed),
+                online: self.sensor_online[idx].load(Ordering::Relaxed) != 0,
+                clock_skew_ms: self.sensor_clock_skew_ms[idx].load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+
     /// Set the POS zone IDs (call once at initialization)
     pub fn set_pos_zones(&self, zone_ids: &[i32]) {
         // Update the zone list (for reporting)
@@ -392,6 +557,25 @@ impl Metrics {
         self.gate_commands_sent.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Record a virtual pre-gate trigger (lock-free)
+    ///
+    /// `opened` is true when the trigger started the gate command.
+    #[inline]
+    pub fn record_gate_trigger(&self, opened: bool) {
+        self.gate_triggers_total.fetch_add(1, Ordering::Relaxed);
+        if opened {
+            self.gate_trigger_opens_total.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Record how long a virtual trigger preceded the GATE zone entry (lock-free)
+    #[inline]
+    pub fn record_gate_trigger_lead(&self, lead_ms: u64) {
+        let bucket = bucket_index(lead_ms);
+        self.gate_trigger_lead_buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.gate_trigger_lead_sum.fetch_add(lead_ms, Ordering::Relaxed);
+    }
+
     /// Record gate command end-to-end latency (lock-free)
     ///
     /// This tracks the time from event received to gate command queued.
@@ -500,6 +684,12 @@ impl Metrics {
         self.gate_state.load(Ordering::Relaxed)
     }
 
+    /// Set whether gate commands are logged instead of sent
+    #[inline]
+    pub fn set_gate_dry_run(&self, dry_run: bool) {
+        self.gate_dry_run.store(dry_run as u64, Ordering::Relaxed);
+    }
+
     /// Record an exit through the gate (lock-free)
     #[inline]
     pub fn record_exit(&self) {
@@ -560,6 +750,129 @@ impl Metrics {
         self.stitch_expired_total.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Record a track handed over to another sensor's live track (lock-free)
+    #[inline]
+    pub fn record_stitch_handover(&self) {
+        self.stitch_handover_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a zone event synthesized by the position zone engine (lock-free)
+    #[inline]
+    pub fn record_zone_event_derived(&self) {
+        self.zone_events_derived_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a sensor zone event dropped as a duplicate of a derived one (lock-free)
+    #[inline]
+    pub fn record_zone_event_deduped(&self) {
+        self.zone_events_deduped_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a sensor going offline (lock-free)
+    #[inline]
+    pub fn record_sensor_offline(&self) {
+        self.sensor_offline_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a gate open suppressed while degraded (lock-free)
+    #[inline]
+    pub fn record_gate_open_suppressed(&self) {
+        self.gate_opens_suppressed_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a free-exit gate open pulse while degraded (lock-free)
+    #[inline]
+    pub fn record_free_exit_pulse(&self) {
+        self.free_exit_pulses_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record sensor lag: receive wall clock minus event time in ms (lock-free)
+    #[inline]
+    pub fn record_sensor_lag(&self, lag_ms: u64) {
+        let bucket = bucket_index(lag_ms);
+        self.sensor_lag_buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sensor_lag_sum.fetch_add(lag_ms, Ordering::Relaxed);
+    }
+
+    /// Record a clock skew alert (lock-free)
+    #[inline]
+    pub fn record_clock_skew_alert(&self) {
+        self.clock_skew_alerts_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a frame reordered by the reorder window (lock-free)
+    #[inline]
+    pub fn record_frame_reordered(&self) {
+        self.frames_reordered_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a duplicate frame dropped by the reorder window (lock-free)
+    #[inline]
+    pub fn record_frame_duplicate(&self) {
+        self.frames_duplicate_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a frame dropped for arriving too late (lock-free)
+    #[inline]
+    pub fn record_frame_too_late(&self) {
+        self.frames_too_late_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a track authorized through the payer's group (lock-free)
+    #[inline]
+    pub fn record_acc_group_extension(&self) {
+        self.acc_group_extensions_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record an event dropped for a filtered object type (lock-free)
+    #[inline]
+    pub fn record_object_event_filtered(&self) {
+        self.object_events_filtered_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a track inheriting authorization (lock-free)
+    #[inline]
+    pub fn record_auth_inherited(&self) {
+        self.auth_inherited_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Reset the shadow day counters when `now_ms` falls on a new UTC day
+    #[inline]
+    pub fn roll_shadow_day(&self, now_ms: u64) {
+        let day = now_ms / MS_PER_DAY;
+        if self.shadow_day.swap(day, Ordering::Relaxed) != day {
+            self.shadow_agree_today.store(0, Ordering::Relaxed);
+            self.shadow_would_open_today.store(0, Ordering::Relaxed);
+            self.shadow_would_block_today.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Record a shadow policy decision against the live one (lock-free)
+    #[inline]
+    pub fn record_shadow_decision(&self, now_ms: u64, live_open: bool, shadow_open: bool) {
+        self.roll_shadow_day(now_ms);
+        let counter = match (live_open, shadow_open) {
+            (false, true) => &self.shadow_would_open_today,
+            (true, false) => &self.shadow_would_block_today,
+            _ => &self.shadow_agree_today,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record an MQTT ingress reconnect after `down_ms` disconnected (lock-free)
+    #[inline]
+    pub fn record_mqtt_reconnect(&self, down_ms: u64) {
+        self.mqtt_reconnects_total.fetch_add(1, Ordering::Relaxed);
+        self.mqtt_disconnected_ms_total.fetch_add(down_ms, Ordering::Relaxed);
+    }
+
+    /// Record sensor time lost across a reconnect and the events estimated in it (lock-free)
+    #[inline]
+    pub fn record_ingress_gap(&self, gap_ms: u64, events_missed: u64) {
+        self.ingress_gap_ms_total.fetch_add(gap_ms, Ordering::Relaxed);
+        self.ingress_events_missed_total.fetch_add(events_missed, Ordering::Relaxed);
+    }
+
     /// Record stitch distance in centimeters (lock-free)
     #[inline]
     pub fn record_stitch_distance(&self, dist_cm: u64) {
@@ -595,6 +908,12 @@ impl Metrics {
         self.mqtt_events_dropped.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Record an HTTP ingress body rejected due to channel full (lock-free)
+    #[inline]
+    pub fn record_http_ingress_rejected_body(&self) {
+        self.http_ingress_rejected_bodies.fetch_add(1, Ordering::Relaxed);
+    }
+
     /// Record an ACC event dropped due to channel full (lock-free)
     #[inline]
     pub fn record_acc_event_dropped(&self) {
@@ -608,6 +927,13 @@ impl Metrics {
         self.mqtt_events_dropped.load(Ordering::Relaxed)
     }
 
+    /// Get HTTP ingress bodies rejected total
+    #[inline]
+    #[allow(dead_code)]
+    pub fn http_ingress_rejected_bodies(&self) -> u64 {
+        self.http_ingress_rejected_bodies.load(Ordering::Relaxed)
+    }
+
     /// Get ACC events dropped total
     #[inline]
     #[allow(dead_code)]
@@ -858,6 +1184,14 @@ impl Metrics {
         // Get monotonic counters (don't reset)
         let events_total = self.events_total.load(Ordering::Relaxed);
         let gate_commands = self.gate_commands_sent.load(Ordering::Relaxed);
+        let gate_triggers_total = self.gate_triggers_total.load(Ordering::Relaxed);
+        let gate_trigger_opens_total = self.gate_trigger_opens_total.load(Ordering::Relaxed);
+        let gate_trigger_lead_buckets = load_buckets(&self.gate_trigger_lead_buckets);
+        let gate_trigger_lead_count: u64 = gate_trigger_lead_buckets.iter().sum();
+        let gate_trigger_lead_avg_ms = avg_or_zero(
+            self.gate_trigger_lead_sum.load(Ordering::Relaxed),
+            gate_trigger_lead_count,
+        );
 
         // Calculate elapsed time and reset
         let elapsed = {
@@ -884,6 +1218,7 @@ impl Metrics {
 
         // Get current gate state and exits (don't reset)
         let gate_state = self.gate_state.load(Ordering::Relaxed);
+        let gate_dry_run = self.gate_dry_run.load(Ordering::Relaxed) != 0;
         let exits_total = self.exits_total.load(Ordering::Relaxed);
         let journeys_position_exit_total = self.journeys_position_exit.load(Ordering::Relaxed);
         let journeys_pass_through_total = self.journeys_pass_through.load(Ordering::Relaxed);
@@ -893,11 +1228,44 @@ impl Metrics {
         let acc_matched_total = self.acc_matched_total.load(Ordering::Relaxed);
         let stitch_matched_total = self.stitch_matched_total.load(Ordering::Relaxed);
         let stitch_expired_total = self.stitch_expired_total.load(Ordering::Relaxed);
+        let stitch_handover_total = self.stitch_handover_total.load(Ordering::Relaxed);
+        let zone_events_derived_total = self.zone_events_derived_total.load(Ordering::Relaxed);
+        let zone_events_deduped_total = self.zone_events_deduped_total.load(Ordering::Relaxed);
+        let sensor_offline_total = self.sensor_offline_total.load(Ordering::Relaxed);
+        let gate_opens_suppressed_total = self.gate_opens_suppressed_total.load(Ordering::Relaxed);
+        let free_exit_pulses_total = self.free_exit_pulses_total.load(Ordering::Relaxed);
+        let sensor_lag_buckets = load_buckets(&self.sensor_lag_buckets);
+        let sensor_lag_count: u64 = sensor_lag_buckets.iter().sum();
+        let sensor_lag_avg_ms =
+            avg_or_zero(self.sensor_lag_sum.load(Ordering::Relaxed), sensor_lag_count);
+        let clock_skew_alerts_total = self.clock_skew_alerts_total.load(Ordering::Relaxed);
+        let frames_reordered_total = self.frames_reordered_total.load(Ordering::Relaxed);
+        let frames_duplicate_total = self.frames_duplicate_total.load(Ordering::Relaxed);
+        let frames_too_late_total = self.frames_too_late_total.load(Ordering::Relaxed);
+        let acc_group_extensions_total = self.acc_group_extensions_total.load(Ordering::Relaxed);
+        let object_events_filtered_total =
+            self.object_events_filtered_total.load(Ordering::Relaxed);
+        let auth_inherited_total = self.auth_inherited_total.load(Ordering::Relaxed);
+        let shadow_agree_today = self.shadow_agree_today.load(Ordering::Relaxed);
+        let shadow_would_open_today = self.shadow_would_open_today.load(Ordering::Relaxed);
+        let shadow_would_block_today = self.shadow_would_block_today.load(Ordering::Relaxed);
+        let mqtt_reconnects_total = self.mqtt_reconnects_total.load(Ordering::Relaxed);
+        let mqtt_disconnected_ms_total = self.mqtt_disconnected_ms_total.load(Ordering::Relaxed);
+        let ingress_gap_ms_total = self.ingress_gap_ms_total.load(Ordering::Relaxed);
+        let ingress_events_missed_total = self.ingress_events_missed_total.load(Ordering::Relaxed);
+        // Worst sensor: largest skew magnitude
+        let clock_skew_ms = self.sensor_clock_skew_ms[..self.sensor_ids.lock().len()]
+            .iter()
+            .map(|skew| skew.load(Ordering::Relaxed))
+            .max_by_key(|skew| skew.unsigned_abs())
+            .unwrap_or(0);
         let acc_late_total = self.acc_late_total.load(Ordering::Relaxed);
         let acc_no_journey_total = self.acc_no_journey_total.load(Ordering::Relaxed);
 
         // Get drop and received counters (don't reset)
         let mqtt_events_dropped = self.mqtt_events_dropped.load(Ordering::Relaxed);
+        let http_ingress_rejected_bodies =
+            self.http_ingress_rejected_bodies.load(Ordering::Relaxed);
         let acc_events_dropped = self.acc_events_dropped.load(Ordering::Relaxed);
         let gate_cmds_dropped = self.gate_cmds_dropped.load(Ordering::Relaxed);
         let mqtt_events_received = self.mqtt_events_received.load(Ordering::Relaxed);
@@ -974,11 +1342,16 @@ impl Metrics {
             active_tracks,
             authorized_tracks,
             gate_commands_sent: gate_commands,
+            gate_triggers_total,
+            gate_trigger_opens_total,
+            gate_trigger_lead_buckets,
+            gate_trigger_lead_avg_ms,
             gate_lat_buckets,
             gate_lat_avg_us: gate_avg_latency,
             gate_lat_max_us: gate_max_latency,
             gate_lat_p99_us: gate_lat_p99,
             gate_state,
+            gate_dry_run,
             exits_total,
             journeys_position_exit_total,
             journeys_pass_through_total,
@@ -986,6 +1359,29 @@ impl Metrics {
             acc_matched_total,
             stitch_matched_total,
             stitch_expired_total,
+            stitch_handover_total,
+            zone_events_derived_total,
+            zone_events_deduped_total,
+            sensor_offline_total,
+            gate_opens_suppressed_total,
+            free_exit_pulses_total,
+            sensor_lag_buckets,
+            sensor_lag_avg_ms,
+            clock_skew_ms,
+            clock_skew_alerts_total,
+            frames_reordered_total,
+            frames_duplicate_total,
+            frames_too_late_total,
+            acc_group_extensions_total,
+            object_events_filtered_total,
+            auth_inherited_total,
+            shadow_agree_today,
+            shadow_would_open_today,
+            shadow_would_block_today,
+            mqtt_reconnects_total,
+            mqtt_disconnected_ms_total,
+            ingress_gap_ms_total,
+            ingress_events_missed_total,
             stitch_distance_buckets,
             stitch_distance_avg_cm,
             stitch_time_buckets,
@@ -993,6 +1389,7 @@ impl Metrics {
             acc_late_total,
             acc_no_journey_total,
             mqtt_events_dropped,
+            http_ingress_rejected_bodies,
             acc_events_dropped,
             gate_cmds_dropped,
             mqtt_events_received,
@@ -1059,6 +1456,14 @@ pub struct MetricsSummary {
     pub active_tracks: usize,
     pub authorized_tracks: usize,
     pub gate_commands_sent: u64,
+    /// Virtual pre-gate triggers fired
+    pub gate_triggers_total: u64,
+    /// Gate commands started by a virtual trigger
+    pub gate_trigger_opens_total: u64,
+    /// Trigger lead over GATE zone entry histogram buckets (ms)
+    pub gate_trigger_lead_buckets: [u64; NUM_BUCKETS],
+    /// Average trigger lead over GATE zone entry (ms)
+    pub gate_trigger_lead_avg_ms: u64,
     /// Gate command E2E latency histogram buckets (same bounds)
     pub gate_lat_buckets: [u64; NUM_BUCKETS],
     /// Average gate command latency (µs)
@@ -1069,6 +1474,8 @@ pub struct MetricsSummary {
     pub gate_lat_p99_us: u64,
     /// Current gate state (0=closed, 1=moving, 2=open)
     pub gate_state: u64,
+    /// Whether gate commands are logged instead of sent
+    pub gate_dry_run: bool,
     /// Total exits through gate
     pub exits_total: u64,
     /// Journeys completed via position-detected exit
@@ -1083,6 +1490,52 @@ pub struct MetricsSummary {
     pub stitch_matched_total: u64,
     /// Total tracks truly lost (expired without stitch)
     pub stitch_expired_total: u64,
+    /// Total tracks handed over to another sensor's live track
+    pub stitch_handover_total: u64,
+    /// Total zone events synthesized from positions
+    pub zone_events_derived_total: u64,
+    /// Total sensor zone events dropped as duplicates of derived ones
+    pub zone_events_deduped_total: u64,
+    /// Total times a sensor went offline
+    pub sensor_offline_total: u64,
+    /// Total gate opens suppressed while degraded (keep_locked)
+    pub gate_opens_suppressed_total: u64,
+    /// Total free-exit gate open pulses while degraded
+    pub free_exit_pulses_total: u64,
+    /// Sensor lag histogram buckets (ms, receive time minus event time)
+    pub sensor_lag_buckets: [u64; NUM_BUCKETS],
+    /// Average sensor lag (ms)
+    pub sensor_lag_avg_ms: u64,
+    /// Clock skew estimate of the worst sensor (ms)
+    pub clock_skew_ms: i64,
+    /// Total clock skew alerts raised
+    pub clock_skew_alerts_total: u64,
+    /// Total frames released out of arrival order
+    pub frames_reordered_total: u64,
+    /// Total duplicate frames dropped
+    pub frames_duplicate_total: u64,
+    /// Total frames dropped for arriving too late
+    pub frames_too_late_total: u64,
+    /// Total tracks authorized through the payer's group
+    pub acc_group_extensions_total: u64,
+    /// Total events dropped for a filtered object type
+    pub object_events_filtered_total: u64,
+    /// Total tracks inheriting authorization from an adjacent track
+    pub auth_inherited_total: u64,
+    /// Shadow decisions agreeing with the live policy (current UTC day)
+    pub shadow_agree_today: u64,
+    /// Shadow would have opened where the live policy blocked (current UTC day)
+    pub shadow_would_open_today: u64,
+    /// Shadow would have blocked where the live policy opened (current UTC day)
+    pub shadow_would_block_today: u64,
+    /// Total MQTT ingress reconnects
+    pub mqtt_reconnects_total: u64,
+    /// Total time the MQTT ingress connection was down (ms)
+    pub mqtt_disconnected_ms_total: u64,
+    /// Total sensor time lost across reconnects (ms, by frame time)
+    pub ingress_gap_ms_total: u64,
+    /// Total events estimated to be lost across reconnects
+    pub ingress_events_missed_total: u64,
     /// Stitch distance histogram buckets (cm)
     /// Bounds: ≤10, ≤20, ≤40, ≤80, ≤160, ≤320, ≤640, ≤1280, ≤2560, ≤5120, >5120 cm
     pub stitch_distance_buckets: [u64; NUM_BUCKETS],
@@ -1098,6 +1551,8 @@ pub struct MetricsSummary {
     pub acc_no_journey_total: u64,
     /// MQTT events dropped due to channel full
     pub mqtt_events_dropped: u64,
+    /// HTTP ingress bodies rejected due to channel full (503, the sensor retries)
+    pub http_ingress_rejected_bodies: u64,
     /// ACC events dropped due to channel full
     pub acc_events_dropped: u64,
     /// Gate commands dropped due to channel full
@@ -1,13 +1,23 @@
 //! Infrastructure - configuration, metrics, and broker
 //!
 //! This module contains infrastructure concerns:
+//! - `clock` - Time source for the tracker (system or manual)
 //! - `config` - Application configuration (TOML loading, defaults)
+//! - `config_check` - Semantic config checks (`gateway check-config`)
+//! - `geometry` - Xovis geometry exports (zone/line polygons)
 //! - `metrics` - Lock-free metrics collection
 //! - `broker` - Embedded MQTT broker (rumqttd)
+//! - `broker_acl` - Authentication and topic ACL proxy in front of the broker
+//! - `tls` - TLS transports and startup certificate checks
 
 pub mod broker;
+pub mod broker_acl;
+pub mod clock;
 pub mod config;
+pub mod config_check;
+pub mod geometry;
 pub mod metrics;
+pub mod tls;
 
 // Re-export commonly used types
 pub use config::{Config, GateMode};
@@ -0,0 +1,220 @@
+//! TLS for the MQTT connections and the embedded broker
+//!
+//! Certificate files are checked once at startup so a missing, malformed or
+//! expired file stops the gateway with an error naming the config key,
+//! instead of showing up later as an endless MQTT reconnect loop.
+
+use crate::domain::journey::epoch_ms;
+use crate::infra::config::{Config, TlsConfig};
+use anyhow::Context;
+use rumqttc::{TlsConfiguration, Transport};
+use std::sync::Arc;
+use tokio_rustls::rustls::pki_types::CertificateDer;
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{RootCertStore, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+use tracing::{info, warn};
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+/// Warn when a certificate expires within this many days
+const EXPIRY_WARN_DAYS: i64 = 30;
+
+const SECS_PER_DAY: i64 = 86_400;
+
+/// Check the files of every enabled TLS endpoint and log their certificates
+pub fn check_certificates(config: &Config) -> anyhow::Result<()> {
+    let now_s = (epoch_ms() / 1000) as i64;
+    let mut endpoints = vec![("mqtt", config.mqtt_tls())];
+    if config.mqtt_egress_enabled() {
+        endpoints.push(("mqtt_egress", config.mqtt_egress_tls()));
+    }
+    endpoints.push(("broker", config.broker_tls()));
+
+    for (section, tls) in endpoints.into_iter().filter(|(_, tls)| tls.enabled) {
+        if let Some(path) = &tls.ca_file {
+            check_certificate_file(section, "ca_file", path, now_s)?;
+        }
+        if let Some(path) = &tls.cert_file {
+            check_certificate_file(section, "cert_file", path, now_s)?;
+        }
+        if let Some(path) = &tls.key_file {
+            check_key_file(section, path)?;
+        }
+        if section == "broker" {
+            broker_acceptor(tls).context("[broker.tls]")?;
+        }
+        info!(endpoint = %section, client_cert = %tls.cert_file.is_some(), "tls_enabled");
+    }
+    Ok(())
+}
+
+/// Transport for an MQTT client connection (plain TCP when TLS is disabled)
+pub fn client_transport(tls: &TlsConfig) -> anyhow::Result<Transport> {
+    if !tls.enabled {
+        return Ok(Transport::Tcp);
+    }
+    let ca_file = tls.ca_file.as_deref().context("TLS enabled without ca_file")?;
+    let ca = read(ca_file)?;
+    let client_auth = match (&tls.cert_file, &tls.key_file) {
+        (Some(cert_file), Some(key_file)) => Some((read(cert_file)?, read(key_file)?)),
+        _ => None,
+    };
+    Ok(Transport::tls_with_config(TlsConfiguration::Simple { ca, alpn: None, client_auth }))
+}
+
+/// TLS settings for the embedded broker listener (None when disabled)
+pub fn broker_tls(tls: &TlsConfig) -> Option<rumqttd::TlsConfig> {
+    match (tls.enabled, &tls.cert_file, &tls.key_file) {
+        (true, Some(cert_file), Some(key_file)) => Some(rumqttd::TlsConfig::Rustls {
+            capath: None,
+            certpath: cert_file.clone(),
+            keypath: key_file.clone(),
+        }),
+        _ => None,
+    }
+}
+
+/// TLS acceptor for the broker ACL proxy (None when disabled)
+///
+/// With `ca_file` set, clients must present a certificate signed by that CA.
+pub fn broker_acceptor(tls: &TlsConfig) -> anyhow::Result<Option<TlsAcceptor>> {
+    let (true, Some(cert_file), Some(key_file)) = (tls.enabled, &tls.cert_file, &tls.key_file)
+    else {
+        return Ok(None);
+    };
+    let certs = read_certs(cert_file)?;
+    let key = rustls_pemfile::private_key(&mut read(key_file)?.as_slice())
+        .with_context(|| format!("Invalid PEM in {key_file}"))?
+        .with_context(|| format!("No private key in {key_file}"))?;
+    let builder = ServerConfig::builder();
+    let builder = match &tls.ca_file {
+        Some(ca_file) => {
+            let mut roots = RootCertStore::empty();
+            for cert in read_certs(ca_file)? {
+                roots.add(cert).with_context(|| format!("Invalid CA certificate in {ca_file}"))?;
+            }
+            let verifier = WebPkiClientVerifier::builder(Arc::new(roots))
+                .build()
+                .with_context(|| format!("No usable CA certificate in {ca_file}"))?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+    let config =
+        builder.with_single_cert(certs, key).context("Broker certificate and key don't match")?;
+    Ok(Some(TlsAcceptor::from(Arc::new(config))))
+}
+
+fn read_certs(path: &str) -> anyhow::Result<Vec<CertificateDer<'static>>> {
+    rustls_pemfile::certs(&mut read(path)?.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Invalid PEM in {path}"))
+}
+
+fn read(path: &str) -> anyhow::Result<Vec<u8>> {
+    std::fs::read(path).with_context(|| format!("Failed to read {path}"))
+}
+
+fn check_certificate_file(section: &str, key: &str, path: &str, now_s: i64) -> anyhow::Result<()> {
+    let pem = read(path).with_context(|| format!("[{section}.tls].{key}"))?;
+    let certs = rustls_pemfile::certs(&mut pem.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("[{section}.tls].{key} = {path}: invalid PEM"))?;
+    if certs.is_empty() {
+        anyhow::bail!("[{section}.tls].{key} = {path}: no certificate found");
+    }
+
+    for der in &certs {
+        let (_, cert) = X509Certificate::from_der(der)
+            .map_err(|e| anyhow::anyhow!("[{section}.tls].{key} = {path}: {e}"))?;
+        let not_before = cert.validity().not_before.timestamp();
+        let not_after = cert.validity().not_after.timestamp();
+        if let Some(problem) = validity_problem(not_before, not_after, now_s) {
+            anyhow::bail!(
+                "[{section}.tls].{key} = {path}: certificate {} {problem}",
+                cert.subject()
+            );
+        }
+
+        let days_left = (not_after - now_s) / SECS_PER_DAY;
+        info!(
+            endpoint = %section,
+            file = %path,
+            subject = %cert.subject(),
+            not_after = %cert.validity().not_after,
+            days_left = %days_left,
+            "tls_certificate"
+        );
+        if days_left < EXPIRY_WARN_DAYS {
+            warn!(
+                endpoint = %section,
+                file = %path,
+                days_left = %days_left,
+                "tls_certificate_expiring"
+            );
+        }
+    }
+    Ok(())
+}
+
+fn check_key_file(section: &str, path: &str) -> anyhow::Result<()> {
+    let pem = read(path).with_context(|| format!("[{section}.tls].key_file"))?;
+    match rustls_pemfile::private_key(&mut pem.as_slice()) {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => anyhow::bail!("[{section}.tls].key_file = {path}: no private key found"),
+        Err(e) => anyhow::bail!("[{section}.tls].key_file = {path}: invalid PEM: {e}"),
+    }
+}
+
+/// Why a certificate valid from `not_before` to `not_after` is unusable at `now_s`
+fn validity_problem(not_before: i64, not_after: i64, now_s: i64) -> Option<String> {
+    if now_s < not_before {
+        Some(format!("is not valid for another {}s", not_before - now_s))
+    } else if now_s > not_after {
+        Some(format!("expired {} days ago", (now_s - not_after) / SECS_PER_DAY))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn tls(ca_file: &str) -> TlsConfig {
+        TlsConfig { enabled: true, ca_file: Some(ca_file.to_string()), ..Default::default() }
+    }
+
+    #[test]
+    fn test_validity_problem() {
+        assert!(validity_problem(1000, 2000, 1500).is_none());
+        assert!(validity_problem(1000, 2000, 500).unwrap().contains("not valid"));
+        let expired = validity_problem(0, SECS_PER_DAY, 3 * SECS_PER_DAY).unwrap();
+        assert_eq!(expired, "expired 2 days ago");
+    }
+
+    #[test]
+    fn test_missing_ca_file_names_config_key() {
+        let config = Config::default().with_mqtt_tls(tls("/nonexistent/ca.pem"));
+        let err = check_certificates(&config).unwrap_err();
+        assert!(format!("{err:#}").contains("[mqtt.tls].ca_file"));
+    }
+
+    #[test]
+    fn test_file_without_certificate_rejected() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(b"not a certificate\n").unwrap();
+        let config = Config::default().with_mqtt_tls(tls(file.path().to_str().unwrap()));
+        let err = check_certificates(&config).unwrap_err();
+        assert!(format!("{err:#}").contains("no certificate found"));
+    }
+
+    #[test]
+    fn test_disabled_tls_is_plain_tcp() {
+        let transport = client_transport(&TlsConfig::default()).unwrap();
+        assert!(matches!(transport, Transport::Tcp));
+        assert!(broker_tls(&TlsConfig::default()).is_none());
+    }
+}
@@ -120,6 +120,8 @@ async fn handle_acc_connection(
                 event_time: 0,
                 received_at: Instant::now(),
                 position: None,
+                derived: false,
+                obj_type: None,
             };
 
             // Use try_send to never block the connection handler
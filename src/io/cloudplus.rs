@@ -448,11 +448,7 @@ impl CloudPlusClient {
             acc.extend_from_slice(&buf[..n]);
 
             // Parse frames
-            loop {
-                let Some((frame, consumed)) = Frame::parse(&acc) else {
-                    break;
-                };
-
+            while let Some((frame, consumed)) = Frame::parse(&acc) {
                 acc.advance(consumed);
 
                 if !frame.valid {
@@ -664,6 +660,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::identity_op)] // XOR every byte of the frame explicitly
     fn test_calculate_checksum() {
         let data = [STX, 0x00, CMD_OPEN_DOOR, 0xff, 0x01, 0x00, 0x00];
         let cs = calculate_checksum(&data);
@@ -15,8 +15,8 @@ pub enum EgressMessage {
     Journey(JourneyPayload),
     /// Live zone event for real-time display
     ZoneEvent(ZoneEventPayload),
-    /// Periodic metrics snapshot
-    Metrics(MetricsPayload),
+    /// Periodic metrics snapshot (boxed: much larger than the other payloads)
+    Metrics(Box<MetricsPayload>),
     /// Gate state change
     GateState(GateStatePayload),
     /// Track lifecycle event (create, delete, stitch, lost)
@@ -25,6 +25,10 @@ pub enum EgressMessage {
     AccEvent(AccEventPayload),
     /// Position update for spatial tracking investigation
     Position(PositionPayload),
+    /// Sensor liveness change (offline/online)
+    SensorState(SensorStatePayload),
+    /// Shadow policy decision differing from the live one
+    ShadowDiff(ShadowDiffPayload),
 }
 
 /// Payload for completed journeys
@@ -114,6 +118,12 @@ pub struct MetricsPayload {
     pub event_queue_utilization_pct: u64,
     /// Gate queue utilization percentage (0-100)
     pub gate_queue_utilization_pct: u64,
+    /// Sensor lag histogram buckets (ms, receive time minus event time)
+    pub sensor_lag_buckets: [u64; METRICS_NUM_BUCKETS],
+    /// Average sensor lag (ms)
+    pub sensor_lag_avg_ms: u64,
+    /// Clock skew estimate of the worst sensor (ms)
+    pub clock_skew_ms: i64,
 }
 
 impl MetricsPayload {
@@ -144,6 +154,9 @@ impl MetricsPayload {
             cloudplus_queue_depth: summary.cloudplus_queue_depth,
             event_queue_utilization_pct: summary.event_queue_utilization_pct,
             gate_queue_utilization_pct: summary.gate_queue_utilization_pct,
+            sensor_lag_buckets: summary.sensor_lag_buckets,
+            sensor_lag_avg_ms: summary.sensor_lag_avg_ms,
+            clock_skew_ms: summary.clock_skew_ms,
         }
     }
 }
@@ -156,12 +169,13 @@ pub struct GateStatePayload {
     pub site: Option<String>,
     /// Timestamp (epoch ms)
     pub ts: u64,
-    /// Gate state (cmd_enqueued, cmd_sent, cmd_dropped, open, closed, moving)
+    /// Gate state (cmd_enqueued, cmd_sent, cmd_dry_run, cmd_dropped, open, closed, moving,
+    /// dry_run_on, dry_run_off)
     pub state: String,
     /// Associated track ID (if any)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tid: Option<i64>,
-    /// Source of the state change (rs485, tcp, cmd)
+    /// Source of the state change (rs485, tcp, cmd, api)
     pub src: String,
     /// Queue delay in microseconds (time from enqueue to processing start)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -242,6 +256,53 @@ pub struct TrackEventPayload {
     pub parent_jid: Option<String>,
 }
 
+/// Payload for sensor liveness changes
+#[derive(Debug, Clone, Serialize)]
+pub struct SensorStatePayload {
+    /// Site identifier
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site: Option<String>,
+    /// Timestamp (epoch ms)
+    pub ts: u64,
+    /// Event type: sensor_offline, sensor_online, clock_skew, clock_skew_ok
+    pub t: String,
+    /// Sensor ID from [[sensors]]
+    pub sensor: u8,
+    /// Sensor name
+    pub name: String,
+    /// Time since the last frame (ms)
+    pub frame_age_ms: u64,
+    /// Time since the last event (ms)
+    pub event_age_ms: u64,
+    /// Gate policy now in effect (normal, keep_locked, free_exit)
+    pub policy: String,
+    /// Clock skew estimate (ms), once known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clock_skew_ms: Option<i64>,
+}
+
+/// Payload for shadow policy decisions that differ from the live policy
+#[derive(Debug, Clone, Serialize)]
+pub struct ShadowDiffPayload {
+    /// Site identifier
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site: Option<String>,
+    /// Timestamp (epoch ms)
+    pub ts: u64,
+    /// Track ID
+    pub tid: i64,
+    /// Decision point: gate_entry or acc
+    pub point: String,
+    /// Live policy decision: open or blocked
+    pub live: String,
+    /// Shadow policy decision: open or blocked
+    pub shadow: String,
+    /// Dwell accumulated under the live policy (ms)
+    pub dwell_ms: u64,
+    /// Dwell accumulated under the shadow policy (ms)
+    pub shadow_dwell_ms: u64,
+}
+
 /// Debug info for a track when ACC unmatched
 #[derive(Debug, Clone, Serialize)]
 pub struct AccDebugTrack {
@@ -311,7 +372,7 @@ pub struct PositionPayload {
     pub ts: u64,
     /// Track ID from Xovis
     pub tid: i64,
-    /// Object type: "PERSON" or "GROUP"
+    /// Object type as classified by Xovis (e.g. "PERSON", "CHILD")
     pub obj_type: String,
     /// X coordinate (meters)
     pub x: f64,
@@ -364,7 +425,7 @@ impl EgressSender {
     /// Send a metrics snapshot with current gate state
     pub fn send_metrics(&self, summary: MetricsSummary, gate_state: &str) {
         let payload = MetricsPayload::from_summary(summary, self.site_id.clone(), gate_state);
-        let _ = self.tx.try_send(EgressMessage::Metrics(payload));
+        let _ = self.tx.try_send(EgressMessage::Metrics(Box::new(payload)));
     }
 
     /// Send a gate state change
@@ -394,6 +455,20 @@ impl EgressSender {
         payload.site = Some(self.site_id.clone());
         let _ = self.tx.try_send(EgressMessage::Position(payload));
     }
+
+    /// Send a sensor liveness change (offline/online)
+    /// Injects site_id into the payload
+    pub fn send_sensor_state(&self, mut payload: SensorStatePayload) {
+        payload.site = Some(self.site_id.clone());
+        let _ = self.tx.try_send(EgressMessage::SensorState(payload));
+    }
+
+    /// Send a shadow policy diff
+    /// Injects site_id into the payload
+    pub fn send_shadow_diff(&self, mut payload: ShadowDiffPayload) {
+        payload.site = Some(self.site_id.clone());
+        let _ = self.tx.try_send(EgressMessage::ShadowDiff(payload));
+    }
 }
 
 /// Create a new egress channel pair
@@ -0,0 +1,255 @@
+//! Xovis HTTP data-push receiver
+//!
+//! Alternative to MQTT for sites where the sensor can't reach the broker.
+//! Xovis POSTs the same `live_data.frames` JSON it would publish; bodies are
+//! handed to the MQTT ingest loop (see `mqtt::start_mqtt_client`) so they go
+//! through the same sensor mapping and analysis logging. Bodies rejected
+//! because the ingest queue is full are counted per body
+//! (`gateway_http_ingress_rejected_bodies_total`), not as dropped events.
+//!
+//! Pushed frames can open the gate, so each request must come from
+//! `allowed_ips` and/or carry `Authorization: Bearer <auth_token>`.
+
+use crate::infra::config::{Config, HttpIngressConfig};
+use crate::infra::metrics::Metrics;
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::header::AUTHORIZATION;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use parking_lot::Mutex;
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use subtle::ConstantTimeEq;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc, watch};
+use tracing::{debug, error, info, warn};
+
+/// A pushed sensor message
+#[derive(Debug)]
+pub struct PushedMessage {
+    /// Request path without the leading `/` (matched like an MQTT topic)
+    pub topic: String,
+    pub payload: String,
+    pub received_at: Instant,
+}
+
+/// State shared by the push connections
+struct PushContext {
+    config: HttpIngressConfig,
+    push_tx: mpsc::Sender<PushedMessage>,
+    metrics: Arc<Metrics>,
+    /// Rate-limits the queue full warning to 1 per second
+    last_drop_warn: Mutex<Instant>,
+}
+
+/// Start the HTTP push listener and send received bodies to `push_tx`
+pub async fn start_http_ingress(
+    config: &Config,
+    push_tx: mpsc::Sender<PushedMessage>,
+    metrics: Arc<Metrics>,
+    mut shutdown: watch::Receiver<bool>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let http_ingress = config.http_ingress();
+    let addr: SocketAddr =
+        format!("{}:{}", http_ingress.bind_address, http_ingress.port).parse()?;
+    let listener = TcpListener::bind(addr).await?;
+    let ctx = Arc::new(PushContext {
+        config: http_ingress.clone(),
+        push_tx,
+        metrics,
+        last_drop_warn: Mutex::new(Instant::now() - Duration::from_secs(2)),
+    });
+
+    info!(
+        addr = %addr,
+        token = %http_ingress.auth_token.is_some(),
+        allowed_ips = ?http_ingress.allowed_ips,
+        "http_ingress_started"
+    );
+
+    loop {
+        tokio::select! {
+            result = listener.accept() => {
+                match result {
+                    Ok((stream, peer)) => {
+                        let io = TokioIo::new(stream);
+                        let ctx = ctx.clone();
+                        tokio::spawn(async move {
+                            let service =
+                                service_fn(move |req| handle_push(req, peer.ip(), ctx.clone()));
+                            if let Err(e) = http1::Builder::new().serve_connection(io, service).await {
+                                debug!(error = %e, "http_ingress_connection_error");
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        error!(error = %e, "http_ingress_accept_error");
+                    }
+                }
+            }
+            _ = shutdown.changed() => {
+                if *shutdown.borrow() {
+                    info!("http_ingress_shutdown");
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+async fn handle_push(
+    req: Request<hyper::body::Incoming>,
+    peer: IpAddr,
+    ctx: Arc<PushContext>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    let received_at = Instant::now();
+    let config = &ctx.config;
+    if !config.allowed_ips.is_empty() && !config.allowed_ips.contains(&peer) {
+        warn!(peer = %peer, "http_ingress_ip_rejected");
+        return Ok(response(StatusCode::FORBIDDEN, "Forbidden"));
+    }
+    if !is_authorized(&req, config.auth_token.as_deref()) {
+        warn!(peer = %peer, "http_ingress_auth_rejected");
+        return Ok(response(StatusCode::UNAUTHORIZED, "Unauthorized"));
+    }
+    if req.method() != Method::POST {
+        return Ok(response(StatusCode::METHOD_NOT_ALLOWED, "POST only"));
+    }
+    let topic = req.uri().path().trim_start_matches('/').to_string();
+
+    let body = match Limited::new(req.into_body(), config.max_body_bytes).collect().await {
+        Ok(collected) => collected.to_bytes(),
+        Err(e) => {
+            warn!(topic = %topic, error = %e, "http_ingress_body_rejected");
+            return Ok(response(StatusCode::PAYLOAD_TOO_LARGE, "Body rejected"));
+        }
+    };
+    let payload = match String::from_utf8(body.to_vec()) {
+        Ok(payload) => payload,
+        Err(_) => return Ok(response(StatusCode::BAD_REQUEST, "Invalid UTF-8")),
+    };
+
+    match ctx.push_tx.try_send(PushedMessage { topic, payload, received_at }) {
+        Ok(()) => Ok(response(StatusCode::OK, "OK")),
+        // Ingest is behind: let the sensor retry rather than queueing stale frames
+        Err(TrySendError::Full(msg)) => {
+            ctx.metrics.record_http_ingress_rejected_body();
+            let mut last_drop_warn = ctx.last_drop_warn.lock();
+            if last_drop_warn.elapsed() > Duration::from_secs(1) {
+                warn!(topic = %msg.topic, "http_ingress_dropped: channel full");
+                *last_drop_warn = Instant::now();
+            }
+            Ok(response(StatusCode::SERVICE_UNAVAILABLE, "Ingest queue full"))
+        }
+        Err(TrySendError::Closed(_)) => {
+            Ok(response(StatusCode::SERVICE_UNAVAILABLE, "Ingest stopped"))
+        }
+    }
+}
+
+/// Check the bearer token in constant time; any request passes without a token
+fn is_authorized<B>(req: &Request<B>, token: Option<&str>) -> bool {
+    let Some(token) = token else {
+        return true;
+    };
+    req.headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|sent| bool::from(sent.as_bytes().ct_eq(token.as_bytes())))
+}
+
+fn response(status: StatusCode, body: &'static str) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(status)
+        .body(Full::new(Bytes::from(body)))
+        .expect("static response should not fail")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Start a listener on a free port and wait until it accepts connections
+    async fn start(
+        auth_token: Option<&str>,
+        allowed_ips: Vec<IpAddr>,
+        push_tx: mpsc::Sender<PushedMessage>,
+        metrics: Arc<Metrics>,
+    ) -> String {
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let config = Config::default().with_http_ingress(HttpIngressConfig {
+            enabled: true,
+            bind_address: "127.0.0.1".to_string(),
+            port,
+            max_body_bytes: 64,
+            auth_token: auth_token.map(str::to_string),
+            allowed_ips,
+        });
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        tokio::spawn(async move {
+            let _shutdown_tx = shutdown_tx;
+            start_http_ingress(&config, push_tx, metrics, shutdown_rx).await
+        });
+        for _ in 0..50 {
+            if tokio::net::TcpStream::connect(("127.0.0.1", port)).await.is_ok() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        format!("http://127.0.0.1:{port}/xovis/entrance")
+    }
+
+    #[tokio::test]
+    async fn test_pushed_body_reaches_ingest() {
+        let (push_tx, mut push_rx) = mpsc::channel(1);
+        let metrics = Arc::new(Metrics::new());
+        let allowed_ips = vec![IpAddr::from([127, 0, 0, 1])];
+        let url = start(Some("secret"), allowed_ips, push_tx, metrics.clone()).await;
+        let client = reqwest::Client::new();
+        let post = |body: &'static str| client.post(&url).bearer_auth("secret").body(body).send();
+        let status = post(r#"{"live_data":{"frames":[]}}"#).await.unwrap().status();
+        assert_eq!(status, StatusCode::OK);
+
+        let msg = push_rx.recv().await.unwrap();
+        assert_eq!(msg.topic, "xovis/entrance");
+        assert_eq!(msg.payload, r#"{"live_data":{"frames":[]}}"#);
+
+        // Queue full (capacity 1, nothing drained)
+        post("{}").await.unwrap();
+        assert_eq!(post("{}").await.unwrap().status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(metrics.http_ingress_rejected_bodies(), 1);
+        assert_eq!(metrics.mqtt_events_dropped(), 0);
+        // Over max_body_bytes
+        let large = post(
+            r#"{"live_data":{"frames":[], "padding":"xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx"}}"#,
+        );
+        assert_eq!(large.await.unwrap().status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let get = client.get(&url).bearer_auth("secret").send();
+        assert_eq!(get.await.unwrap().status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[tokio::test]
+    async fn test_unauthenticated_push_rejected() {
+        let (push_tx, mut push_rx) = mpsc::channel(1);
+        let url = start(Some("secret"), Vec::new(), push_tx, Arc::new(Metrics::new())).await;
+        let client = reqwest::Client::new();
+        let missing = client.post(&url).body("{}").send();
+        assert_eq!(missing.await.unwrap().status(), StatusCode::UNAUTHORIZED);
+        let wrong = client.post(&url).bearer_auth("guess").body("{}").send();
+        assert_eq!(wrong.await.unwrap().status(), StatusCode::UNAUTHORIZED);
+
+        let (push_tx, _push_rx) = mpsc::channel(1);
+        let allowed_ips = vec![IpAddr::from([10, 0, 0, 1])];
+        let url = start(None, allowed_ips, push_tx, Arc::new(Metrics::new())).await;
+        let other_ip = client.post(&url).body("{}").send();
+        assert_eq!(other_ip.await.unwrap().status(), StatusCode::FORBIDDEN);
+        assert!(push_rx.try_recv().is_err());
+    }
+}
@@ -2,6 +2,9 @@
 //!
 //! This module contains all external IO operations:
 //! - `mqtt` - MQTT client for receiving Xovis sensor data
+//! - `http_ingress` - HTTP data-push receiver (alternative to MQTT ingress)
+//! - `sensor_adapter` - Sensor payload parsing trait, selected per `[[sensors]]` input
+//! - `xovis` - Xovis live data adapter
 //! - `mqtt_egress` - MQTT publisher for egress events
 //! - `egress_channel` - Typed channel for MQTT egress messages
 //! - `rs485` - Serial communication for door state monitoring
@@ -16,17 +19,21 @@ pub mod analysis_logger;
 pub mod cloudplus;
 pub mod egress;
 pub mod egress_channel;
+pub mod http_ingress;
 pub mod mqtt;
 pub mod mqtt_egress;
 pub mod prometheus;
 pub mod rs485;
+pub mod sensor_adapter;
+pub mod xovis;
 
 // Re-export commonly used types
 pub use acc_listener::{start_acc_listener, AccListenerConfig};
 pub use egress::{create_egress_writer, EgressWriter};
 pub use egress_channel::{
     create_egress_channel, AccDebugPending, AccDebugTrack, AccEventPayload, EgressSender,
-    GateStatePayload, PositionPayload, TrackEventPayload, ZoneEventPayload,
+    GateStatePayload, PositionPayload, SensorStatePayload, ShadowDiffPayload, TrackEventPayload,
+    ZoneEventPayload,
 };
 pub use mqtt_egress::MqttPublisher;
 pub use rs485::Rs485Monitor;
@@ -1,34 +1,131 @@
 //! MQTT client for receiving Xovis sensor data
 
-use crate::domain::journey::epoch_ms;
-use crate::domain::types::{
-    EventType, Frame, GeometryId, ParsedEvent, TimestampValue, TrackId, TrackedObject, XovisMessage,
-};
-use crate::infra::config::Config;
+use crate::domain::types::{EventType, ParsedEvent, TrackId, TrackedObject};
+use crate::infra::clock::{Clock, SystemClock};
+use crate::infra::config::{Config, Sensor};
 use crate::infra::metrics::Metrics;
+use crate::infra::tls;
 use crate::io::analysis_logger::AnalysisLogger;
 use crate::io::egress_channel::{EgressSender, PositionPayload};
+use crate::io::http_ingress::PushedMessage;
+use crate::io::sensor_adapter::{self, SensorAdapter, SensorFrame};
+use crate::services::frame_reorder::FrameReorder;
+use crate::services::gate_trigger::GateTriggerDetector;
+use crate::services::group_membership::GroupDetector;
+use crate::services::object_filter::ObjectTypeFilter;
+use crate::services::sensor_watchdog::SensorHealth;
+use crate::services::stale_tracks::StalePruner;
+use crate::services::stitcher::LivePositions;
+use crate::services::tracker::RestoredTracks;
+use crate::services::zone_engine::ZoneEngine;
 use parking_lot::Mutex;
 use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS};
 use rustc_hash::FxHashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use time::format_description::well_known::Rfc3339;
-use time::OffsetDateTime;
 use tokio::sync::mpsc::error::TrySendError;
 use tokio::sync::{mpsc, watch};
 use tracing::{debug, error, info, warn};
 
-/// Bit flag for Xovis GROUP tracks (high bit set)
-const XOVIS_GROUP_BIT: i64 = 0x80000000;
+/// Frame-time jumps after a reconnect below this are normal frame spacing
+/// (or were covered by the broker replaying a persistent session)
+const MIN_INGRESS_GAP_MS: u64 = 1000;
+
+/// Smoothing factor for the per-sensor event rate estimate
+const EVENT_RATE_ALPHA: f64 = 0.05;
+
+/// Sensor time lost across a broker reconnect
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct IngressGap {
+    /// Last frame time before the disconnect (epoch ms)
+    from_ms: u64,
+    /// First frame time after the reconnect (epoch ms)
+    to_ms: u64,
+    /// Events estimated to have been published in the gap
+    events_missed: u64,
+}
+
+#[derive(Debug, Default)]
+struct SensorFrames {
+    last_frame_ms: u64,
+    /// Events per second of frame time (smoothed)
+    events_per_s: f64,
+    /// Next frame is the first after a reconnect
+    after_reconnect: bool,
+}
+
+/// Reconnect accounting: how long the connection was down and how much
+/// sensor time (by frame time) each sensor lost
+struct ReconnectGaps {
+    sensors: Vec<SensorFrames>,
+    disconnected_at: Option<Instant>,
+}
+
+impl ReconnectGaps {
+    fn new(sensor_count: usize) -> Self {
+        Self {
+            sensors: (0..sensor_count).map(|_| SensorFrames::default()).collect(),
+            disconnected_at: None,
+        }
+    }
+
+    fn disconnected(&mut self, now: Instant) {
+        self.disconnected_at.get_or_insert(now);
+    }
+
+    /// Returns how long the connection was down (None on the first connect)
+    fn connected(&mut self, now: Instant) -> Option<Duration> {
+        let down = now.duration_since(self.disconnected_at.take()?);
+        for sensor in &mut self.sensors {
+            sensor.after_reconnect = true;
+        }
+        Some(down)
+    }
+
+    /// Observe the frames of one message from sensor `index`
+    ///
+    /// Returns the gap when this is the first message after a reconnect and
+    /// its frame time jumped by at least `MIN_INGRESS_GAP_MS`.
+    fn observe(&mut self, index: usize, frames: &[SensorFrame]) -> Option<IngressGap> {
+        let sensor = self.sensors.get_mut(index)?;
+        let mut gap = None;
+        for frame in frames {
+            let time_ms = frame.time_ms;
+            if time_ms == 0 || time_ms <= sensor.last_frame_ms {
+                continue;
+            }
+            let elapsed_ms = time_ms - sensor.last_frame_ms;
+            if sensor.last_frame_ms > 0 && sensor.after_reconnect {
+                if elapsed_ms >= MIN_INGRESS_GAP_MS {
+                    let events_missed = (sensor.events_per_s * elapsed_ms as f64 / 1000.0) as u64;
+                    gap = Some(IngressGap {
+                        from_ms: sensor.last_frame_ms,
+                        to_ms: time_ms,
+                        events_missed,
+                    });
+                }
+            } else if sensor.last_frame_ms > 0 && elapsed_ms < MIN_INGRESS_GAP_MS {
+                let rate = frame.events.len() as f64 * 1000.0 / elapsed_ms as f64;
+                sensor.events_per_s += EVENT_RATE_ALPHA * (rate - sensor.events_per_s);
+            }
+            sensor.after_reconnect = false;
+            sensor.last_frame_ms = time_ms;
+        }
+        gap
+    }
+}
 
 /// Throttles position updates to reduce bandwidth
 /// Only publishes when:
 /// - At least 100ms since last publish
 /// - Position has moved more than 5cm
+///
+/// Tracks are forgotten by last-seen age: a message only carries the tracks of
+/// one sensor, so its track list can't tell which other tracks are gone.
 struct PositionThrottler {
-    /// Last published position per track_id [x, y, z]
-    last_positions: FxHashMap<i64, [f64; 3]>,
+    /// Last published position [x, y, z] and last seen time (epoch ms) per track_id
+    last_positions: FxHashMap<i64, ([f64; 3], u64)>,
+    pruner: StalePruner,
     /// Last publish time
     last_publish: Instant,
     /// Minimum interval between publishes (100ms)
@@ -41,6 +138,7 @@ impl PositionThrottler {
     fn new() -> Self {
         Self {
             last_positions: FxHashMap::default(),
+            pruner: StalePruner::default(),
             last_publish: Instant::now() - Duration::from_millis(200), // Allow immediate first publish
             min_interval: Duration::from_millis(100),
             min_distance: 0.05, // 5cm
@@ -59,8 +157,9 @@ impl PositionThrottler {
 
     /// Check if a specific track has moved enough to publish
     /// Returns true if the track should be published
-    fn should_publish_track(&mut self, track_id: i64, pos: [f64; 3]) -> bool {
-        if let Some(last_pos) = self.last_positions.get(&track_id) {
+    fn should_publish_track(&mut self, track_id: i64, pos: [f64; 3], now_ms: u64) -> bool {
+        if let Some((last_pos, last_seen_ms)) = self.last_positions.get_mut(&track_id) {
+            *last_seen_ms = now_ms;
             let dx = pos[0] - last_pos[0];
             let dy = pos[1] - last_pos[1];
             let distance = (dx * dx + dy * dy).sqrt();
@@ -69,13 +168,13 @@ impl PositionThrottler {
             }
         }
         // Update last position
-        self.last_positions.insert(track_id, pos);
+        self.last_positions.insert(track_id, (pos, now_ms));
         true
     }
 
     /// Remove stale tracks (not seen in a while)
-    fn cleanup_stale(&mut self, active_track_ids: &[i64]) {
-        self.last_positions.retain(|tid, _| active_track_ids.contains(tid));
+    fn cleanup_stale(&mut self, now_ms: u64) {
+        self.pruner.retain(&mut self.last_positions, now_ms, |&(_, last_seen_ms)| last_seen_ms);
     }
 }
 
@@ -84,6 +183,22 @@ impl PositionThrottler {
 /// Events are sent via try_send to avoid blocking the MQTT eventloop.
 /// Dropped events are counted in metrics and logged (rate-limited).
 /// If egress_sender is provided, position updates are streamed at 10Hz with 5cm threshold.
+/// If `[object_types]` lists are configured, filtered object types are dropped first.
+/// If `[zone_engine]` is enabled, zone events missed by the sensor are derived from positions.
+/// If `[[gate_triggers]]` are configured, GateTrigger events are emitted from positions.
+/// If `[groups]` is enabled, GroupJoin/GroupLeave events are emitted for GROUP tracks.
+/// If live_positions is provided (several sensors), store-frame positions are published
+/// there for cross-sensor handover.
+/// Tracks in frames are reported to restored_tracks (snapshot restore).
+/// Every message is stamped in sensor_health for the liveness watchdog.
+/// If `[frame_reorder]` is enabled, frames pass a per-sensor reorder window first.
+/// Connects over TLS if `[mqtt.tls]` is enabled.
+/// Subscribes with `[mqtt].qos`; reconnect gaps (by frame time) are counted and
+/// sent to the tracker as IngressGap events.
+/// If push_rx is provided, HTTP-pushed messages take the same path as MQTT messages.
+/// If config_rx is provided, reloaded gate triggers replace the running ones.
+/// With `[mqtt].enabled = false` only pushed messages are processed.
+#[allow(clippy::too_many_arguments)]
 pub async fn start_mqtt_client(
     config: &Config,
     event_tx: mpsc::Sender<ParsedEvent>,
@@ -91,122 +206,87 @@ pub async fn start_mqtt_client(
     mut shutdown: watch::Receiver<bool>,
     analysis_logger: Option<Arc<Mutex<AnalysisLogger>>>,
     egress_sender: Option<EgressSender>,
+    live_positions: Option<Arc<LivePositions>>,
+    restored_tracks: Option<Arc<RestoredTracks>>,
+    sensor_health: Arc<SensorHealth>,
+    mut push_rx: Option<mpsc::Receiver<PushedMessage>>,
+    mut config_rx: Option<watch::Receiver<Config>>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let mut mqttoptions = MqttOptions::new("gateway-poc", config.mqtt_host(), config.mqtt_port());
+    let mut mqttoptions =
+        MqttOptions::new(config.mqtt_client_id(), config.mqtt_host(), config.mqtt_port());
     mqttoptions.set_keep_alive(Duration::from_secs(30));
+    mqttoptions.set_clean_session(!config.mqtt_persistent_session());
+    mqttoptions.set_transport(tls::client_transport(config.mqtt_tls())?);
+    let qos = if config.mqtt_qos() == 1 { QoS::AtLeastOnce } else { QoS::AtMostOnce };
 
     // Set credentials if configured
     if let (Some(username), Some(password)) = (config.mqtt_username(), config.mqtt_password()) {
         mqttoptions.set_credentials(username, password);
     }
 
+    // The eventloop only connects when polled, so with MQTT disabled nothing connects
+    let mqtt_enabled = config.mqtt_enabled();
     let (client, mut eventloop) = AsyncClient::new(mqttoptions, 100);
-    client.subscribe(config.mqtt_topic(), QoS::AtMostOnce).await?;
-
-    info!(topic = %config.mqtt_topic(), host = %config.mqtt_host(), port = %config.mqtt_port(), "MQTT client subscribed");
-
-    // Rate-limit drop warnings to 1 per second
-    let mut last_drop_warn = Instant::now() - Duration::from_secs(2);
-
-    // Position throttler for streaming positions at 10Hz with 5cm threshold
-    let mut position_throttler = PositionThrottler::new();
+    let sensors = config.sensors();
+    let mut topics: Vec<&str> = sensors.iter().map(|s| s.topic.as_str()).collect();
+    topics.sort_unstable();
+    topics.dedup();
+
+    let clock = SystemClock;
+    let mut pipeline = FramePipeline::new(config, &metrics, &event_tx, &sensor_health, &clock);
+    pipeline.egress_sender = egress_sender.as_ref();
+    pipeline.live_positions = live_positions.as_deref();
+    pipeline.restored_tracks = restored_tracks.as_deref();
+    pipeline.analysis_logger = analysis_logger.as_deref();
+
+    let flush_period = pipeline.reorder.as_ref().map_or(Duration::from_secs(1), |r| r.window() / 2);
+    let mut flush_interval = tokio::time::interval(flush_period.max(Duration::from_millis(10)));
+    flush_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
 
     loop {
         tokio::select! {
             // Check for shutdown signal
             _ = shutdown.changed() => {
                 if *shutdown.borrow() {
+                    // Frames held in the reorder window are still processed
+                    pipeline.drain_reorder();
                     info!("mqtt_shutdown");
                     return Ok(());
                 }
             }
+            // Release frames held longer than the reorder window
+            _ = flush_interval.tick(), if pipeline.reorder.is_some() => {
+                if !pipeline.flush_reorder(Instant::now()) {
+                    return Ok(());
+                }
+            }
+            // Config hot reload
+            reloaded = recv_config(&mut config_rx), if config_rx.is_some() => {
+                match reloaded {
+                    Some(reloaded) => pipeline.apply_config(&reloaded),
+                    None => config_rx = None,
+                }
+            }
+            // Messages from the HTTP push ingress
+            pushed = recv_pushed(&mut push_rx), if push_rx.is_some() => {
+                match pushed {
+                    Some(msg) => {
+                        if !pipeline.ingest(&msg.topic, &msg.payload, msg.received_at) {
+                            return Ok(());
+                        }
+                    }
+                    None => push_rx = None,
+                }
+            }
             // Process MQTT events
-            result = eventloop.poll() => {
+            result = eventloop.poll(), if mqtt_enabled => {
                 match result {
                     Ok(Event::Incoming(Packet::Publish(publish))) => {
                         let received_at = Instant::now();
-                        let topic = &publish.topic;
-                        let payload = std::str::from_utf8(&publish.payload);
-
-                        match payload {
+                        match std::str::from_utf8(&publish.payload) {
                             Ok(json_str) => {
-                                // Log to analysis file if enabled
-                                if let Some(ref logger) = analysis_logger {
-                                    // Try to parse for ts_event extraction
-                                    let parsed: Option<serde_json::Value> =
-                                        serde_json::from_str(json_str).ok();
-                                    logger.lock().log_mqtt(topic, json_str, parsed.as_ref());
-                                }
-
-                                // Parse and extract tracked objects for position streaming
-                                let (events, tracked_objects) = parse_xovis_message_with_positions(json_str, received_at);
-
-                                // Stream positions if egress sender is available and throttle allows
-                                if let Some(ref sender) = egress_sender {
-                                    if position_throttler.should_publish_batch() && !tracked_objects.is_empty() {
-                                        let ts = epoch_ms();
-                                        let mut published_any = false;
-
-                                        for obj in &tracked_objects {
-                                            // Skip GROUP tracks (high bit set)
-                                            if obj.track_id & XOVIS_GROUP_BIT != 0 {
-                                                continue;
-                                            }
-                                            if obj.position.len() >= 3 {
-                                                let pos = [obj.position[0], obj.position[1], obj.position[2]];
-                                                if position_throttler.should_publish_track(obj.track_id, pos) {
-                                                    sender.send_position(PositionPayload {
-                                                        site: None,
-                                                        ts,
-                                                        tid: obj.track_id,
-                                                        obj_type: obj.obj_type.clone(),
-                                                        x: pos[0],
-                                                        y: pos[1],
-                                                        z: pos[2],
-                                                        zone: None,
-                                                        auth: false,
-                                                        ctx: Some("continuous".to_string()),
-                                                    });
-                                                    published_any = true;
-                                                }
-                                            }
-                                        }
-
-                                        if published_any {
-                                            position_throttler.mark_published();
-                                        }
-
-                                        // Cleanup stale tracks periodically
-                                        let active_ids: Vec<i64> = tracked_objects.iter().map(|o| o.track_id).collect();
-                                        position_throttler.cleanup_stale(&active_ids);
-                                    }
-                                }
-
-                                if !events.is_empty() {
-                                    debug!(topic = %topic, event_count = %events.len(), "MQTT message with events");
-                                }
-                                for event in events {
-                                    // Skip GROUP tracks (high bit set) - same person as base track
-                                    if event.track_id.0 & XOVIS_GROUP_BIT != 0 {
-                                        continue;
-                                    }
-                                    debug!(track_id = %event.track_id, event_type = ?event.event_type, "Parsed event");
-                                    metrics.record_mqtt_event_received();
-                                    if let Err(e) = event_tx.try_send(event) {
-                                        match e {
-                                            TrySendError::Full(_) => {
-                                                metrics.record_mqtt_event_dropped();
-                                                if last_drop_warn.elapsed() > Duration::from_secs(1) {
-                                                    warn!("mqtt_event_dropped: channel full");
-                                                    last_drop_warn = Instant::now();
-                                                }
-                                            }
-                                            TrySendError::Closed(_) => {
-                                                warn!("Event channel closed");
-                                                return Ok(());
-                                            }
-                                        }
-                                    }
+                                if !pipeline.ingest(&publish.topic, json_str, received_at) {
+                                    return Ok(());
                                 }
                             }
                             Err(e) => {
@@ -214,12 +294,29 @@ pub async fn start_mqtt_client(
                             }
                         }
                     }
-                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
-                        info!("MQTT connected");
+                    Ok(Event::Incoming(Packet::ConnAck(connack))) => {
+                        info!(
+                            client_id = %config.mqtt_client_id(),
+                            session_present = %connack.session_present,
+                            "MQTT connected"
+                        );
+                        if let Some(down) = pipeline.gaps.connected(Instant::now()) {
+                            let down_ms = down.as_millis() as u64;
+                            metrics.record_mqtt_reconnect(down_ms);
+                            warn!(down_ms = %down_ms, session_present = %connack.session_present, "mqtt_reconnected");
+                        }
+                        // The broker keeps subscriptions only in a present session
+                        if !connack.session_present {
+                            for topic in &topics {
+                                client.try_subscribe(*topic, qos)?;
+                                info!(topic = %topic, qos = ?qos, host = %config.mqtt_host(), port = %config.mqtt_port(), "MQTT client subscribed");
+                            }
+                        }
                     }
                     Ok(_) => {}
                     Err(e) => {
                         error!(error = %e, "MQTT error");
+                        pipeline.gaps.disconnected(Instant::now());
                         tokio::time::sleep(Duration::from_secs(1)).await;
                     }
                 }
@@ -228,221 +325,448 @@ pub async fn start_mqtt_client(
     }
 }
 
-/// Parse a Xovis JSON message and extract events
-pub fn parse_xovis_message(json_str: &str, received_at: Instant) -> Vec<ParsedEvent> {
-    let (events, _) = parse_xovis_message_with_positions(json_str, received_at);
-    events
+/// Next reloaded config (None once the reloader is gone)
+async fn recv_config(rx: &mut Option<watch::Receiver<Config>>) -> Option<Config> {
+    let rx = rx.as_mut()?;
+    rx.changed().await.ok()?;
+    Some(rx.borrow_and_update().clone())
 }
 
-/// Parse a Xovis JSON message and extract both events and tracked objects
-/// Returns (events, tracked_objects) for position streaming
-fn parse_xovis_message_with_positions(
-    json_str: &str,
-    received_at: Instant,
-) -> (Vec<ParsedEvent>, Vec<TrackedObject>) {
-    let mut parsed_events = Vec::with_capacity(8);
-    let mut all_tracked_objects = Vec::with_capacity(16);
-
-    let message: XovisMessage = match serde_json::from_str(json_str) {
-        Ok(m) => m,
-        Err(e) => {
-            debug!(error = %e, "Failed to parse Xovis message");
-            return (parsed_events, all_tracked_objects);
-        }
-    };
-
-    let Some(live_data) = message.live_data else {
-        return (parsed_events, all_tracked_objects);
-    };
-
-    for frame in live_data.frames {
-        parsed_events.extend(parse_frame(&frame, received_at));
-        // Collect all tracked objects from all frames
-        all_tracked_objects.extend(frame.tracked_objects.into_iter());
+async fn recv_pushed(rx: &mut Option<mpsc::Receiver<PushedMessage>>) -> Option<PushedMessage> {
+    match rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
     }
-
-    (parsed_events, all_tracked_objects)
 }
 
-/// Parse ISO 8601 timestamp to epoch milliseconds
-fn parse_iso_time(time_str: &str) -> Option<u64> {
-    // Parse "2026-01-05T16:41:30.048+00:00" format (RFC 3339)
-    OffsetDateTime::parse(time_str, &Rfc3339)
-        .ok()
-        .map(|dt| (dt.unix_timestamp_nanos() / 1_000_000) as u64)
+/// Sensor message ingest: analysis logging, liveness, reorder window,
+/// store-frame mapping, derived events, position streaming and forwarding to
+/// the tracker
+pub(crate) struct FramePipeline<'a> {
+    sensors: &'a [Sensor],
+    /// Payload parser per sensor (config order)
+    adapters: Vec<Box<dyn SensorAdapter>>,
+    metrics: &'a Metrics,
+    event_tx: &'a mpsc::Sender<ParsedEvent>,
+    pub(crate) egress_sender: Option<&'a EgressSender>,
+    pub(crate) live_positions: Option<&'a LivePositions>,
+    /// Restored tracks still to be seen after a snapshot restore
+    pub(crate) restored_tracks: Option<&'a RestoredTracks>,
+    sensor_health: &'a SensorHealth,
+    pub(crate) analysis_logger: Option<&'a Mutex<AnalysisLogger>>,
+    gaps: ReconnectGaps,
+    reorder: Option<FrameReorder<SensorFrame>>,
+    object_filter: Option<ObjectTypeFilter>,
+    zone_engine: Option<ZoneEngine>,
+    gate_triggers: Option<GateTriggerDetector>,
+    groups: Option<GroupDetector>,
+    position_throttler: PositionThrottler,
+    last_drop_warn: Instant,
+    /// Wall-clock source for sensor health and derived events
+    clock: &'a dyn Clock,
 }
 
-/// Extract epoch milliseconds from TimestampValue
-fn timestamp_to_epoch_ms(ts: &TimestampValue) -> u64 {
-    match ts {
-        TimestampValue::EpochMs(ms) => *ms,
-        TimestampValue::IsoString(s) => parse_iso_time(s).unwrap_or(0),
-        TimestampValue::None => 0,
+impl<'a> FramePipeline<'a> {
+    /// Pipeline for the sensors in `config`, without egress, live positions
+    /// or analysis logging (set the fields to enable them)
+    pub(crate) fn new(
+        config: &'a Config,
+        metrics: &'a Arc<Metrics>,
+        event_tx: &'a mpsc::Sender<ParsedEvent>,
+        sensor_health: &'a SensorHealth,
+        clock: &'a dyn Clock,
+    ) -> Self {
+        let sensors = config.sensors();
+        Self {
+            sensors,
+            adapters: sensors.iter().map(|s| sensor_adapter::for_protocol(s.protocol)).collect(),
+            metrics,
+            event_tx,
+            egress_sender: None,
+            live_positions: None,
+            restored_tracks: None,
+            sensor_health,
+            analysis_logger: None,
+            gaps: ReconnectGaps::new(sensors.len()),
+            // Frame reorder window; held frames are flushed when a sensor goes quiet
+            reorder: FrameReorder::new(config, metrics.clone()),
+            // Object type allow/deny lists
+            object_filter: ObjectTypeFilter::new(config, metrics.clone()),
+            // Position-driven zone engine (fallback for missed zone events)
+            zone_engine: ZoneEngine::new(config, metrics.clone()),
+            // Virtual pre-gate triggers (open the gate before the sensor's GATE zone entry)
+            gate_triggers: GateTriggerDetector::new(config),
+            // Xovis GROUP membership of individual tracks
+            groups: GroupDetector::new(config),
+            // Position throttler for streaming positions at 10Hz with 5cm threshold
+            position_throttler: PositionThrottler::new(),
+            // Rate-limit drop warnings to 1 per second
+            last_drop_warn: Instant::now() - Duration::from_secs(2),
+            clock,
+        }
     }
-}
-
-fn parse_frame(frame: &Frame, received_at: Instant) -> Vec<ParsedEvent> {
-    let mut events = Vec::with_capacity(8);
 
-    // Extract event time from frame timestamp (handles both ISO string and epoch ms)
-    let event_time = timestamp_to_epoch_ms(&frame.time);
+    /// Apply a reloaded config: virtual gate triggers follow the reloaded gate zone
+    pub(crate) fn apply_config(&mut self, config: &Config) {
+        match self.gate_triggers.as_mut() {
+            Some(detector) if !config.gate_triggers().is_empty() => {
+                detector.set_triggers(config.gate_triggers())
+            }
+            _ => self.gate_triggers = GateTriggerDetector::new(config),
+        }
+    }
 
-    for xovis_event in &frame.events {
-        let event_type: EventType = xovis_event.event_type.parse().unwrap();
+    /// Ingest one sensor message (MQTT publish or HTTP push) on `topic`
+    ///
+    /// Returns false if the event channel is closed.
+    pub(crate) fn ingest(&mut self, topic: &str, json_str: &str, received_at: Instant) -> bool {
+        // Pushed messages are logged like MQTT ones so analysis tooling sees one stream
+        if let Some(logger) = self.analysis_logger {
+            // Try to parse for ts_event extraction
+            let parsed: Option<serde_json::Value> = serde_json::from_str(json_str).ok();
+            logger.lock().log_mqtt(topic, json_str, parsed.as_ref());
+        }
 
-        let Some(attrs) = xovis_event.attributes.as_ref() else { continue };
-        let Some(track_id) = attrs.track_id else { continue };
+        let Some(index) = self.sensors.iter().position(|s| s.matches_topic(topic)) else {
+            debug!(topic = %topic, "mqtt_message_unknown_sensor");
+            return true;
+        };
+        let frames = self.adapters[index].parse(json_str, received_at);
+        if let Some(gap) = self.gaps.observe(index, &frames) {
+            if !self.report_gap(index, gap, received_at) {
+                return false;
+            }
+        }
+        let now_ms = self.clock.epoch_ms();
+        let has_events = frames.iter().any(|f| !f.events.is_empty());
+        self.sensor_health.record_frame(index, now_ms, has_events);
+        record_sensor_lag(self.metrics, self.sensor_health, index, &frames, now_ms);
+
+        let frames = match self.reorder.as_mut() {
+            Some(reorder) => frames
+                .into_iter()
+                .flat_map(|frame| reorder.push(index, frame.time_ms, frame, received_at))
+                .map(|r| (r.frame, r.received_at))
+                .collect(),
+            None => frames.into_iter().map(|f| (f, received_at)).collect(),
+        };
+        self.process(index, frames)
+    }
 
-        // Linear search for position - frames typically have <10 tracked objects
-        let position = frame
-            .tracked_objects
-            .iter()
-            .find(|obj| obj.track_id == track_id)
-            .filter(|obj| obj.position.len() >= 3)
-            .map(|obj| [obj.position[0], obj.position[1], obj.position[2]]);
+    /// Release frames held longer than the reorder window
+    ///
+    /// Returns false if the event channel is closed.
+    pub(crate) fn flush_reorder(&mut self, now: Instant) -> bool {
+        let released = self.reorder.as_mut().map(|r| r.flush(now)).unwrap_or_default();
+        for frame in released {
+            if !self.process(frame.sensor, vec![(frame.frame, frame.received_at)]) {
+                return false;
+            }
+        }
+        true
+    }
 
-        events.push(ParsedEvent {
-            event_type,
-            track_id: TrackId(track_id),
-            geometry_id: attrs.geometry_id.map(GeometryId),
-            direction: attrs.direction.clone(),
-            event_time,
-            received_at,
-            position,
-        });
+    /// Release every frame held in the reorder window (shutdown)
+    pub(crate) fn drain_reorder(&mut self) {
+        let released = self.reorder.as_mut().map(|r| r.drain()).unwrap_or_default();
+        for frame in released {
+            if !self.process(frame.sensor, vec![(frame.frame, frame.received_at)]) {
+                return;
+            }
+        }
     }
 
-    events
-}
+    /// Process frames of sensor `index` in order
+    ///
+    /// Returns false if the event channel is closed.
+    fn process(&mut self, index: usize, frames: Vec<(SensorFrame, Instant)>) -> bool {
+        let Some(received_at) = frames.last().map(|(_, received_at)| *received_at) else {
+            return true;
+        };
+        let mut events = Vec::with_capacity(8);
+        let mut tracked_objects = Vec::with_capacity(16);
+        for (frame, _) in frames {
+            events.extend(frame.events);
+            tracked_objects.extend(frame.objects);
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        // Map into the store frame of the sensor
+        let sensor = &self.sensors[index];
+        apply_sensor(sensor, &mut events, &mut tracked_objects);
+        if let Some(ref mut filter) = self.object_filter {
+            filter.process(&mut events, &mut tracked_objects);
+        }
+        if let Some(live) = self.live_positions {
+            publish_live_positions(live, &events, &tracked_objects, received_at);
+        }
+        if let Some(restored) = self.restored_tracks {
+            restored.seen(tracked_objects.iter().map(|o| TrackId(o.track_id)));
+        }
 
-    #[test]
-    fn test_parse_zone_entry() {
-        let json = r#"{
-            "live_data": {
-                "frames": [{
-                    "time": "2026-01-05T16:41:30.048+00:00",
-                    "tracked_objects": [{
-                        "track_id": 123,
-                        "type": "PERSON",
-                        "position": [1.5, 2.0, 1.7]
-                    }],
-                    "events": [{
-                        "type": "ZONE_ENTRY",
-                        "attributes": {
-                            "track_id": 123,
-                            "geometry_id": 1001
-                        }
-                    }]
-                }]
+        if let Some(ref mut engine) = self.zone_engine {
+            events = engine.process(events, &tracked_objects, self.clock.epoch_ms(), received_at);
+        }
+        if let Some(ref mut detector) = self.gate_triggers {
+            let triggered =
+                detector.process(&events, &tracked_objects, self.clock.epoch_ms(), received_at);
+            events.extend(triggered);
+        }
+        if let Some(ref mut groups) = self.groups {
+            let membership =
+                groups.process(&events, &tracked_objects, self.clock.epoch_ms(), received_at);
+            events.extend(membership);
+        }
+
+        // Stream positions if egress sender is available and throttle allows
+        if let Some(sender) = self.egress_sender {
+            self.stream_positions(sender, &tracked_objects);
+        }
+
+        if !events.is_empty() {
+            debug!(sensor = %sensor.name, event_count = %events.len(), "MQTT message with events");
+        }
+        for event in events {
+            // Skip GROUP tracks (high bit set) - modeled as membership events instead
+            if event.track_id.is_group() {
+                continue;
+            }
+            debug!(track_id = %event.track_id, event_type = ?event.event_type, "Parsed event");
+            self.metrics.record_mqtt_event_received();
+            if !self.forward(event) {
+                return false;
             }
-        }"#;
-
-        let events = parse_xovis_message(json, Instant::now());
-        assert_eq!(events.len(), 1);
-        assert_eq!(events[0].track_id, TrackId(123));
-        assert_eq!(events[0].event_type, EventType::ZoneEntry);
-        assert_eq!(events[0].geometry_id, Some(GeometryId(1001)));
-        // event_time should now be parsed from ISO 8601
-        assert!(events[0].event_time > 0, "event_time should be parsed from ISO timestamp");
-        // Position should be extracted
-        assert_eq!(events[0].position, Some([1.5, 2.0, 1.7]));
+        }
+        true
     }
 
-    #[test]
-    fn test_parse_track_create() {
-        let json = r#"{
-            "live_data": {
-                "frames": [{
-                    "time": "2026-01-05T16:40:00.000+00:00",
-                    "events": [{
-                        "type": "TRACK_CREATE",
-                        "attributes": {
-                            "track_id": 100
-                        }
-                    }]
-                }]
-            }
-        }"#;
+    /// Account a reconnect gap and let the tracker flag overlapping journeys
+    ///
+    /// Returns false if the event channel is closed.
+    fn report_gap(&mut self, index: usize, gap: IngressGap, received_at: Instant) -> bool {
+        let gap_ms = gap.to_ms - gap.from_ms;
+        self.metrics.record_ingress_gap(gap_ms, gap.events_missed);
+        warn!(
+            sensor = %self.sensors[index].name,
+            gap_ms = %gap_ms,
+            events_missed_est = %gap.events_missed,
+            "mqtt_ingress_gap"
+        );
+        self.forward(ParsedEvent {
+            event_type: EventType::IngressGap(gap.from_ms),
+            track_id: TrackId(0),
+            geometry_id: None,
+            direction: None,
+            event_time: gap.to_ms,
+            received_at,
+            position: None,
+            derived: false,
+            obj_type: None,
+        })
+    }
 
-        let events = parse_xovis_message(json, Instant::now());
-        assert_eq!(events.len(), 1);
-        assert_eq!(events[0].track_id, TrackId(100));
-        assert_eq!(events[0].event_type, EventType::TrackCreate);
+    /// Send an event to the tracker (dropped if the channel is full)
+    ///
+    /// Returns false if the event channel is closed.
+    fn forward(&mut self, event: ParsedEvent) -> bool {
+        match self.event_tx.try_send(event) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                self.metrics.record_mqtt_event_dropped();
+                if self.last_drop_warn.elapsed() > Duration::from_secs(1) {
+                    warn!("mqtt_event_dropped: channel full");
+                    self.last_drop_warn = Instant::now();
+                }
+                true
+            }
+            Err(TrySendError::Closed(_)) => {
+                warn!("Event channel closed");
+                false
+            }
+        }
     }
 
-    #[test]
-    fn test_parse_line_cross() {
-        let json = r#"{
-            "live_data": {
-                "frames": [{
-                    "time": "2026-01-05T16:42:00.000+00:00",
-                    "events": [{
-                        "type": "LINE_CROSS_FORWARD",
-                        "attributes": {
-                            "track_id": 100,
-                            "geometry_id": 1006,
-                            "direction": "forward"
-                        }
-                    }]
-                }]
+    fn stream_positions(&mut self, sender: &EgressSender, tracked_objects: &[TrackedObject]) {
+        if !self.position_throttler.should_publish_batch() || tracked_objects.is_empty() {
+            return;
+        }
+        let ts = self.clock.epoch_ms();
+        let mut published_any = false;
+
+        for obj in tracked_objects {
+            // Skip GROUP tracks (high bit set)
+            if TrackId(obj.track_id).is_group() {
+                continue;
+            }
+            if obj.position.len() >= 3 {
+                let pos = [obj.position[0], obj.position[1], obj.position[2]];
+                if self.position_throttler.should_publish_track(obj.track_id, pos, ts) {
+                    sender.send_position(PositionPayload {
+                        site: None,
+                        ts,
+                        tid: obj.track_id,
+                        obj_type: obj.obj_type.to_string(),
+                        x: pos[0],
+                        y: pos[1],
+                        z: pos[2],
+                        zone: None,
+                        auth: false,
+                        ctx: Some("continuous".to_string()),
+                    });
+                    published_any = true;
+                }
             }
-        }"#;
+        }
 
-        let events = parse_xovis_message(json, Instant::now());
-        assert_eq!(events.len(), 1);
-        assert_eq!(events[0].event_type, EventType::LineCrossForward);
-        assert_eq!(events[0].geometry_id, Some(GeometryId(1006)));
+        if published_any {
+            self.position_throttler.mark_published();
+        }
+
+        // Cleanup stale tracks periodically
+        self.position_throttler.cleanup_stale(ts);
     }
+}
 
-    #[test]
-    fn test_parse_multiple_events() {
-        let json = r#"{
-            "live_data": {
-                "frames": [{
-                    "time": "2026-01-05T16:41:30.000+00:00",
-                    "events": [
-                        {"type": "ZONE_EXIT", "attributes": {"track_id": 100, "geometry_id": 1001}},
-                        {"type": "ZONE_ENTRY", "attributes": {"track_id": 100, "geometry_id": 1007}}
-                    ]
-                }]
-            }
-        }"#;
+/// Map a message from `sensor` into the store frame
+///
+/// Namespaces track IDs per sensor, transforms positions and remaps geometry IDs.
+fn apply_sensor(sensor: &Sensor, events: &mut [ParsedEvent], objects: &mut [TrackedObject]) {
+    if sensor.is_passthrough() {
+        return;
+    }
+    for event in events.iter_mut() {
+        event.track_id = sensor.track_id(event.track_id.0);
+        event.geometry_id = event.geometry_id.map(|id| sensor.map_geometry(id));
+        if let Some(pos) = event.position.as_mut() {
+            (pos[0], pos[1]) = sensor.to_store(pos[0], pos[1]);
+        }
+    }
+    for obj in objects.iter_mut() {
+        obj.track_id = sensor.track_id(obj.track_id).0;
+        if obj.position.len() >= 2 {
+            (obj.position[0], obj.position[1]) = sensor.to_store(obj.position[0], obj.position[1]);
+        }
+    }
+}
 
-        let events = parse_xovis_message(json, Instant::now());
-        assert_eq!(events.len(), 2);
-        assert_eq!(events[0].event_type, EventType::ZoneExit);
-        assert_eq!(events[1].event_type, EventType::ZoneEntry);
+/// Record receive-time minus event-time lag of sensor events (input to the skew estimate)
+///
+/// Measured on arrival, before the reorder window holds frames back.
+fn record_sensor_lag(
+    metrics: &Metrics,
+    health: &SensorHealth,
+    index: usize,
+    frames: &[SensorFrame],
+    now_ms: u64,
+) {
+    for frame in frames {
+        let event_time = frame.time_ms;
+        if event_time == 0 {
+            continue;
+        }
+        let lag_ms = now_ms as i64 - event_time as i64;
+        for _ in &frame.events {
+            metrics.record_sensor_lag(lag_ms.max(0) as u64);
+            health.record_lag(index, lag_ms);
+        }
+    }
+}
+
+/// Publish store-frame positions of individual tracks for cross-sensor handover
+fn publish_live_positions(
+    live: &LivePositions,
+    events: &[ParsedEvent],
+    objects: &[TrackedObject],
+    now: Instant,
+) {
+    for obj in objects {
+        if !TrackId(obj.track_id).is_group() && obj.position.len() >= 3 {
+            let pos = [obj.position[0], obj.position[1], obj.position[2]];
+            live.update(TrackId(obj.track_id), pos, now);
+        }
+    }
+    for event in events {
+        if event.event_type == EventType::TrackDelete {
+            live.remove(event.track_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::stale_tracks::STALE_TRACK_MS;
+
+    fn frame(time_ms: u64, event_count: usize) -> SensorFrame {
+        let events = (0..event_count)
+            .map(|i| ParsedEvent {
+                event_type: EventType::ZoneEntry,
+                track_id: TrackId(i as i64),
+                geometry_id: None,
+                direction: None,
+                event_time: time_ms,
+                received_at: Instant::now(),
+                position: None,
+                derived: false,
+                obj_type: None,
+            })
+            .collect();
+        SensorFrame { time_ms, events, objects: Vec::new() }
     }
 
     #[test]
-    fn test_parse_invalid_json() {
-        let events = parse_xovis_message("not json", Instant::now());
-        assert!(events.is_empty());
+    fn test_reconnect_gap_estimates_missed_events() {
+        let mut gaps = ReconnectGaps::new(1);
+        let start = Instant::now();
+        assert!(gaps.connected(start).is_none());
+
+        // 2 events per 100ms frame = 20 events/s
+        for i in 0..200 {
+            assert!(gaps.observe(0, &[frame(1_000_000 + i * 100, 2)]).is_none());
+        }
+
+        gaps.disconnected(start + Duration::from_secs(1));
+        let down = gaps.connected(start + Duration::from_secs(6));
+        assert_eq!(down, Some(Duration::from_secs(5)));
+
+        let gap = gaps.observe(0, &[frame(1_024_900, 2)]).unwrap();
+        assert_eq!(gap.from_ms, 1_019_900);
+        assert_eq!(gap.to_ms, 1_024_900);
+        assert!((80..=100).contains(&gap.events_missed), "missed {}", gap.events_missed);
+
+        // Only the first frame after the reconnect can open a gap
+        assert!(gaps.observe(0, &[frame(1_030_000, 2)]).is_none());
     }
 
     #[test]
-    fn test_parse_empty_frames() {
-        let json = r#"{"live_data": {"frames": []}}"#;
-        let events = parse_xovis_message(json, Instant::now());
-        assert!(events.is_empty());
+    fn test_position_throttle_kept_across_sensors() {
+        let mut throttler = PositionThrottler::new();
+        // Track 1 from sensor A, track 2 from sensor B, in alternating messages
+        assert!(throttler.should_publish_track(1, [1.0, 1.0, 1.7], 1_000));
+        throttler.cleanup_stale(1_000);
+        assert!(throttler.should_publish_track(2, [5.0, 5.0, 1.7], 1_100));
+        throttler.cleanup_stale(1_100);
+
+        // Sensor B's message did not reset track 1: a 1cm move is still throttled
+        assert!(!throttler.should_publish_track(1, [1.01, 1.0, 1.7], 1_200));
+        throttler.cleanup_stale(1_200);
+        assert!(!throttler.should_publish_track(2, [5.01, 5.0, 1.7], 1_300));
+        assert!(throttler.should_publish_track(2, [5.1, 5.0, 1.7], 1_400));
+
+        // Track 1 is forgotten once it has not been seen for STALE_TRACK_MS
+        throttler.cleanup_stale(1_200 + STALE_TRACK_MS);
+        assert!(!throttler.last_positions.contains_key(&1));
+        assert!(throttler.last_positions.contains_key(&2));
     }
 
     #[test]
-    fn test_parse_iso_time() {
-        // Test RFC 3339 format parsing
-        let ts = parse_iso_time("2026-01-05T16:41:30.048+00:00");
-        assert!(ts.is_some());
-        let ms = ts.unwrap();
-        // 2026-01-05T16:41:30.048Z should be around 1767630090048 ms
-        assert!(ms > 1767000000000, "timestamp should be in 2026");
-        assert!(ms < 1800000000000, "timestamp should be before 2027");
-
-        // Test invalid input
-        assert!(parse_iso_time("not a timestamp").is_none());
-        assert!(parse_iso_time("").is_none());
+    fn test_replayed_session_has_no_gap() {
+        let mut gaps = ReconnectGaps::new(1);
+        let start = Instant::now();
+        gaps.observe(0, &[frame(1_000_000, 1)]);
+
+        gaps.disconnected(start);
+        gaps.connected(start + Duration::from_secs(5));
+        // The broker replays queued frames: frame time continues without a jump
+        assert!(gaps.observe(0, &[frame(1_000_100, 1), frame(1_000_200, 1)]).is_none());
     }
 }
@@ -6,12 +6,16 @@
 //! - gateway/metrics - Periodic metrics snapshots (QoS 0)
 //! - gateway/gate - Gate state changes (QoS 0)
 //! - gateway/tracks - Track lifecycle events (QoS 0)
+//! - gateway/sensors - Sensor offline/online changes (QoS 1)
+//! - gateway/shadow - Shadow policy decisions differing from the live ones (QoS 1)
 
 use crate::infra::config::Config;
+use crate::infra::tls;
 use crate::io::egress_channel::EgressMessage;
-use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS};
+use rumqttc::{AsyncClient, Event, MqttOptions, Outgoing, Packet, QoS};
 use std::time::Duration;
 use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
 /// MQTT publisher actor
@@ -19,6 +23,8 @@ use tracing::{debug, error, info, warn};
 /// Receives messages from the egress channel and publishes to MQTT topics.
 pub struct MqttPublisher {
     client: AsyncClient,
+    /// Connection task; ends once the shutdown disconnect has been sent
+    eventloop_task: JoinHandle<()>,
     rx: mpsc::Receiver<EgressMessage>,
     host: String,
     port: u16,
@@ -29,6 +35,8 @@ pub struct MqttPublisher {
     tracks_topic: String,
     acc_topic: String,
     positions_topic: String,
+    sensors_topic: String,
+    shadow_topic: String,
 }
 
 impl MqttPublisher {
@@ -36,12 +44,14 @@ impl MqttPublisher {
     ///
     /// Connects to the broker at the configured MQTT egress host/port.
     /// Falls back to main MQTT host/port if egress-specific settings not configured.
-    pub fn new(config: &Config, rx: mpsc::Receiver<EgressMessage>) -> Self {
+    /// Connects over TLS if `[mqtt_egress.tls]` is enabled (fails if its files can't be read).
+    pub fn new(config: &Config, rx: mpsc::Receiver<EgressMessage>) -> anyhow::Result<Self> {
         let client_id = format!("gateway-egress-{}", std::process::id());
         let mut mqttoptions =
             MqttOptions::new(client_id, config.mqtt_egress_host(), config.mqtt_egress_port());
         mqttoptions.set_keep_alive(Duration::from_secs(30));
         mqttoptions.set_clean_session(true);
+        mqttoptions.set_transport(tls::client_transport(config.mqtt_egress_tls())?);
 
         // Set credentials if configured (uses egress-specific or falls back to main mqtt creds)
         if let (Some(username), Some(password)) =
@@ -53,7 +63,7 @@ impl MqttPublisher {
         let (client, eventloop) = AsyncClient::new(mqttoptions, 100);
 
         // Spawn the eventloop handler
-        tokio::spawn(async move {
+        let eventloop_task = tokio::spawn(async move {
             let mut eventloop = eventloop;
             loop {
                 match eventloop.poll().await {
@@ -64,6 +74,8 @@ impl MqttPublisher {
                         // QoS 1 acknowledgement received
                         debug!("mqtt_egress_puback");
                     }
+                    // Requests are sent in order, so everything published before it is out
+                    Ok(Event::Outgoing(Outgoing::Disconnect)) => break,
                     Ok(_) => {}
                     Err(e) => {
                         warn!(error = %e, "mqtt_egress_error");
@@ -73,8 +85,9 @@ impl MqttPublisher {
             }
         });
 
-        Self {
+        Ok(Self {
             client,
+            eventloop_task,
             rx,
             host: config.mqtt_egress_host().to_string(),
             port: config.mqtt_egress_port(),
@@ -85,13 +98,16 @@ impl MqttPublisher {
             tracks_topic: config.mqtt_egress_tracks_topic().to_string(),
             acc_topic: config.mqtt_egress_acc_topic().to_string(),
             positions_topic: config.mqtt_egress_positions_topic().to_string(),
-        }
+            sensors_topic: config.mqtt_egress_sensors_topic().to_string(),
+            shadow_topic: config.mqtt_egress_shadow_topic().to_string(),
+        })
     }
 
     /// Run the publisher loop
     ///
     /// Processes messages from the channel and publishes to MQTT.
-    /// Runs until shutdown signal is received.
+    /// Runs until shutdown signal is received, then publishes queued messages
+    /// and disconnects once they have been sent.
     pub async fn run(mut self, mut shutdown: watch::Receiver<bool>) {
         info!(
             host = %self.host,
@@ -102,6 +118,8 @@ impl MqttPublisher {
             gate = %self.gate_topic,
             acc = %self.acc_topic,
             positions = %self.positions_topic,
+            sensors = %self.sensors_topic,
+            shadow = %self.shadow_topic,
             "mqtt_egress_started"
         );
 
@@ -112,9 +130,15 @@ impl MqttPublisher {
                     if *shutdown.borrow() {
                         info!("mqtt_egress_shutdown");
                         // Drain remaining messages
+                        let mut drained = 0usize;
                         while let Ok(msg) = self.rx.try_recv() {
                             self.publish_message(msg).await;
+                            drained += 1;
+                        }
+                        if self.client.disconnect().await.is_ok() {
+                            let _ = self.eventloop_task.await;
                         }
+                        info!(drained = %drained, "mqtt_egress_stopped");
                         return;
                     }
                 }
@@ -210,6 +234,30 @@ impl MqttPublisher {
                     }
                 }
             }
+            EgressMessage::SensorState(payload) => {
+                // Use QoS 1 for sensor liveness (rare, operationally important)
+                if let Ok(json) = serde_json::to_string(&payload) {
+                    if let Err(e) = self
+                        .client
+                        .publish(&self.sensors_topic, QoS::AtLeastOnce, false, json.as_bytes())
+                        .await
+                    {
+                        error!(error = %e, "mqtt_egress_sensor_state_failed");
+                    }
+                }
+            }
+            EgressMessage::ShadowDiff(payload) => {
+                // Use QoS 1 for shadow diffs (rare, the record of the trial)
+                if let Ok(json) = serde_json::to_string(&payload) {
+                    if let Err(e) = self
+                        .client
+                        .publish(&self.shadow_topic, QoS::AtLeastOnce, false, json.as_bytes())
+                        .await
+                    {
+                        warn!(error = %e, "mqtt_egress_shadow_diff_failed");
+                    }
+                }
+            }
         }
     }
 }
@@ -5,22 +5,26 @@
 
 use crate::domain::types::{DoorStatus, EventType, ParsedEvent, TrackId};
 use crate::infra::metrics::{
-    Metrics, MetricsSummary, METRICS_BUCKET_BOUNDS, METRICS_NUM_BUCKETS, METRICS_STITCH_DIST_BOUNDS,
+    Metrics, MetricsSummary, SensorLiveness, METRICS_BUCKET_BOUNDS, METRICS_NUM_BUCKETS,
+    METRICS_STITCH_DIST_BOUNDS,
 };
+use crate::services::config_reload::ReloadRequest;
 use crate::services::gate::GateCommand;
 use bytes::Bytes;
 use http_body_util::Full;
+use hyper::header::AUTHORIZATION;
 use hyper::server::conn::http1;
 use hyper::service::service_fn;
-use hyper::{Method, Request, Response, StatusCode};
+use hyper::{HeaderMap, Method, Request, Response, StatusCode};
 use hyper_util::rt::TokioIo;
 use std::convert::Infallible;
 use std::fmt::Write;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Instant;
+use subtle::ConstantTimeEq;
 use tokio::net::TcpListener;
-use tokio::sync::{mpsc, watch};
+use tokio::sync::{mpsc, oneshot, watch};
 use tracing::{error, info, warn};
 
 /// Prometheus metric type
@@ -103,6 +107,8 @@ fn format_prometheus_metrics(
     write_pos_occupancy(&mut output, site_id, metrics);
     write_acc_metrics(&mut output, site_id, &summary);
     write_stitch_metrics(&mut output, site_id, &summary);
+    write_zone_engine_metrics(&mut output, site_id, &summary);
+    write_sensor_metrics(&mut output, site_id, &summary, metrics);
     write_drop_metrics(&mut output, site_id, &summary);
     write_queue_metrics(&mut output, site_id, &summary);
 
@@ -210,6 +216,40 @@ fn write_gate_metrics(
         site,
         summary.gate_state,
     );
+    write_metric(
+        output,
+        "gateway_gate_dry_run",
+        "1 while gate commands are logged instead of sent (dry run)",
+        MetricType::Gauge,
+        site,
+        summary.gate_dry_run as u64,
+    );
+
+    write_metric(
+        output,
+        "gateway_gate_triggers_total",
+        "Virtual pre-gate triggers fired",
+        MetricType::Counter,
+        site,
+        summary.gate_triggers_total,
+    );
+    write_metric(
+        output,
+        "gateway_gate_trigger_opens_total",
+        "Gate commands started by a virtual pre-gate trigger",
+        MetricType::Counter,
+        site,
+        summary.gate_trigger_opens_total,
+    );
+    write_histogram(
+        output,
+        "gateway_gate_trigger_lead_ms",
+        "Virtual trigger lead over GATE zone entry in milliseconds",
+        site,
+        &summary.gate_trigger_lead_buckets,
+        &METRICS_BUCKET_BOUNDS,
+        summary.gate_trigger_lead_avg_ms,
+    );
 
     // Check for long opens while scraping (catches in-progress long opens)
     metrics.check_gate_long_open();
@@ -328,6 +368,48 @@ fn write_acc_metrics(output: &mut String, site: &str, summary: &MetricsSummary)
         site,
         summary.acc_no_journey_total,
     );
+    write_metric(
+        output,
+        "gateway_acc_group_extensions_total",
+        "Tracks authorized through the payer's Xovis group",
+        MetricType::Counter,
+        site,
+        summary.acc_group_extensions_total,
+    );
+    write_metric(
+        output,
+        "gateway_auth_inherited_total",
+        "Tracks inheriting authorization from an adjacent track (object type policy)",
+        MetricType::Counter,
+        site,
+        summary.auth_inherited_total,
+    );
+
+    // Shadow policy agreement, reset at UTC midnight
+    write_metric(
+        output,
+        "gateway_shadow_agree_today",
+        "Shadow policy decisions agreeing with the live policy today",
+        MetricType::Gauge,
+        site,
+        summary.shadow_agree_today,
+    );
+    write_metric(
+        output,
+        "gateway_shadow_would_open_today",
+        "Gate decisions the shadow policy would have opened but the live policy blocked today",
+        MetricType::Gauge,
+        site,
+        summary.shadow_would_open_today,
+    );
+    write_metric(
+        output,
+        "gateway_shadow_would_block_today",
+        "Gate decisions the shadow policy would have blocked but the live policy opened today",
+        MetricType::Gauge,
+        site,
+        summary.shadow_would_block_today,
+    );
 
     // ACC empty POS timing - diagnose payment timing issues
     write_histogram(
@@ -382,6 +464,14 @@ fn write_stitch_metrics(output: &mut String, site: &str, summary: &MetricsSummar
         site,
         summary.stitch_expired_total,
     );
+    write_metric(
+        output,
+        "gateway_stitch_handover_total",
+        "Tracks handed over to another sensor's live track",
+        MetricType::Counter,
+        site,
+        summary.stitch_handover_total,
+    );
 
     write_histogram(
         output,
@@ -420,6 +510,154 @@ fn write_stitch_metrics(output: &mut String, site: &str, summary: &MetricsSummar
     );
 }
 
+fn write_zone_engine_metrics(output: &mut String, site: &str, summary: &MetricsSummary) {
+    write_metric(
+        output,
+        "gateway_zone_events_derived_total",
+        "Zone entry/exit events synthesized from positions",
+        MetricType::Counter,
+        site,
+        summary.zone_events_derived_total,
+    );
+    write_metric(
+        output,
+        "gateway_zone_events_deduped_total",
+        "Sensor zone events dropped as duplicates of derived events",
+        MetricType::Counter,
+        site,
+        summary.zone_events_deduped_total,
+    );
+}
+
+/// Write a gauge with one sample per sensor
+fn write_sensor_gauge(
+    output: &mut String,
+    name: &str,
+    help: &str,
+    site: &str,
+    liveness: &[SensorLiveness],
+    value: impl Fn(&SensorLiveness) -> i64,
+) {
+    let _ = writeln!(output, "# HELP {name} {help}");
+    let _ = writeln!(output, "# TYPE {name} gauge");
+    for sensor in liveness {
+        let _ = writeln!(
+            output,
+            "{name}{{site=\"{site}\",sensor=\"{}\"}} {}",
+            sensor.sensor_id,
+            value(sensor)
+        );
+    }
+}
+
+fn write_sensor_metrics(
+    output: &mut String,
+    site: &str,
+    summary: &MetricsSummary,
+    metrics: &Metrics,
+) {
+    let liveness = metrics.sensor_liveness();
+    write_sensor_gauge(
+        output,
+        "gateway_sensor_frame_age_ms",
+        "Time since the last sensor frame in ms",
+        site,
+        &liveness,
+        |s| s.frame_age_ms as i64,
+    );
+    write_sensor_gauge(
+        output,
+        "gateway_sensor_event_age_ms",
+        "Time since the last sensor event in ms",
+        site,
+        &liveness,
+        |s| s.event_age_ms as i64,
+    );
+    write_sensor_gauge(
+        output,
+        "gateway_sensor_online",
+        "Sensor liveness (1=online, 0=offline)",
+        site,
+        &liveness,
+        |s| s.online as i64,
+    );
+    write_sensor_gauge(
+        output,
+        "gateway_sensor_clock_skew_ms",
+        "Estimated sensor clock skew (min receive time minus event time) in ms",
+        site,
+        &liveness,
+        |s| s.clock_skew_ms,
+    );
+    write_histogram(
+        output,
+        "gateway_sensor_lag_ms",
+        "Sensor lag (receive wall clock minus Xovis event time) in ms",
+        site,
+        &summary.sensor_lag_buckets,
+        &METRICS_BUCKET_BOUNDS,
+        summary.sensor_lag_avg_ms,
+    );
+    write_metric(
+        output,
+        "gateway_clock_skew_alerts_total",
+        "Clock skew alerts raised",
+        MetricType::Counter,
+        site,
+        summary.clock_skew_alerts_total,
+    );
+
+    write_metric(
+        output,
+        "gateway_sensor_offline_total",
+        "Times a sensor went offline",
+        MetricType::Counter,
+        site,
+        summary.sensor_offline_total,
+    );
+    write_metric(
+        output,
+        "gateway_gate_opens_suppressed_total",
+        "Gate opens suppressed while a sensor was offline (keep_locked)",
+        MetricType::Counter,
+        site,
+        summary.gate_opens_suppressed_total,
+    );
+    write_metric(
+        output,
+        "gateway_free_exit_pulses_total",
+        "Gate open pulses while a sensor was offline (free_exit)",
+        MetricType::Counter,
+        site,
+        summary.free_exit_pulses_total,
+    );
+
+    write_metric(
+        output,
+        "gateway_frames_reordered_total",
+        "Frames released out of arrival order by the reorder window",
+        MetricType::Counter,
+        site,
+        summary.frames_reordered_total,
+    );
+    write_metric(
+        output,
+        "gateway_frames_duplicate_total",
+        "Exact duplicate frames dropped by the reorder window",
+        MetricType::Counter,
+        site,
+        summary.frames_duplicate_total,
+    );
+    write_metric(
+        output,
+        "gateway_frames_too_late_total",
+        "Frames dropped for arriving after newer frames were released",
+        MetricType::Counter,
+        site,
+        summary.frames_too_late_total,
+    );
+}
+
 fn write_drop_metrics(output: &mut String, site: &str, summary: &MetricsSummary) {
     write_metric(
         output,
@@ -437,6 +675,54 @@ fn write_drop_metrics(output: &mut String, site: &str, summary: &MetricsSummary)
         site,
         summary.mqtt_events_dropped,
     );
+    write_metric(
+        output,
+        "gateway_http_ingress_rejected_bodies_total",
+        "HTTP ingress bodies rejected due to channel full",
+        MetricType::Counter,
+        site,
+        summary.http_ingress_rejected_bodies,
+    );
+    write_metric(
+        output,
+        "gateway_object_events_filtered_total",
+        "MQTT events dropped for a filtered object type",
+        MetricType::Counter,
+        site,
+        summary.object_events_filtered_total,
+    );
+    write_metric(
+        output,
+        "gateway_mqtt_reconnects_total",
+        "MQTT ingress reconnects",
+        MetricType::Counter,
+        site,
+        summary.mqtt_reconnects_total,
+    );
+    write_metric(
+        output,
+        "gateway_mqtt_disconnected_ms_total",
+        "Time the MQTT ingress connection was down in ms",
+        MetricType::Counter,
+        site,
+        summary.mqtt_disconnected_ms_total,
+    );
+    write_metric(
+        output,
+        "gateway_ingress_gap_ms_total",
+        "Sensor time lost across MQTT reconnects in ms (by frame time)",
+        MetricType::Counter,
+        site,
+        summary.ingress_gap_ms_total,
+    );
+    write_metric(
+        output,
+        "gateway_ingress_events_missed_total",
+        "Events estimated to be lost across MQTT reconnects",
+        MetricType::Counter,
+        site,
+        summary.ingress_events_missed_total,
+    );
     write_gauge_f64(
         output,
         "gateway_mqtt_drop_ratio",
@@ -608,7 +894,24 @@ fn write_queue_metrics(output: &mut String, site: &str, summary: &MetricsSummary
     );
 }
 
+/// Why a dry run toggle is refused (`[gate].dry_run_token` as Bearer token)
+fn dry_run_token_error(
+    headers: &HeaderMap,
+    token: Option<&str>,
+) -> Option<(StatusCode, &'static str)> {
+    let Some(token) = token else {
+        return Some((StatusCode::FORBIDDEN, "dry_run_token_not_configured"));
+    };
+    let authorized = headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|sent| bool::from(sent.as_bytes().ct_eq(token.as_bytes())));
+    (!authorized).then_some((StatusCode::UNAUTHORIZED, "unauthorized"))
+}
+
 /// Handle HTTP requests
+#[allow(clippy::too_many_arguments)]
 async fn handle_request<G: GateCommand>(
     req: Request<hyper::body::Incoming>,
     metrics: Arc<Metrics>,
@@ -616,6 +919,8 @@ async fn handle_request<G: GateCommand>(
     gate: Option<Arc<G>>,
     event_tx: Option<mpsc::Sender<ParsedEvent>>,
     door_tx: Option<watch::Sender<DoorStatus>>,
+    reload_tx: Option<mpsc::Sender<ReloadRequest>>,
+    dry_run_token: Option<Arc<str>>,
 ) -> Result<Response<Full<Bytes>>, Infallible> {
     match (req.method(), req.uri().path()) {
         (&Method::GET, "/metrics") => {
@@ -658,6 +963,56 @@ async fn handle_request<G: GateCommand>(
                     .expect("static response should not fail"))
             }
         }
+        // Gate dry run toggle - GET /gate/dry-run, POST /gate/dry-run?enabled=true|false
+        // (POST needs the dry run token; no CORS so browser pages can't send it)
+        (&Method::GET, "/gate/dry-run") | (&Method::POST, "/gate/dry-run") => {
+            let refused = dry_run_token_error(req.headers(), dry_run_token.as_deref());
+            let (status, body) = match (gate, refused) {
+                (None, _) => (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    serde_json::json!({"ok": false, "error": "gate_not_configured"}),
+                ),
+                (Some(gate), _) if req.method() == Method::GET => {
+                    (StatusCode::OK, serde_json::json!({"ok": true, "dry_run": gate.is_dry_run()}))
+                }
+                (Some(_), Some((status, error))) => {
+                    warn!(error = %error, "gate_dry_run_refused");
+                    (status, serde_json::json!({"ok": false, "error": error}))
+                }
+                (Some(gate), None) => {
+                    let query = req.uri().query().unwrap_or("");
+                    let enabled = query.split('&').find_map(|p| p.strip_prefix("enabled="));
+                    match enabled.and_then(|v| v.parse::<bool>().ok()) {
+                        Some(dry_run) => {
+                            if gate.set_dry_run(dry_run) {
+                                (
+                                    StatusCode::OK,
+                                    serde_json::json!({"ok": true, "dry_run": gate.is_dry_run()}),
+                                )
+                            } else {
+                                (
+                                    StatusCode::CONFLICT,
+                                    serde_json::json!({"ok": false, "error": "gate_mode_dry_run"}),
+                                )
+                            }
+                        }
+                        _ => (
+                            StatusCode::BAD_REQUEST,
+                            serde_json::json!({
+                                "ok": false,
+                                "error": "invalid_enabled",
+                                "valid": ["true", "false"],
+                            }),
+                        ),
+                    }
+                }
+            };
+            Ok(Response::builder()
+                .status(status)
+                .header("Content-Type", "application/json")
+                .body(Full::new(Bytes::from(body.to_string())))
+                .expect("static response should not fail"))
+        }
         // CORS preflight for gate/open
         (&Method::OPTIONS, "/gate/open") => Ok(Response::builder()
             .status(StatusCode::OK)
@@ -686,6 +1041,8 @@ async fn handle_request<G: GateCommand>(
                     event_time: 0,
                     received_at: Instant::now(),
                     position: None,
+                    derived: false,
+                    obj_type: None,
                 };
 
                 // Send to tracker
@@ -778,6 +1135,38 @@ async fn handle_request<G: GateCommand>(
                     .expect("static response should not fail"))
             }
         }
+        // Config hot reload - POST /config/reload (same as SIGHUP)
+        (&Method::POST, "/config/reload") => {
+            let (reply_tx, reply_rx) = oneshot::channel();
+            let sent = match reload_tx {
+                Some(reload_tx) => reload_tx.send(reply_tx).await.is_ok(),
+                None => false,
+            };
+            let (status, body) = match (sent, reply_rx.await) {
+                (true, Ok(Ok(report))) => (
+                    StatusCode::OK,
+                    serde_json::json!({
+                        "ok": true,
+                        "applied": report.applied,
+                        "restart_required": report.restart_required,
+                    }),
+                ),
+                (true, Ok(Err(error))) => (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    serde_json::json!({"ok": false, "error": "invalid_config", "detail": error}),
+                ),
+                _ => (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    serde_json::json!({"ok": false, "error": "config_reload_not_enabled"}),
+                ),
+            };
+            Ok(Response::builder()
+                .status(status)
+                .header("Content-Type", "application/json")
+                .header("Access-Control-Allow-Origin", "*")
+                .body(Full::new(Bytes::from(body.to_string())))
+                .expect("static response should not fail"))
+        }
         // CORS preflight for door/simulate
         (&Method::OPTIONS, "/door/simulate") => Ok(Response::builder()
             .status(StatusCode::OK)
@@ -794,6 +1183,7 @@ async fn handle_request<G: GateCommand>(
 }
 
 /// Start the Prometheus metrics HTTP server
+#[allow(clippy::too_many_arguments)]
 pub async fn start_metrics_server<G: GateCommand + 'static>(
     port: u16,
     metrics: Arc<Metrics>,
@@ -801,11 +1191,14 @@ pub async fn start_metrics_server<G: GateCommand + 'static>(
     gate: Option<Arc<G>>,
     event_tx: Option<mpsc::Sender<ParsedEvent>>,
     door_tx: Option<watch::Sender<DoorStatus>>,
+    reload_tx: Option<mpsc::Sender<ReloadRequest>>,
+    dry_run_token: Option<String>,
     mut shutdown: watch::Receiver<bool>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
     let listener = TcpListener::bind(addr).await?;
     let site_id = Arc::new(site_id);
+    let dry_run_token: Option<Arc<str>> = dry_run_token.map(Arc::from);
 
     info!(port = %port, site = %site_id, "prometheus_metrics_server_started");
 
@@ -820,6 +1213,8 @@ pub async fn start_metrics_server<G: GateCommand + 'static>(
                         let gate = gate.clone();
                         let event_tx = event_tx.clone();
                         let door_tx = door_tx.clone();
+                        let reload_tx = reload_tx.clone();
+                        let dry_run_token = dry_run_token.clone();
 
                         tokio::spawn(async move {
                             let service = service_fn(move |req| {
@@ -828,7 +1223,21 @@ pub async fn start_metrics_server<G: GateCommand + 'static>(
                                 let gate = gate.clone();
                                 let event_tx = event_tx.clone();
                                 let door_tx = door_tx.clone();
-                                async move { handle_request(req, metrics, site_id, gate, event_tx, door_tx).await }
+                                let reload_tx = reload_tx.clone();
+                                let dry_run_token = dry_run_token.clone();
+                                async move {
+                                    handle_request(
+                                        req,
+                                        metrics,
+                                        site_id,
+                                        gate,
+                                        event_tx,
+                                        door_tx,
+                                        reload_tx,
+                                        dry_run_token,
+                                    )
+                                    .await
+                                }
                             });
 
                             if let Err(e) = http1::Builder::new()
@@ -876,4 +1285,41 @@ mod tests {
         assert!(output.contains("gateway_active_tracks{site=\"netto\"} 5"));
         assert!(output.contains("gateway_authorized_tracks{site=\"netto\"} 2"));
     }
+
+    #[test]
+    fn test_sensor_liveness_metrics() {
+        let metrics = Metrics::new();
+        metrics.set_sensors(&[0, 3]);
+        metrics.set_sensor_liveness(1, 7200, 9000, false);
+        metrics.set_sensor_clock_skew(1, -4200);
+        metrics.record_sensor_lag(1300);
+
+        let output = format_prometheus_metrics(&metrics, 0, 0, "netto");
+
+        assert!(output.contains("gateway_sensor_online{site=\"netto\",sensor=\"0\"} 1"));
+        assert!(output.contains("gateway_sensor_online{site=\"netto\",sensor=\"3\"} 0"));
+        assert!(output.contains("gateway_sensor_frame_age_ms{site=\"netto\",sensor=\"3\"} 7200"));
+        assert!(output.contains("gateway_sensor_event_age_ms{site=\"netto\",sensor=\"3\"} 9000"));
+        assert!(output.contains("gateway_sensor_clock_skew_ms{site=\"netto\",sensor=\"3\"} -4200"));
+        assert!(output.contains("gateway_sensor_lag_ms_bucket{site=\"netto\",le=\"1600\"} 1"));
+    }
+
+    #[test]
+    fn test_dry_run_toggle_needs_token() {
+        let mut headers = HeaderMap::new();
+        let refused = dry_run_token_error(&headers, None);
+        assert_eq!(refused, Some((StatusCode::FORBIDDEN, "dry_run_token_not_configured")));
+        assert_eq!(
+            dry_run_token_error(&headers, Some("secret")).unwrap().0,
+            StatusCode::UNAUTHORIZED
+        );
+
+        headers.insert(AUTHORIZATION, "Bearer wrong".parse().unwrap());
+        assert_eq!(
+            dry_run_token_error(&headers, Some("secret")).unwrap().0,
+            StatusCode::UNAUTHORIZED
+        );
+        headers.insert(AUTHORIZATION, "Bearer secret".parse().unwrap());
+        assert_eq!(dry_run_token_error(&headers, Some("secret")), None);
+    }
 }
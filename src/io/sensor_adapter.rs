@@ -0,0 +1,34 @@
+//! Sensor adapters - vendor payloads to gateway events
+//!
+//! The ingest loop (`mqtt::start_mqtt_client`) only sees `SensorFrame`s, so
+//! evaluating another people-tracker vendor needs a `SensorAdapter` and a
+//! `SensorProtocol` variant, not tracker changes. Adapters return events and
+//! positions in sensor coordinates with raw track IDs; mapping into the store
+//! frame (`[[sensors]]` transform, geometry map) happens after parsing.
+
+use crate::domain::types::{ParsedEvent, TrackedObject};
+use crate::infra::config::SensorProtocol;
+use crate::io::xovis::XovisAdapter;
+use std::time::Instant;
+
+/// One sensor frame: events and object positions at a point in time
+#[derive(Debug)]
+pub struct SensorFrame {
+    /// Frame time (epoch ms, 0 if the payload has none)
+    pub time_ms: u64,
+    pub events: Vec<ParsedEvent>,
+    pub objects: Vec<TrackedObject>,
+}
+
+/// Parser for the payloads of one sensor vendor
+pub trait SensorAdapter: Send + Sync {
+    /// Parse one message into frames in send order (empty if invalid)
+    fn parse(&mut self, payload: &str, received_at: Instant) -> Vec<SensorFrame>;
+}
+
+/// Adapter for a `[[sensors]]` protocol
+pub fn for_protocol(protocol: SensorProtocol) -> Box<dyn SensorAdapter> {
+    match protocol {
+        SensorProtocol::Xovis => Box::new(XovisAdapter::default()),
+    }
+}
@@ -0,0 +1,289 @@
+//! Xovis live data adapter
+//!
+//! Parses the `live_data.frames` JSON Xovis sensors send over MQTT or HTTP push.
+//! Strings are borrowed from the payload and object types are interned, so
+//! parsing allocates only the frame, event and object vectors.
+
+use crate::domain::types::{
+    Direction, EventType, Frame, GeometryId, ParsedEvent, TimestampValue, TrackId, TrackedObject,
+    XovisEvent, XovisMessage,
+};
+use crate::io::sensor_adapter::{SensorAdapter, SensorFrame};
+use std::sync::Arc;
+use std::time::Instant;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use tracing::debug;
+
+/// Distinct object types kept interned (further types allocate per object)
+const MAX_INTERNED_TYPES: usize = 16;
+
+/// Adapter for Xovis `live_data.frames` messages
+#[derive(Default)]
+pub struct XovisAdapter {
+    /// Interned object types ("PERSON", "CHILD", "GROUP", ...)
+    obj_types: Vec<Arc<str>>,
+}
+
+impl XovisAdapter {
+    fn intern(&mut self, obj_type: &str) -> Arc<str> {
+        if let Some(interned) = self.obj_types.iter().find(|t| ***t == *obj_type) {
+            return interned.clone();
+        }
+        let interned: Arc<str> = Arc::from(obj_type);
+        if self.obj_types.len() < MAX_INTERNED_TYPES {
+            self.obj_types.push(interned.clone());
+        }
+        interned
+    }
+
+    fn convert_frame(&mut self, frame: Frame, received_at: Instant) -> SensorFrame {
+        let mut objects = Vec::with_capacity(frame.tracked_objects.len());
+        for obj in frame.tracked_objects {
+            objects.push(TrackedObject {
+                track_id: obj.track_id,
+                obj_type: self.intern(&obj.obj_type),
+                position: obj.position,
+            });
+        }
+
+        // Extract event time from frame timestamp (handles both ISO string and epoch ms)
+        let time_ms = timestamp_to_epoch_ms(&frame.time);
+        let mut events = Vec::with_capacity(frame.events.len());
+        for xovis_event in &frame.events {
+            events.extend(parse_event(xovis_event, &objects, time_ms, received_at));
+        }
+        SensorFrame { time_ms, events, objects }
+    }
+}
+
+impl SensorAdapter for XovisAdapter {
+    fn parse(&mut self, payload: &str, received_at: Instant) -> Vec<SensorFrame> {
+        let frames = parse_xovis_frames(payload);
+        let mut parsed = Vec::with_capacity(frames.len());
+        for frame in frames {
+            parsed.push(self.convert_frame(frame, received_at));
+        }
+        parsed
+    }
+}
+
+/// Parse a Xovis JSON message and extract events
+pub fn parse_xovis_message(json_str: &str, received_at: Instant) -> Vec<ParsedEvent> {
+    let frames = XovisAdapter::default().parse(json_str, received_at);
+    frames.into_iter().flat_map(|f| f.events).collect()
+}
+
+/// Parse the frames of a Xovis JSON message (empty if invalid)
+fn parse_xovis_frames(json_str: &str) -> Vec<Frame<'_>> {
+    match serde_json::from_str::<XovisMessage>(json_str) {
+        Ok(message) => message.live_data.map(|l| l.frames).unwrap_or_default(),
+        Err(e) => {
+            debug!(error = %e, "Failed to parse Xovis message");
+            Vec::new()
+        }
+    }
+}
+
+/// Parse ISO 8601 timestamp to epoch milliseconds
+fn parse_iso_time(time_str: &str) -> Option<u64> {
+    // Parse "2026-01-05T16:41:30.048+00:00" format (RFC 3339)
+    OffsetDateTime::parse(time_str, &Rfc3339)
+        .ok()
+        .map(|dt| (dt.unix_timestamp_nanos() / 1_000_000) as u64)
+}
+
+/// Extract epoch milliseconds from TimestampValue
+fn timestamp_to_epoch_ms(ts: &TimestampValue) -> u64 {
+    match ts {
+        TimestampValue::EpochMs(ms) => *ms,
+        TimestampValue::IsoString(s) => parse_iso_time(s).unwrap_or(0),
+        TimestampValue::None => 0,
+    }
+}
+
+/// Event of a frame with the position and object type of its track
+fn parse_event(
+    xovis_event: &XovisEvent,
+    objects: &[TrackedObject],
+    event_time: u64,
+    received_at: Instant,
+) -> Option<ParsedEvent> {
+    let attrs = xovis_event.attributes.as_ref()?;
+    let track_id = attrs.track_id?;
+    let event_type: EventType = xovis_event.event_type.parse().unwrap();
+
+    // Linear search for position - frames typically have <10 tracked objects
+    let object = objects.iter().find(|obj| obj.track_id == track_id);
+    let position = object
+        .filter(|obj| obj.position.len() >= 3)
+        .map(|obj| [obj.position[0], obj.position[1], obj.position[2]]);
+
+    Some(ParsedEvent {
+        event_type,
+        track_id: TrackId(track_id),
+        geometry_id: attrs.geometry_id.map(GeometryId),
+        direction: attrs.direction.as_deref().and_then(Direction::parse),
+        event_time,
+        received_at,
+        position,
+        derived: false,
+        obj_type: object.map(|obj| obj.obj_type.clone()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_zone_entry() {
+        let json = r#"{
+            "live_data": {
+                "frames": [{
+                    "time": "2026-01-05T16:41:30.048+00:00",
+                    "tracked_objects": [{
+                        "track_id": 123,
+                        "type": "PERSON",
+                        "position": [1.5, 2.0, 1.7]
+                    }],
+                    "events": [{
+                        "type": "ZONE_ENTRY",
+                        "attributes": {
+                            "track_id": 123,
+                            "geometry_id": 1001
+                        }
+                    }]
+                }]
+            }
+        }"#;
+
+        let events = parse_xovis_message(json, Instant::now());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].track_id, TrackId(123));
+        assert_eq!(events[0].event_type, EventType::ZoneEntry);
+        assert_eq!(events[0].geometry_id, Some(GeometryId(1001)));
+        // event_time should now be parsed from ISO 8601
+        assert!(events[0].event_time > 0, "event_time should be parsed from ISO timestamp");
+        // Position should be extracted
+        assert_eq!(events[0].position, Some([1.5, 2.0, 1.7]));
+    }
+
+    #[test]
+    fn test_parse_track_create() {
+        let json = r#"{
+            "live_data": {
+                "frames": [{
+                    "time": "2026-01-05T16:40:00.000+00:00",
+                    "events": [{
+                        "type": "TRACK_CREATE",
+                        "attributes": {
+                            "track_id": 100
+                        }
+                    }]
+                }]
+            }
+        }"#;
+
+        let events = parse_xovis_message(json, Instant::now());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].track_id, TrackId(100));
+        assert_eq!(events[0].event_type, EventType::TrackCreate);
+    }
+
+    #[test]
+    fn test_parse_line_cross() {
+        let json = r#"{
+            "live_data": {
+                "frames": [{
+                    "time": "2026-01-05T16:42:00.000+00:00",
+                    "events": [{
+                        "type": "LINE_CROSS_FORWARD",
+                        "attributes": {
+                            "track_id": 100,
+                            "geometry_id": 1006,
+                            "direction": "forward"
+                        }
+                    }]
+                }]
+            }
+        }"#;
+
+        let events = parse_xovis_message(json, Instant::now());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, EventType::LineCrossForward);
+        assert_eq!(events[0].geometry_id, Some(GeometryId(1006)));
+    }
+
+    #[test]
+    fn test_parse_multiple_events() {
+        let json = r#"{
+            "live_data": {
+                "frames": [{
+                    "time": "2026-01-05T16:41:30.000+00:00",
+                    "events": [
+                        {"type": "ZONE_EXIT", "attributes": {"track_id": 100, "geometry_id": 1001}},
+                        {"type": "ZONE_ENTRY", "attributes": {"track_id": 100, "geometry_id": 1007}}
+                    ]
+                }]
+            }
+        }"#;
+
+        let events = parse_xovis_message(json, Instant::now());
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_type, EventType::ZoneExit);
+        assert_eq!(events[1].event_type, EventType::ZoneEntry);
+    }
+
+    #[test]
+    fn test_parse_invalid_json() {
+        let events = parse_xovis_message("not json", Instant::now());
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_parse_empty_frames() {
+        let json = r#"{"live_data": {"frames": []}}"#;
+        let events = parse_xovis_message(json, Instant::now());
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_parse_iso_time() {
+        // Test RFC 3339 format parsing
+        let ts = parse_iso_time("2026-01-05T16:41:30.048+00:00");
+        assert!(ts.is_some());
+        let ms = ts.unwrap();
+        // 2026-01-05T16:41:30.048Z should be around 1767630090048 ms
+        assert!(ms > 1767000000000, "timestamp should be in 2026");
+        assert!(ms < 1800000000000, "timestamp should be before 2027");
+
+        // Test invalid input
+        assert!(parse_iso_time("not a timestamp").is_none());
+        assert!(parse_iso_time("").is_none());
+    }
+
+    #[test]
+    fn test_adapter_keeps_frames_and_objects() {
+        let json = r#"{
+            "live_data": {
+                "frames": [
+                    {"time": 1767630090048, "tracked_objects": [
+                        {"track_id": 7, "type": "PERSON", "position": [1.0, 2.0, 1.7]}
+                    ]},
+                    {"time": 1767630090148, "events": [
+                        {"type": "TRACK_DELETE", "attributes": {"track_id": 7}}
+                    ]}
+                ]
+            }
+        }"#;
+
+        let frames = XovisAdapter::default().parse(json, Instant::now());
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].time_ms, 1767630090048);
+        assert_eq!(frames[0].objects.len(), 1);
+        assert!(frames[0].events.is_empty());
+        assert_eq!(frames[1].events[0].event_type, EventType::TrackDelete);
+        assert_eq!(frames[1].events[0].event_time, 1767630090148);
+    }
+}
@@ -9,12 +9,13 @@
 //! - `services/` - Business logic (Tracker, JourneyManager, Gate)
 //! - `infra/` - Infrastructure (Config, Metrics, Broker)
 
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use parking_lot::Mutex;
 use tokio::sync::{mpsc, watch};
-use tracing::info;
+use tracing::{info, warn};
 use tracing_subscriber::fmt::time::UtcTime;
 use tracing_subscriber::EnvFilter;
 
@@ -24,6 +25,10 @@ use gateway::io::{
     create_egress_channel, create_egress_writer, start_acc_listener, AccListenerConfig,
     MqttPublisher, Rs485Monitor,
 };
+use gateway::services::config_reload::ConfigReloader;
+use gateway::services::replay::Capture;
+use gateway::services::sensor_watchdog::{SensorHealth, SensorWatchdog};
+use gateway::services::sweep::{format_table, SweepGrid};
 use gateway::services::{create_gate_worker, GateController};
 
 /// Gateway PoC - Automated retail gate control system
@@ -31,22 +36,176 @@ use gateway::services::{create_gate_worker, GateController};
 #[command(name = "gateway-poc", version, about)]
 struct Args {
     /// Path to TOML configuration file
-    #[arg(short, long, default_value = "config/dev.toml")]
+    #[arg(short, long, global = true, default_value = "config/dev.toml")]
     config: String,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Check the configuration file, print every problem and exit non-zero if any
+    CheckConfig,
+    /// Replay a gateway-analysis capture through the tracker (simulated clock, no gate I/O)
+    Replay {
+        /// Capture directory (the gateway-analysis log dir)
+        capture_dir: PathBuf,
+        /// Output JSONL file for journeys and gate decisions
+        #[arg(short, long, default_value = "replay.jsonl")]
+        output: PathBuf,
+    },
+    /// Replay a capture once per parameter set of a grid and compare the results
+    Sweep {
+        /// Capture directory (the gateway-analysis log dir)
+        capture_dir: PathBuf,
+        /// Parameter grid (TOML)
+        #[arg(short, long)]
+        grid: PathBuf,
+        /// Also write the results as JSON
+        #[arg(long)]
+        json: Option<PathBuf>,
+    },
+}
+
+/// `check-config`: structural and semantic checks on the config file
+///
+/// Returns true if the file has no problems.
+fn check_config(path: &str) -> bool {
+    match gateway::infra::config_check::check_file(path) {
+        Ok(_) => {
+            println!("{path}: ok");
+            true
+        }
+        Err(problems) => {
+            for problem in &problems {
+                eprintln!("error: {problem}");
+            }
+            eprintln!("{path}: {} problem(s)", problems.len());
+            false
+        }
+    }
+}
+
+/// `replay`: run a capture through the tracker with the config at `config_path`
+///
+/// Returns true if the replay ran and its output was written.
+async fn replay(config_path: &str, capture_dir: &Path, output: &Path) -> bool {
+    let run = async {
+        let config = Config::load_strict(config_path)?;
+        let capture = Capture::load(capture_dir)?;
+        let outcome = gateway::services::replay::replay(&config, &capture).await;
+        outcome.write(output)?;
+        println!(
+            "{}: {} records ({} skipped), {} journeys, {} gate opens -> {}",
+            capture_dir.display(),
+            capture.records.len(),
+            capture.skipped,
+            outcome.journeys().count(),
+            outcome.gate_opens().count(),
+            output.display()
+        );
+        anyhow::Ok(())
+    };
+    match run.await {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            false
+        }
+    }
+}
+
+/// `sweep`: replay a capture under every parameter set of `grid_path`
+///
+/// Prints a table and optionally writes the results as JSON. Returns true if
+/// every set passed the config checks and the sweep ran.
+async fn sweep(
+    config_path: &str,
+    capture_dir: &Path,
+    grid_path: &Path,
+    json: Option<&Path>,
+) -> bool {
+    let run = async {
+        let config = Config::load_strict(config_path)?;
+        let sets = SweepGrid::from_file(grid_path)?.expand(&config);
+        let problems: Vec<String> = sets
+            .iter()
+            .enumerate()
+            .flat_map(|(i, params)| {
+                let problems = gateway::infra::config_check::check(&params.apply(&config));
+                problems.into_iter().map(move |p| format!("set {}: {p}", i + 1))
+            })
+            .collect();
+        if !problems.is_empty() {
+            anyhow::bail!("invalid parameter sets:\n{}", problems.join("\n"));
+        }
+
+        let capture = Capture::load(capture_dir)?;
+        let results = gateway::services::sweep::sweep(&config, &capture, sets).await;
+        print!("{}", format_table(&results));
+        if let Some(path) = json {
+            std::fs::write(path, serde_json::to_string_pretty(&results)?)?;
+            println!("{} sets -> {}", results.len(), path.display());
+        }
+        anyhow::Ok(())
+    };
+    match run.await {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            false
+        }
+    }
+}
+
+/// Resolves on Ctrl+C (SIGINT) or SIGTERM from systemd
+async fn wait_for_shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = sigterm.recv() => {}
+                }
+            }
+            Err(e) => {
+                warn!(error = %e, "sigterm_handler_failed");
+                tokio::signal::ctrl_c().await.ok();
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await.ok();
 }
 
 /// Calculate queue utilization as a percentage (0-100).
 #[inline]
 fn utilization_pct(used: u64, capacity: u64) -> u64 {
-    if capacity > 0 {
-        used * 100 / capacity
-    } else {
-        0
-    }
+    (used * 100).checked_div(capacity).unwrap_or(0)
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Parse command line arguments using clap
+    let args = Args::parse();
+    match args.command {
+        Some(Command::CheckConfig) => {
+            std::process::exit(if check_config(&args.config) { 0 } else { 1 });
+        }
+        Some(Command::Replay { ref capture_dir, ref output }) => {
+            let ok = replay(&args.config, capture_dir, output).await;
+            std::process::exit(if ok { 0 } else { 1 });
+        }
+        Some(Command::Sweep { ref capture_dir, ref grid, ref json }) => {
+            let ok = sweep(&args.config, capture_dir, grid, json.as_deref()).await;
+            std::process::exit(if ok { 0 } else { 1 });
+        }
+        None => {}
+    }
+
     // Initialize structured logging with configurable level via RUST_LOG env var
     // Default: INFO, use RUST_LOG=debug for full event visibility
     let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
@@ -63,11 +222,29 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         "gateway_starting"
     );
 
-    // Parse command line arguments using clap
-    let args = Args::parse();
-
     // Load configuration from TOML file (needed for broker config)
-    let config = Config::load_from_path(&args.config);
+    let config = match Config::load_strict(&args.config) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Same semantic checks as `check-config`
+    let problems = gateway::infra::config_check::check(&config);
+    if !problems.is_empty() {
+        for problem in &problems {
+            eprintln!("Error: {}", problem);
+        }
+        std::process::exit(1);
+    }
+
+    // Report TLS certificates; unusable files stop startup instead of reconnect loops
+    if let Err(e) = gateway::infra::tls::check_certificates(&config) {
+        eprintln!("Error: {:#}", e);
+        std::process::exit(1);
+    }
 
     // Start embedded MQTT broker with config
     gateway::infra::broker::start_embedded_broker(&config);
@@ -106,17 +283,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         None
     };
 
-    // Create shutdown signal
+    // Create shutdown signals, set in order: ingress and I/O tasks, then the
+    // tracker once ingress has stopped, then MQTT egress once the tracker is done
     let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let (tracker_shutdown_tx, tracker_shutdown_rx) = watch::channel(false);
+    let (egress_shutdown_tx, egress_shutdown_rx) = watch::channel(false);
 
     // Create shared components
     let metrics = Arc::new(Metrics::new());
-    let gate = Arc::new(GateController::new(config.clone(), Some(metrics.clone())));
-
-    // Initialize POS zone tracking
-    metrics.set_pos_zones(config.pos_zones());
 
-    // Create MQTT egress channel early (needed by gate worker for timing events)
+    // Create MQTT egress channel early (needed by the gate and gate worker)
     // The publisher will be started later if mqtt_egress is enabled
     let (egress_sender, egress_rx) = if config.mqtt_egress_enabled() {
         let (sender, rx) = create_egress_channel(1000, config.site_id().to_string());
@@ -125,6 +301,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         (None, None)
     };
 
+    let gate = Arc::new(
+        GateController::new(config.clone(), Some(metrics.clone()))
+            .with_egress(egress_sender.clone()),
+    );
+
+    // Initialize POS zone tracking
+    metrics.set_pos_zones(config.pos_zones());
+
+    // Sensor liveness (stamped by ingest, checked by the watchdog)
+    let sensor_ids: Vec<u8> = config.sensors().iter().map(|s| s.id).collect();
+    metrics.set_sensors(&sensor_ids);
+    let sensor_health =
+        Arc::new(SensorHealth::new(sensor_ids.len(), gateway::domain::journey::epoch_ms()));
+
     // Start CloudPlus TCP client if in TCP mode
     if let Some(tcp_client) = gate.tcp_client() {
         let tcp_shutdown = shutdown_rx.clone();
@@ -141,8 +331,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     });
 
     // Create egress writer (decouples file I/O from tracker loop)
+    // Runs until the tracker drops its sender, then flushes
     let (journey_tx, egress_writer) = create_egress_writer(config.egress_file().to_string(), 100);
-    tokio::spawn(async move {
+    let egress_writer_task = tokio::spawn(async move {
         egress_writer.run().await;
     });
 
@@ -169,7 +360,46 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mqtt_shutdown = shutdown_rx.clone();
     let mqtt_analysis_logger = analysis_logger.clone();
     let mqtt_egress_sender = egress_sender.clone();
-    tokio::spawn(async move {
+    // Several sensors: ingest shares live positions with the tracker for handover
+    let live_positions = (config.sensors().len() > 1)
+        .then(|| Arc::new(gateway::services::stitcher::LivePositions::default()));
+    let mqtt_live_positions = live_positions.clone();
+    // Snapshot restore: ingest reports restored tracks still present in frames
+    let restored_tracks =
+        config.snapshot().enabled.then(|| Arc::new(gateway::services::RestoredTracks::default()));
+    let mqtt_restored_tracks = restored_tracks.clone();
+    // Config hot reload: the tracker and ingest (gate triggers) apply reloaded configs
+    let (config_reloader, config_rx, reload_tx) = ConfigReloader::new(&config);
+    let mqtt_config_rx = config_rx.clone();
+    let mqtt_sensor_health = sensor_health.clone();
+
+    // Ingress tasks, awaited on shutdown before the tracker drains its queue
+    let mut ingress_tasks = Vec::new();
+
+    // Start Xovis HTTP push ingress (messages join the MQTT ingest path)
+    let push_rx = if config.http_ingress().enabled {
+        let (push_tx, push_rx) = mpsc::channel(64);
+        let http_config = config.clone();
+        let http_metrics = metrics.clone();
+        let http_shutdown = shutdown_rx.clone();
+        ingress_tasks.push(tokio::spawn(async move {
+            if let Err(e) = gateway::io::http_ingress::start_http_ingress(
+                &http_config,
+                push_tx,
+                http_metrics,
+                http_shutdown,
+            )
+            .await
+            {
+                tracing::error!(error = %e, "HTTP ingress error");
+            }
+        }));
+        Some(push_rx)
+    } else {
+        None
+    };
+
+    ingress_tasks.push(tokio::spawn(async move {
         if let Err(e) = gateway::io::mqtt::start_mqtt_client(
             &mqtt_config,
             mqtt_tx,
@@ -177,12 +407,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             mqtt_shutdown,
             mqtt_analysis_logger,
             mqtt_egress_sender,
+            mqtt_live_positions,
+            mqtt_restored_tracks,
+            mqtt_sensor_health,
+            push_rx,
+            Some(mqtt_config_rx),
         )
         .await
         {
             tracing::error!(error = %e, "MQTT client error");
         }
-    });
+    }));
 
     // Clone event_tx for the HTTP server's ACC simulation endpoint
     let prom_event_tx = event_tx.clone();
@@ -195,10 +430,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let acc_tx = event_tx;
     let acc_metrics = metrics.clone();
     let acc_shutdown = shutdown_rx.clone();
-    tokio::spawn(async move {
+    ingress_tasks.push(tokio::spawn(async move {
         if let Err(e) = start_acc_listener(acc_config, acc_tx, acc_metrics, acc_shutdown).await {
             tracing::error!(error = %e, "ACC listener error");
         }
+    }));
+
+    // Start config hot reload (SIGHUP or POST /config/reload)
+    let reload_shutdown = shutdown_rx.clone();
+    tokio::spawn(async move {
+        config_reloader.run(reload_shutdown).await;
     });
 
     // Start Prometheus metrics HTTP server (if port > 0)
@@ -208,6 +449,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         let prom_site_id = config.site_id().to_string();
         let prom_gate = gate.clone();
         let prom_door_tx = door_tx.clone(); // For /door/simulate endpoint
+        let prom_reload_tx = reload_tx.clone();
+        let prom_dry_run_token = config.gate_dry_run_token().map(str::to_string);
         let prom_shutdown = shutdown_rx.clone();
         tokio::spawn(async move {
             if let Err(e) = gateway::io::prometheus::start_metrics_server(
@@ -217,6 +460,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 Some(prom_gate),
                 Some(prom_event_tx),
                 Some(prom_door_tx),
+                Some(prom_reload_tx),
+                prom_dry_run_token,
                 prom_shutdown,
             )
             .await
@@ -261,13 +506,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     });
 
     // Start MQTT egress publisher (if enabled, channel was created earlier)
+    let mut publisher_task = None;
     if let (Some(ref sender), Some(rx)) = (&egress_sender, egress_rx) {
-        // Start MQTT egress publisher
-        let publisher = MqttPublisher::new(&config, rx);
-        let publisher_shutdown = shutdown_rx.clone();
-        tokio::spawn(async move {
-            publisher.run(publisher_shutdown).await;
-        });
+        // Start MQTT egress publisher (stopped after the tracker's final journeys)
+        let publisher = MqttPublisher::new(&config, rx)?;
+        publisher_task = Some(tokio::spawn(async move {
+            publisher.run(egress_shutdown_rx).await;
+        }));
 
         // Start metrics egress publisher (separate from logging)
         // Clone door_rx to get current gate state for heartbeat
@@ -287,7 +532,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         });
     }
 
+    // Start sensor watchdog (degraded-mode state is consumed by the tracker)
+    let degraded_rx = if config.sensor_watchdog().enabled {
+        let (watchdog, degraded_rx) =
+            SensorWatchdog::new(&config, sensor_health, metrics.clone(), egress_sender.clone());
+        let watchdog_shutdown = shutdown_rx.clone();
+        tokio::spawn(async move {
+            watchdog.run(watchdog_shutdown).await;
+        });
+        Some(degraded_rx)
+    } else {
+        None
+    };
+
     // Start tracker (main event processing loop)
+    let shutdown_deadline = std::time::Duration::from_secs(config.shutdown().deadline_secs);
     let mut tracker = gateway::services::Tracker::new(
         config,
         gate_cmd_tx,
@@ -295,20 +554,55 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         metrics,
         egress_sender,
         door_rx,
+        gateway::infra::clock::SystemClock::shared(),
     );
+    if let Some(live_positions) = live_positions {
+        tracker = tracker.with_live_positions(live_positions);
+    }
+    if let Some(restored_tracks) = restored_tracks {
+        tracker = tracker.with_restored_tracks(restored_tracks);
+    }
+    if let Some(degraded_rx) = degraded_rx {
+        tracker = tracker.with_degraded_rx(degraded_rx);
+    }
+    tracker = tracker.with_gate(gate);
+    tracker = tracker.with_config_reload(config_rx);
+    tracker = tracker.with_shutdown(tracker_shutdown_rx);
+    tracker.restore_snapshot();
     info!("tracker_started");
 
-    // Handle shutdown on Ctrl+C
-    let shutdown_signal = shutdown_tx;
+    // Handle shutdown on Ctrl+C or SIGTERM
     tokio::spawn(async move {
-        tokio::signal::ctrl_c().await.ok();
-        info!("shutdown_signal_received");
-        let _ = shutdown_signal.send(true);
+        wait_for_shutdown_signal().await;
+        info!(deadline_secs = %shutdown_deadline.as_secs(), "shutdown_signal_received");
+        let _ = shutdown_tx.send(true);
+
+        // Hard deadline for the whole sequence
+        tokio::spawn(async move {
+            tokio::time::sleep(shutdown_deadline).await;
+            warn!("shutdown_deadline_exceeded");
+            std::process::exit(1);
+        });
+
+        // Stop ingress before the tracker drains its queue
+        for task in ingress_tasks {
+            let _ = task.await;
+        }
+        info!("ingress_stopped");
+        let _ = tracker_shutdown_tx.send(true);
     });
 
-    // Run tracker - consumes events until channel closes
+    // Run tracker - consumes events until shutdown, then finalizes journeys
     tracker.run(event_rx).await;
 
+    // Dropping the tracker closes the journey and gate command channels
+    drop(tracker);
+    let _ = egress_writer_task.await;
+    let _ = egress_shutdown_tx.send(true);
+    if let Some(task) = publisher_task {
+        let _ = task.await;
+    }
+
     info!("gateway-poc shutdown complete");
     Ok(())
 }
@@ -0,0 +1,246 @@
+//! Hot reload of site configuration
+//!
+//! On SIGHUP or `POST /config/reload` the config file is read and validated
+//! again (`config_check`). Every changed field is logged with its old and new
+//! value. Fields the tracker can apply live (zone names and roles,
+//! `ip_to_pos`, dwell and grace thresholds, exit detection) are published to
+//! the tracker, which swaps them into its `Config`, `AccCollector` and
+//! `PosOccupancyState`. Everything else (ports, broker, sensors, ...) is
+//! reported as needing a restart and keeps its running value, so it is
+//! reported again on the next reload.
+
+use crate::infra::config::{Config, ConfigChange};
+use crate::infra::config_check;
+use serde::Serialize;
+use tokio::sync::{mpsc, oneshot, watch};
+use tracing::{info, warn};
+
+/// Reply channel of an admin reload request
+pub type ReloadRequest = oneshot::Sender<Result<ReloadReport, String>>;
+
+/// Outcome of a successful reload
+#[derive(Debug, Default, Serialize)]
+pub struct ReloadReport {
+    /// Changes now in effect
+    pub applied: Vec<ConfigChange>,
+    /// Changes that take effect after a restart
+    pub restart_required: Vec<ConfigChange>,
+}
+
+/// Re-reads the config file on request and publishes reloadable changes
+pub struct ConfigReloader {
+    path: String,
+    /// Running config: startup values plus applied reloads
+    current: Config,
+    config_tx: watch::Sender<Config>,
+    request_rx: mpsc::Receiver<ReloadRequest>,
+}
+
+impl ConfigReloader {
+    /// Create a reloader for `config.config_file()`
+    ///
+    /// Returns the reloader, the receiver the tracker applies configs from, and
+    /// the sender for admin reload requests.
+    pub fn new(config: &Config) -> (Self, watch::Receiver<Config>, mpsc::Sender<ReloadRequest>) {
+        let (config_tx, config_rx) = watch::channel(config.clone());
+        let (request_tx, request_rx) = mpsc::channel(4);
+        let reloader = Self {
+            path: config.config_file().to_string(),
+            current: config.clone(),
+            config_tx,
+            request_rx,
+        };
+        (reloader, config_rx, request_tx)
+    }
+
+    /// Handle SIGHUP and admin requests until shutdown
+    pub async fn run(mut self, mut shutdown: watch::Receiver<bool>) {
+        #[cfg(unix)]
+        let mut hangup = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+        {
+            Ok(signal) => Some(signal),
+            Err(e) => {
+                warn!(error = %e, "sighup_handler_failed");
+                None
+            }
+        };
+        #[cfg(not(unix))]
+        let mut hangup: Option<()> = None;
+
+        info!(path = %self.path, "config_reloader_started");
+
+        loop {
+            tokio::select! {
+                _ = recv_hangup(&mut hangup), if hangup.is_some() => {
+                    info!("sighup_received");
+                    let _ = self.reload();
+                }
+                Some(reply) = self.request_rx.recv() => {
+                    let _ = reply.send(self.reload().map_err(|e| format!("{e:#}")));
+                }
+                _ = shutdown.changed() => {
+                    if *shutdown.borrow() {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Read and validate the config file and apply the reloadable changes
+    pub fn reload(&mut self) -> anyhow::Result<ReloadReport> {
+        let new = match config_check::check_file(&self.path) {
+            Ok(config) => config,
+            Err(problems) => {
+                for problem in &problems {
+                    warn!(path = %self.path, problem = %problem, "config_reload_failed");
+                }
+                let problems: Vec<String> = problems.iter().map(|p| p.to_string()).collect();
+                anyhow::bail!("{}", problems.join("; "));
+            }
+        };
+
+        let mut report = ReloadReport::default();
+        for change in self.current.diff(&new) {
+            info!(
+                field = %change.field,
+                old = %change.old,
+                new = %change.new,
+                applied = %change.reloadable,
+                "config_changed"
+            );
+            if change.reloadable {
+                report.applied.push(change);
+            } else {
+                report.restart_required.push(change);
+            }
+        }
+
+        if !report.applied.is_empty() {
+            self.current.apply_reloadable(&new);
+            self.config_tx.send_replace(self.current.clone());
+        }
+        info!(
+            path = %self.path,
+            applied = %report.applied.len(),
+            restart_required = %report.restart_required.len(),
+            "config_reloaded"
+        );
+        Ok(report)
+    }
+}
+
+#[cfg(unix)]
+async fn recv_hangup(hangup: &mut Option<tokio::signal::unix::Signal>) {
+    match hangup {
+        Some(signal) => {
+            signal.recv().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(not(unix))]
+async fn recv_hangup(_hangup: &mut Option<()>) {
+    std::future::pending().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::types::GeometryId;
+    use tempfile::NamedTempFile;
+
+    fn config_toml(min_dwell_ms: &str, prometheus_port: u16) -> String {
+        format!(
+            r#"
+[mqtt]
+host = "localhost"
+port = 1883
+topic = "xovis/#"
+
+[gate]
+mode = "tcp"
+tcp_addr = "127.0.0.1:8000"
+http_url = "http://localhost/open"
+timeout_ms = 2000
+
+[rs485]
+device = "/dev/null"
+baud = 19200
+poll_interval_ms = 250
+
+[zones]
+pos_zones = [2001]
+gate_zone = 2003
+exit_line = 2004
+
+[authorization]
+min_dwell_ms = {min_dwell_ms}
+
+[metrics]
+interval_secs = 10
+prometheus_port = {prometheus_port}
+"#
+        )
+    }
+
+    fn create_reloader(file: &NamedTempFile) -> (ConfigReloader, watch::Receiver<Config>) {
+        std::fs::write(file.path(), config_toml("7000", 9090)).unwrap();
+        let config = Config::from_file(file.path()).unwrap();
+        let (reloader, config_rx, _request_tx) = ConfigReloader::new(&config);
+        (reloader, config_rx)
+    }
+
+    #[test]
+    fn test_reload_applies_reloadable_and_reports_restart_fields() {
+        let file = NamedTempFile::new().unwrap();
+        let (mut reloader, config_rx) = create_reloader(&file);
+
+        std::fs::write(file.path(), config_toml("3000", 9999)).unwrap();
+        let report = reloader.reload().unwrap();
+
+        let applied: Vec<_> = report.applied.iter().map(|c| c.field).collect();
+        assert_eq!(applied, vec!["min_dwell_ms"]);
+        assert_eq!(config_rx.borrow().min_dwell_ms(), 3000);
+
+        // Restart-only changes keep their running value and are reported again
+        let restart: Vec<_> = report.restart_required.iter().map(|c| c.field).collect();
+        assert_eq!(restart, vec!["prometheus_port"]);
+        assert_eq!(config_rx.borrow().prometheus_port(), 9090);
+        let report = reloader.reload().unwrap();
+        assert!(report.applied.is_empty());
+        assert_eq!(report.restart_required.len(), 1);
+    }
+
+    #[test]
+    fn test_reload_re_resolves_gate_triggers_for_new_gate_zone() {
+        let trigger = "\n[[gate_triggers]]\npolygon = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0]]\n";
+        let file = NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), config_toml("7000", 9090) + trigger).unwrap();
+        let config = Config::from_file(file.path()).unwrap();
+        let (mut reloader, config_rx, _request_tx) = ConfigReloader::new(&config);
+        assert_eq!(config_rx.borrow().gate_triggers()[0].gate_zone, GeometryId(2003));
+
+        let moved = config_toml("7000", 9090).replace("gate_zone = 2003", "gate_zone = 2005");
+        std::fs::write(file.path(), moved + trigger).unwrap();
+        let report = reloader.reload().unwrap();
+
+        let applied: Vec<_> = report.applied.iter().map(|c| c.field).collect();
+        assert!(applied.contains(&"gate_zone"));
+        assert!(applied.contains(&"gate_triggers"));
+        assert_eq!(config_rx.borrow().gate_zone(), GeometryId(2005));
+        assert_eq!(config_rx.borrow().gate_triggers()[0].gate_zone, GeometryId(2005));
+    }
+
+    #[test]
+    fn test_invalid_config_is_not_applied() {
+        let file = NamedTempFile::new().unwrap();
+        let (mut reloader, config_rx) = create_reloader(&file);
+
+        std::fs::write(file.path(), config_toml("\"soon\"", 9090)).unwrap();
+        assert!(reloader.reload().is_err());
+        assert!(!config_rx.has_changed().unwrap());
+        assert_eq!(config_rx.borrow().min_dwell_ms(), 7000);
+    }
+}
@@ -3,8 +3,9 @@
 //! Correlates gate commands sent by the tracker with actual door state
 //! changes from the RS485 monitor.
 
-use crate::domain::journey::{epoch_ms, JourneyEvent, JourneyEventType};
+use crate::domain::journey::{JourneyEvent, JourneyEventType};
 use crate::domain::types::{DoorStatus, TrackId};
+use crate::infra::clock::SharedClock;
 use crate::services::journey_manager::JourneyManager;
 use smallvec::SmallVec;
 use std::time::Instant;
@@ -31,21 +32,23 @@ pub struct DoorCorrelator {
     pending_cmds: SmallVec<[PendingGateCmd; 2]>,
     /// Track ID of current gate flow (preserved across open/moving/closed cycle)
     current_flow_track_id: Option<TrackId>,
+    clock: SharedClock,
 }
 
 impl DoorCorrelator {
-    pub fn new() -> Self {
+    pub fn new(clock: SharedClock) -> Self {
         Self {
             last_status: DoorStatus::Unknown,
             pending_cmds: SmallVec::new(),
             current_flow_track_id: None,
+            clock,
         }
     }
 
     /// Record that a gate command was sent for a track
     pub fn record_gate_cmd(&mut self, track_id: TrackId) {
-        let now = Instant::now();
-        let now_ms = epoch_ms();
+        let now = self.clock.now();
+        let now_ms = self.clock.epoch_ms();
 
         // Record door state at time of command (per-command, not global)
         let door_was_open = self.last_status == DoorStatus::Open;
@@ -93,8 +96,8 @@ impl DoorCorrelator {
             return self.current_flow_track_id; // Return current flow even if no new correlation
         }
 
-        let now = Instant::now();
-        let now_ms = epoch_ms();
+        let now = self.clock.now();
+        let now_ms = self.clock.epoch_ms();
 
         // Find the most recent (newest) gate command within window
         // Iterate from end to find the newest valid command
@@ -147,7 +150,7 @@ impl DoorCorrelator {
 
     /// Clean up gate commands older than correlation window
     fn cleanup_old_cmds(&mut self) {
-        let now = Instant::now();
+        let now = self.clock.now();
         self.pending_cmds.retain(|cmd| {
             let elapsed_ms = now.duration_since(cmd.sent_at).as_millis() as u64;
             elapsed_ms <= MAX_GATE_CORRELATION_MS * 2 // Keep a bit longer for safety
@@ -173,20 +176,17 @@ impl DoorCorrelator {
     }
 }
 
-impl Default for DoorCorrelator {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::infra::clock::{ManualClock, SystemClock};
     use crate::services::journey_manager::JourneyManager;
+    use std::sync::Arc;
+    use std::time::Duration;
 
     #[test]
     fn test_gate_cmd_recorded() {
-        let mut correlator = DoorCorrelator::new();
+        let mut correlator = DoorCorrelator::new(SystemClock::shared());
 
         correlator.record_gate_cmd(TrackId(100));
 
@@ -196,8 +196,8 @@ mod tests {
 
     #[test]
     fn test_door_open_correlates() {
-        let mut correlator = DoorCorrelator::new();
-        let mut jm = JourneyManager::new();
+        let mut correlator = DoorCorrelator::new(SystemClock::shared());
+        let mut jm = JourneyManager::new(SystemClock::shared());
         jm.new_journey(TrackId(100));
 
         // Record gate command
@@ -214,8 +214,8 @@ mod tests {
 
     #[test]
     fn test_door_was_already_open() {
-        let mut correlator = DoorCorrelator::new();
-        let mut jm = JourneyManager::new();
+        let mut correlator = DoorCorrelator::new(SystemClock::shared());
+        let mut jm = JourneyManager::new(SystemClock::shared());
         jm.new_journey(TrackId(100));
 
         // Door is already open when command sent
@@ -234,8 +234,8 @@ mod tests {
 
     #[test]
     fn test_no_correlation_without_cmd() {
-        let mut correlator = DoorCorrelator::new();
-        let mut jm = JourneyManager::new();
+        let mut correlator = DoorCorrelator::new(SystemClock::shared());
+        let mut jm = JourneyManager::new(SystemClock::shared());
 
         // Door opens without any gate command
         let result = correlator.process_door_state(DoorStatus::Open, &mut jm);
@@ -245,8 +245,8 @@ mod tests {
 
     #[test]
     fn test_no_correlation_door_closed() {
-        let mut correlator = DoorCorrelator::new();
-        let mut jm = JourneyManager::new();
+        let mut correlator = DoorCorrelator::new(SystemClock::shared());
+        let mut jm = JourneyManager::new(SystemClock::shared());
         jm.new_journey(TrackId(100));
 
         correlator.record_gate_cmd(TrackId(100));
@@ -259,8 +259,8 @@ mod tests {
 
     #[test]
     fn test_no_correlation_already_open() {
-        let mut correlator = DoorCorrelator::new();
-        let mut jm = JourneyManager::new();
+        let mut correlator = DoorCorrelator::new(SystemClock::shared());
+        let mut jm = JourneyManager::new(SystemClock::shared());
         jm.new_journey(TrackId(100));
 
         // Door already open
@@ -275,16 +275,13 @@ mod tests {
 
     #[test]
     fn test_cleanup_old_cmds() {
-        let mut correlator = DoorCorrelator::new();
-        let mut jm = JourneyManager::new();
-
-        // Add a command with artificially old timestamp (older than 2x correlation window)
-        correlator.pending_cmds.push(PendingGateCmd {
-            track_id: TrackId(100),
-            sent_at: Instant::now() - std::time::Duration::from_secs(25),
-            _sent_at_ms: 0,
-            door_was_open: false,
-        });
+        let clock = Arc::new(ManualClock::new(1_000_000));
+        let mut correlator = DoorCorrelator::new(clock.clone());
+        let mut jm = JourneyManager::new(clock.clone());
+
+        // Command older than 2x correlation window
+        correlator.record_gate_cmd(TrackId(100));
+        clock.advance(Duration::from_secs(25));
 
         // Process door state (triggers cleanup)
         correlator.process_door_state(DoorStatus::Closed, &mut jm);
@@ -293,10 +290,29 @@ mod tests {
         assert!(correlator.pending_cmds.is_empty());
     }
 
+    #[test]
+    fn test_correlation_window_edges() {
+        let clock = Arc::new(ManualClock::new(1_000_000));
+        let mut correlator = DoorCorrelator::new(clock.clone());
+        let mut jm = JourneyManager::new(clock.clone());
+        jm.new_journey(TrackId(100));
+        jm.new_journey(TrackId(200));
+
+        correlator.record_gate_cmd(TrackId(100));
+        clock.advance(Duration::from_millis(MAX_GATE_CORRELATION_MS));
+        assert_eq!(correlator.process_door_state(DoorStatus::Open, &mut jm), Some(TrackId(100)));
+        assert_eq!(jm.get(TrackId(100)).unwrap().gate_opened_at, Some(1_010_000));
+
+        correlator.process_door_state(DoorStatus::Closed, &mut jm);
+        correlator.record_gate_cmd(TrackId(200));
+        clock.advance(Duration::from_millis(MAX_GATE_CORRELATION_MS + 1));
+        assert_eq!(correlator.process_door_state(DoorStatus::Open, &mut jm), None);
+    }
+
     #[test]
     fn test_moving_to_open_transition() {
-        let mut correlator = DoorCorrelator::new();
-        let mut jm = JourneyManager::new();
+        let mut correlator = DoorCorrelator::new(SystemClock::shared());
+        let mut jm = JourneyManager::new(SystemClock::shared());
         jm.new_journey(TrackId(100));
 
         correlator.record_gate_cmd(TrackId(100));
@@ -312,8 +328,8 @@ mod tests {
 
     #[test]
     fn test_newest_command_selected() {
-        let mut correlator = DoorCorrelator::new();
-        let mut jm = JourneyManager::new();
+        let mut correlator = DoorCorrelator::new(SystemClock::shared());
+        let mut jm = JourneyManager::new(SystemClock::shared());
         jm.new_journey(TrackId(100));
         jm.new_journey(TrackId(200));
 
@@ -333,8 +349,8 @@ mod tests {
 
     #[test]
     fn test_per_command_door_was_open() {
-        let mut correlator = DoorCorrelator::new();
-        let mut jm = JourneyManager::new();
+        let mut correlator = DoorCorrelator::new(SystemClock::shared());
+        let mut jm = JourneyManager::new(SystemClock::shared());
         jm.new_journey(TrackId(100));
         jm.new_journey(TrackId(200));
 
@@ -0,0 +1,259 @@
+//! Per-sensor reorder and de-duplication window for Xovis frames
+//!
+//! After a broker reconnect frames can arrive duplicated or out of order, which
+//! yields double ZONE_ENTRY or ZONE_EXIT before ENTRY. Frames are held for up
+//! to `window_ms` and released in frame-time order:
+//! - a frame is released once a frame `window_ms` newer arrived from the same
+//!   sensor, or after it was held `window_ms` of wall time (`flush`)
+//! - a frame with the same time as a held or recently released one is an exact
+//!   duplicate and dropped
+//! - a frame older than the last released one is too late and dropped
+//!
+//! Frames without a parseable time (0) bypass the window. Holding frames adds
+//! up to `window_ms` to every gate decision, so the window is opt-in.
+
+use crate::infra::config::Config;
+use crate::infra::metrics::Metrics;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+/// Released frame times remembered for duplicate detection
+const RECENT_RELEASED: usize = 64;
+
+/// A frame leaving the window
+#[derive(Debug)]
+pub struct Released<T> {
+    /// Sensor index (config order)
+    pub sensor: usize,
+    pub frame: T,
+    pub received_at: Instant,
+}
+
+#[derive(Debug)]
+struct SensorWindow<T> {
+    /// Held frames by frame time
+    pending: BTreeMap<u64, (T, Instant)>,
+    /// Newest frame time seen
+    newest_ms: u64,
+    /// Time of the last released frame
+    released_ms: u64,
+    /// Recently released frame times
+    recent: VecDeque<u64>,
+}
+
+impl<T> SensorWindow<T> {
+    fn new() -> Self {
+        Self { pending: BTreeMap::new(), newest_ms: 0, released_ms: 0, recent: VecDeque::new() }
+    }
+
+    fn release(&mut self, sensor: usize, time_ms: u64, out: &mut Vec<Released<T>>) {
+        if let Some((frame, received_at)) = self.pending.remove(&time_ms) {
+            self.released_ms = time_ms;
+            if self.recent.len() == RECENT_RELEASED {
+                self.recent.pop_front();
+            }
+            self.recent.push_back(time_ms);
+            out.push(Released { sensor, frame, received_at });
+        }
+    }
+}
+
+/// Reorders and de-duplicates frames per sensor by frame time
+pub struct FrameReorder<T> {
+    window_ms: u64,
+    sensors: Vec<SensorWindow<T>>,
+    metrics: Arc<Metrics>,
+}
+
+impl<T> FrameReorder<T> {
+    /// Create the window from `[frame_reorder]` (`None` when disabled)
+    pub fn new(config: &Config, metrics: Arc<Metrics>) -> Option<Self> {
+        let reorder = config.frame_reorder();
+        if !reorder.enabled {
+            return None;
+        }
+        info!(window_ms = %reorder.window_ms, "frame_reorder_enabled");
+        let sensors = config.sensors().iter().map(|_| SensorWindow::new()).collect();
+        Some(Self { window_ms: reorder.window_ms, sensors, metrics })
+    }
+
+    /// How long held frames may wait before `flush` releases them
+    pub fn window(&self) -> Duration {
+        Duration::from_millis(self.window_ms)
+    }
+
+    /// Add a frame from `sensor` and return the frames that can be released
+    pub fn push(
+        &mut self,
+        sensor: usize,
+        time_ms: u64,
+        frame: T,
+        received_at: Instant,
+    ) -> Vec<Released<T>> {
+        let mut out = Vec::new();
+        let Some(window) = self.sensors.get_mut(sensor) else {
+            return out;
+        };
+        if time_ms == 0 {
+            out.push(Released { sensor, frame, received_at });
+            return out;
+        }
+
+        if window.pending.contains_key(&time_ms) || window.recent.contains(&time_ms) {
+            self.metrics.record_frame_duplicate();
+            debug!(sensor = %sensor, time_ms = %time_ms, "frame_duplicate_dropped");
+            return out;
+        }
+        if time_ms < window.released_ms {
+            self.metrics.record_frame_too_late();
+            debug!(
+                sensor = %sensor,
+                time_ms = %time_ms,
+                released_ms = %window.released_ms,
+                "frame_too_late_dropped"
+            );
+            return out;
+        }
+        if time_ms < window.newest_ms {
+            self.metrics.record_frame_reordered();
+        }
+
+        window.newest_ms = window.newest_ms.max(time_ms);
+        window.pending.insert(time_ms, (frame, received_at));
+
+        let watermark = window.newest_ms.saturating_sub(self.window_ms);
+        while let Some(&oldest) = window.pending.keys().next() {
+            if oldest > watermark {
+                break;
+            }
+            window.release(sensor, oldest, &mut out);
+        }
+        out
+    }
+
+    /// Release frames held for at least the window (sensor went quiet)
+    pub fn flush(&mut self, now: Instant) -> Vec<Released<T>> {
+        let window = self.window();
+        let mut out = Vec::new();
+        for (sensor, sensor_window) in self.sensors.iter_mut().enumerate() {
+            // Release in time order up to the newest expired frame
+            let expired = sensor_window
+                .pending
+                .iter()
+                .filter(|(_, (_, received_at))| now.duration_since(*received_at) >= window)
+                .map(|(&time_ms, _)| time_ms)
+                .max();
+            let Some(expired) = expired else { continue };
+            while let Some(&oldest) = sensor_window.pending.keys().next() {
+                if oldest > expired {
+                    break;
+                }
+                sensor_window.release(sensor, oldest, &mut out);
+            }
+        }
+        out
+    }
+
+    /// Release every held frame in time order (shutdown)
+    pub fn drain(&mut self) -> Vec<Released<T>> {
+        let mut out = Vec::new();
+        for (sensor, sensor_window) in self.sensors.iter_mut().enumerate() {
+            while let Some(&oldest) = sensor_window.pending.keys().next() {
+                sensor_window.release(sensor, oldest, &mut out);
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_reorder() -> (FrameReorder<&'static str>, Arc<Metrics>) {
+        let config = Config::default().with_frame_reorder(200);
+        let metrics = Arc::new(Metrics::new());
+        (FrameReorder::new(&config, metrics.clone()).unwrap(), metrics)
+    }
+
+    fn frames(released: Vec<Released<&'static str>>) -> Vec<&'static str> {
+        released.into_iter().map(|r| r.frame).collect()
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        let metrics = Arc::new(Metrics::new());
+        assert!(FrameReorder::<()>::new(&Config::default(), metrics).is_none());
+    }
+
+    #[test]
+    fn test_out_of_order_frames_released_in_time_order() {
+        let (mut reorder, metrics) = create_reorder();
+        let now = Instant::now();
+
+        assert!(reorder.push(0, 1000, "a", now).is_empty());
+        assert!(reorder.push(0, 1100, "c", now).is_empty());
+        // Arrives after c but belongs before it
+        assert!(reorder.push(0, 1050, "b", now).is_empty());
+        assert_eq!(frames(reorder.push(0, 1300, "d", now)), vec!["a", "b", "c"]);
+
+        let summary = metrics.report(0, 0);
+        assert_eq!(summary.frames_reordered_total, 1);
+        assert_eq!(summary.frames_duplicate_total, 0);
+    }
+
+    #[test]
+    fn test_duplicates_and_late_frames_dropped() {
+        let (mut reorder, metrics) = create_reorder();
+        let now = Instant::now();
+
+        reorder.push(0, 1000, "a", now);
+        assert!(reorder.push(0, 1000, "a again", now).is_empty());
+        assert_eq!(frames(reorder.push(0, 1250, "b", now)), vec!["a"]);
+
+        // Replayed after release: still a duplicate
+        assert!(reorder.push(0, 1000, "a replay", now).is_empty());
+        // Older than the last released frame
+        assert!(reorder.push(0, 900, "late", now).is_empty());
+
+        let summary = metrics.report(0, 0);
+        assert_eq!(summary.frames_duplicate_total, 2);
+        assert_eq!(summary.frames_too_late_total, 1);
+    }
+
+    #[test]
+    fn test_flush_releases_held_frames_after_window() {
+        let (mut reorder, _) = create_reorder();
+        let start = Instant::now();
+
+        reorder.push(0, 1000, "a", start);
+        reorder.push(0, 1100, "b", start + Duration::from_millis(100));
+        assert!(reorder.flush(start + Duration::from_millis(150)).is_empty());
+
+        let released = reorder.flush(start + Duration::from_millis(250));
+        assert_eq!(frames(released), vec!["a"]);
+        let released = reorder.flush(start + Duration::from_millis(300));
+        assert_eq!(released.len(), 1);
+        assert_eq!(released[0].sensor, 0);
+    }
+
+    #[test]
+    fn test_drain_releases_all_held_frames() {
+        let (mut reorder, _) = create_reorder();
+        let start = Instant::now();
+
+        reorder.push(0, 1100, "b", start);
+        reorder.push(0, 1000, "a", start);
+        let released = reorder.drain();
+        assert_eq!(frames(released), vec!["a", "b"]);
+        assert!(reorder.flush(start + Duration::from_secs(1)).is_empty());
+    }
+
+    #[test]
+    fn test_untimed_frames_bypass_window() {
+        let (mut reorder, _) = create_reorder();
+        assert_eq!(frames(reorder.push(0, 0, "untimed", Instant::now())), vec!["untimed"]);
+    }
+}
@@ -1,11 +1,21 @@
 //! Gate control via HTTP or TCP (CloudPlus) commands
-
+//!
+//! In dry run (`mode = "dry_run"`, or switched on at runtime through the admin
+//! API) commands are logged with what would have been sent, and nothing is.
+//! Switching dry run at runtime is published as a gate state event.
+//!
+//! A gateway drives a single gate (`[gate]`), so the dry run flag of its one
+//! `GateController` is the per-gate toggle; `/gate/dry-run` takes no gate id.
+
+use crate::domain::journey::epoch_ms;
 use crate::domain::types::TrackId;
 use crate::infra::config::{Config, GateMode};
 use crate::infra::metrics::Metrics;
 use crate::io::cloudplus::{CloudPlusClient, CloudPlusConfig};
+use crate::io::egress_channel::{EgressSender, GateStatePayload};
 use async_trait::async_trait;
 use base64::{engine::general_purpose::STANDARD, Engine};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::{error, info, warn};
@@ -15,6 +25,16 @@ use tracing::{error, info, warn};
 pub trait GateCommand: Send + Sync {
     /// Send gate open command, returns latency in microseconds
     async fn send_open_command(&self, track_id: TrackId) -> u64;
+
+    /// Whether commands are logged instead of sent
+    fn is_dry_run(&self) -> bool {
+        false
+    }
+
+    /// Switch dry run on or off; false if this gate cannot
+    fn set_dry_run(&self, _dry_run: bool) -> bool {
+        false
+    }
 }
 
 /// Log TCP client not initialized error (cold path)
@@ -59,6 +79,11 @@ fn log_http_command_error(track_id: TrackId, latency_us: u64, e: &reqwest::Error
 
 pub struct GateController {
     mode: GateMode,
+    /// Log commands instead of sending them (always set in `GateMode::DryRun`);
+    /// per gate, as each gate has its own controller
+    dry_run: AtomicBool,
+    /// Configured TCP address (logged in dry run)
+    tcp_addr: String,
     // HTTP mode
     url: String,
     username: Option<String>,
@@ -68,6 +93,8 @@ pub struct GateController {
     tcp_client: Option<Arc<CloudPlusClient>>,
     // Metrics for tracking dropped commands
     metrics: Option<Arc<Metrics>>,
+    // MQTT egress for dry run changes
+    egress: Option<EgressSender>,
 }
 
 impl GateController {
@@ -91,7 +118,28 @@ impl GateController {
             None
         };
 
-        Self { mode: config.gate_mode(), url, username, password, http_client, tcp_client, metrics }
+        let dry_run = config.gate_mode() == GateMode::DryRun;
+        if let Some(ref metrics) = metrics {
+            metrics.set_gate_dry_run(dry_run);
+        }
+        Self {
+            mode: config.gate_mode(),
+            dry_run: AtomicBool::new(dry_run),
+            tcp_addr: config.gate_tcp_addr().to_string(),
+            url,
+            username,
+            password,
+            http_client,
+            tcp_client,
+            metrics,
+            egress: None,
+        }
+    }
+
+    /// Publish runtime dry run changes to MQTT egress
+    pub fn with_egress(mut self, egress: Option<EgressSender>) -> Self {
+        self.egress = egress;
+        self
     }
 
     /// Get the TCP client for running in main
@@ -168,6 +216,22 @@ impl GateController {
         }
     }
 
+    /// Log the command that would have been sent
+    fn log_dry_run(&self, track_id: TrackId, start: Instant) -> u64 {
+        let target = match self.mode {
+            GateMode::Tcp => self.tcp_addr.as_str(),
+            GateMode::Http => self.url.as_str(),
+            GateMode::DryRun => "none",
+        };
+        info!(
+            track_id = %track_id,
+            mode = ?self.mode,
+            target = %target,
+            "gate_open_command_dry_run"
+        );
+        start.elapsed().as_micros() as u64
+    }
+
     async fn send_open_http(&self, track_id: TrackId, start: Instant) -> u64 {
         let Some(ref client) = self.http_client else {
             log_http_client_not_initialized(track_id);
@@ -212,11 +276,37 @@ impl GateController {
 impl GateCommand for GateController {
     async fn send_open_command(&self, track_id: TrackId) -> u64 {
         let start = Instant::now();
+        if self.is_dry_run() {
+            return self.log_dry_run(track_id, start);
+        }
         match self.mode {
             GateMode::Tcp => self.send_open_tcp(track_id, start).await,
             GateMode::Http => self.send_open_http(track_id, start).await,
+            GateMode::DryRun => self.log_dry_run(track_id, start),
         }
     }
+
+    fn is_dry_run(&self) -> bool {
+        self.dry_run.load(Ordering::Relaxed)
+    }
+
+    /// A gate configured as `dry_run` has no target to switch to
+    fn set_dry_run(&self, dry_run: bool) -> bool {
+        if !dry_run && self.mode == GateMode::DryRun {
+            return false;
+        }
+        if self.dry_run.swap(dry_run, Ordering::Relaxed) != dry_run {
+            info!(dry_run = %dry_run, mode = ?self.mode, "gate_dry_run_changed");
+            if let Some(ref sender) = self.egress {
+                let state = if dry_run { "dry_run_on" } else { "dry_run_off" };
+                sender.send_gate_state(GateStatePayload::new(epoch_ms(), state, None, "api"));
+            }
+        }
+        if let Some(ref metrics) = self.metrics {
+            metrics.set_gate_dry_run(dry_run);
+        }
+        true
+    }
 }
 
 /// Mock gate controller for testing
@@ -241,6 +331,7 @@ impl GateCommand for MockGateController {
 mod tests {
     use super::*;
     use crate::domain::types::TrackId;
+    use crate::io::egress_channel::{create_egress_channel, EgressMessage};
 
     #[test]
     fn test_parse_url_with_auth() {
@@ -261,6 +352,47 @@ mod tests {
         assert_eq!(pass, None);
     }
 
+    fn create_gate(mode: GateMode) -> GateController {
+        let config = Config::default().with_gate_mode(mode);
+        GateController::new(config, Some(Arc::new(Metrics::new())))
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_mode_sends_nothing() {
+        let gate = create_gate(GateMode::DryRun);
+        assert!(gate.is_dry_run());
+        assert!(gate.tcp_client().is_none());
+        gate.send_open_command(TrackId(100)).await;
+
+        // Nothing to switch to
+        assert!(!gate.set_dry_run(false));
+        assert!(gate.is_dry_run());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_toggle() {
+        let (egress, mut egress_rx) = create_egress_channel(8, "test".to_string());
+        let gate = create_gate(GateMode::Http).with_egress(Some(egress));
+        assert!(!gate.is_dry_run());
+
+        assert!(gate.set_dry_run(true));
+        assert!(gate.is_dry_run());
+        assert!(gate.metrics.as_ref().unwrap().report(0, 0).gate_dry_run);
+        // Logged only: the unreachable URL is never requested
+        gate.send_open_command(TrackId(100)).await;
+
+        // Published once per change
+        assert!(gate.set_dry_run(true));
+        assert!(gate.set_dry_run(false));
+        assert!(!gate.is_dry_run());
+        let states: Vec<String> = std::iter::from_fn(|| match egress_rx.try_recv() {
+            Ok(EgressMessage::GateState(payload)) => Some(payload.state),
+            _ => None,
+        })
+        .collect();
+        assert_eq!(states, ["dry_run_on", "dry_run_off"]);
+    }
+
     #[tokio::test]
     async fn test_mock_gate_command() {
         let gate = MockGateController;
@@ -0,0 +1,219 @@
+//! Virtual pre-gate triggers
+//!
+//! Evaluates `[[gate_triggers]]` polygons and lines on tracked object positions
+//! in the ingest path. The sensor reports the GATE zone entry roughly 300ms
+//! after a person passes y = -0.5 (see pos.md); a trigger placed there lets the
+//! tracker open the gate for authorized tracks that much earlier.
+//!
+//! Each trigger fires at most once per track. The tracker records both the
+//! trigger and the real GATE zone entry so the lead time can be measured.
+
+use crate::domain::types::{EventType, ParsedEvent, TrackId, TrackedObject};
+use crate::infra::config::{Config, GateTrigger};
+use crate::infra::geometry::GeometryKind;
+use crate::services::stale_tracks::StalePruner;
+use rustc_hash::FxHashMap;
+use smallvec::SmallVec;
+use std::time::Instant;
+use tracing::{debug, info};
+
+#[derive(Debug)]
+struct TrackState {
+    last_xy: Option<[f64; 2]>,
+    last_seen_ms: u64,
+    /// Indices of triggers that already fired for this track
+    fired: SmallVec<[usize; 2]>,
+}
+
+/// Detects tracks reaching a virtual pre-gate trigger
+pub struct GateTriggerDetector {
+    triggers: Vec<GateTrigger>,
+    tracks: FxHashMap<TrackId, TrackState>,
+    pruner: StalePruner,
+}
+
+impl GateTriggerDetector {
+    /// Create a detector for the configured triggers (`None` if there are none)
+    pub fn new(config: &Config) -> Option<Self> {
+        let triggers = config.gate_triggers().to_vec();
+        if triggers.is_empty() {
+            return None;
+        }
+        for trigger in &triggers {
+            info!(
+                name = %trigger.name(),
+                gate_zone = %trigger.gate_zone.0,
+                kind = ?trigger.shape.kind,
+                "gate_trigger_enabled"
+            );
+        }
+        Some(Self { triggers, tracks: FxHashMap::default(), pruner: StalePruner::default() })
+    }
+
+    /// Swap in reloaded triggers; per-track state is reset if they changed
+    pub fn set_triggers(&mut self, triggers: &[GateTrigger]) {
+        if self.triggers == triggers {
+            return;
+        }
+        self.triggers = triggers.to_vec();
+        self.tracks.clear();
+        for trigger in &self.triggers {
+            info!(
+                name = %trigger.name(),
+                gate_zone = %trigger.gate_zone.0,
+                "gate_trigger_reloaded"
+            );
+        }
+    }
+
+    /// Evaluate one sensor message and return GateTrigger events
+    ///
+    /// `events` are the sensor events of the same message (used to forget
+    /// deleted tracks).
+    pub fn process(
+        &mut self,
+        events: &[ParsedEvent],
+        objects: &[TrackedObject],
+        now_ms: u64,
+        received_at: Instant,
+    ) -> Vec<ParsedEvent> {
+        for event in events {
+            if event.event_type == EventType::TrackDelete {
+                self.tracks.remove(&event.track_id);
+            }
+        }
+
+        let mut out = Vec::new();
+        for obj in objects {
+            if TrackId(obj.track_id).is_group() || obj.position.len() < 2 {
+                continue;
+            }
+            let track_id = TrackId(obj.track_id);
+            let xy = [obj.position[0], obj.position[1]];
+
+            let state = self.tracks.entry(track_id).or_insert_with(|| TrackState {
+                last_xy: None,
+                last_seen_ms: now_ms,
+                fired: SmallVec::new(),
+            });
+            let from = state.last_xy.replace(xy);
+            state.last_seen_ms = now_ms;
+
+            for (index, trigger) in self.triggers.iter().enumerate() {
+                if state.fired.contains(&index) {
+                    continue;
+                }
+                // Polygons can fire on first sighting, lines need a previous position
+                let hit = match trigger.shape.kind {
+                    GeometryKind::Zone => trigger.shape.contains(xy[0], xy[1]),
+                    GeometryKind::Line => from.is_some_and(|from| trigger.shape.crosses(from, xy)),
+                };
+                if !hit {
+                    continue;
+                }
+                debug!(track_id = %track_id, trigger = %trigger.name(), "gate_trigger_fired");
+                state.fired.push(index);
+                out.push(ParsedEvent {
+                    event_type: EventType::GateTrigger,
+                    track_id,
+                    geometry_id: Some(trigger.gate_zone),
+                    direction: None,
+                    event_time: now_ms,
+                    received_at,
+                    position: (obj.position.len() >= 3)
+                        .then(|| [obj.position[0], obj.position[1], obj.position[2]]),
+                    derived: false,
+                    obj_type: None,
+                });
+            }
+        }
+
+        self.pruner.retain(&mut self.tracks, now_ms, |t| t.last_seen_ms);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::types::GeometryId;
+    use smallvec::smallvec;
+    use std::sync::Arc;
+
+    fn create_detector() -> GateTriggerDetector {
+        let config = Config::default().with_gate_trigger_line(vec![[1.0, -0.5], [3.0, -0.5]]);
+        GateTriggerDetector::new(&config).unwrap()
+    }
+
+    fn step(detector: &mut GateTriggerDetector, track_id: i64, y: f64) -> Vec<ParsedEvent> {
+        let obj = TrackedObject {
+            track_id,
+            obj_type: Arc::from("PERSON"),
+            position: smallvec![2.0, y, 1.7],
+        };
+        detector.process(&[], &[obj], 0, Instant::now())
+    }
+
+    #[test]
+    fn test_no_triggers_configured() {
+        assert!(GateTriggerDetector::new(&Config::default()).is_none());
+    }
+
+    #[test]
+    fn test_line_fires_once_per_track() {
+        let mut detector = create_detector();
+
+        assert!(step(&mut detector, 100, -1.2).is_empty());
+        assert!(step(&mut detector, 100, -0.9).is_empty());
+        let out = step(&mut detector, 100, -0.4);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].event_type, EventType::GateTrigger);
+        assert_eq!(out[0].track_id, TrackId(100));
+        assert_eq!(out[0].geometry_id, Some(GeometryId(1007)));
+
+        // Walking back and forth does not fire again
+        assert!(step(&mut detector, 100, -0.8).is_empty());
+        assert!(step(&mut detector, 100, -0.2).is_empty());
+    }
+
+    #[test]
+    fn test_reloaded_triggers_open_new_gate_zone() {
+        let mut detector = create_detector();
+        let mut triggers = detector.triggers.clone();
+        triggers[0].gate_zone = GeometryId(1017);
+        detector.set_triggers(&triggers);
+
+        assert!(step(&mut detector, 100, -1.2).is_empty());
+        let out = step(&mut detector, 100, -0.4);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].geometry_id, Some(GeometryId(1017)));
+    }
+
+    #[test]
+    fn test_first_sighting_past_line_does_not_fire() {
+        let mut detector = create_detector();
+        assert!(step(&mut detector, 100, -0.2).is_empty());
+        assert!(step(&mut detector, 100, 0.3).is_empty());
+    }
+
+    #[test]
+    fn test_track_delete_forgets_state() {
+        let mut detector = create_detector();
+        step(&mut detector, 100, -0.8);
+        assert_eq!(step(&mut detector, 100, -0.2).len(), 1);
+
+        let delete = ParsedEvent {
+            event_type: EventType::TrackDelete,
+            track_id: TrackId(100),
+            geometry_id: None,
+            direction: None,
+            event_time: 0,
+            received_at: Instant::now(),
+            position: None,
+            derived: false,
+            obj_type: None,
+        };
+        detector.process(&[delete], &[], 0, Instant::now());
+        assert!(detector.tracks.is_empty());
+    }
+}
@@ -55,6 +55,7 @@ impl GateCmdWorker {
             let queue_delay_us = cmd.enqueued_at.elapsed().as_micros() as u64;
 
             // Send the actual gate command (this may block on network)
+            let dry_run = self.gate.is_dry_run();
             let send_start = Instant::now();
             let send_latency_us = self.gate.send_open_command(cmd.track_id).await;
             let total_send_us = send_start.elapsed().as_micros() as u64;
@@ -65,22 +66,24 @@ impl GateCmdWorker {
                 queue_delay_us = %queue_delay_us,
                 send_latency_us = %send_latency_us,
                 total_send_us = %total_send_us,
+                dry_run = %dry_run,
                 "gate_cmd_processed"
             );
 
-            // Record metrics histograms
+            // Record metrics histograms (a dry run send is only a log line)
             self.metrics.record_gate_queue_delay(queue_delay_us);
-            self.metrics.record_gate_send_latency(send_latency_us);
-
-            // Record total enqueue-to-send time (after send completes)
             let enqueue_to_send_us = cmd.enqueued_at.elapsed().as_micros() as u64;
-            self.metrics.record_gate_enqueue_to_send(enqueue_to_send_us);
+            if !dry_run {
+                self.metrics.record_gate_send_latency(send_latency_us);
+                // Record total enqueue-to-send time (after send completes)
+                self.metrics.record_gate_enqueue_to_send(enqueue_to_send_us);
+            }
 
-            // Emit cmd_sent event with timing info
+            // Emit cmd_sent (cmd_dry_run if only logged) event with timing info
             if let Some(ref sender) = self.egress_sender {
                 sender.send_gate_state(GateStatePayload::with_timing(
                     epoch_ms(),
-                    "cmd_sent",
+                    if dry_run { "cmd_dry_run" } else { "cmd_sent" },
                     Some(cmd.track_id.0),
                     "gate_worker",
                     queue_delay_us,
@@ -0,0 +1,345 @@
+//! Xovis GROUP membership from positions
+//!
+//! Xovis reports a GROUP track (high bit set) next to the individual tracks of
+//! people walking together, but does not say who is in it. Individuals staying
+//! close to a group position for `join_ms` become members and leave once
+//! farther than `leave_radius_m`. A track belongs to at most one group.
+//!
+//! Membership changes are emitted as GroupJoin / GroupLeave events on the
+//! member's track so the tracker can record groups on journeys and optionally
+//! extend ACC authorization to the payer's group.
+
+use crate::domain::types::{EventType, ParsedEvent, TrackId, TrackedObject};
+use crate::infra::config::{Config, GroupConfig};
+use crate::services::stale_tracks::{is_stale, StalePruner};
+use rustc_hash::FxHashMap;
+use smallvec::SmallVec;
+use std::time::Instant;
+use tracing::{debug, info};
+
+#[derive(Debug, Default)]
+struct GroupState {
+    members: SmallVec<[i64; 4]>,
+    /// Tracks within member_radius_m and since when (epoch ms)
+    candidates: SmallVec<[(i64, u64); 4]>,
+    last_seen_ms: u64,
+}
+
+/// Derives GROUP track membership from tracked object positions
+pub struct GroupDetector {
+    config: GroupConfig,
+    groups: FxHashMap<i64, GroupState>,
+    /// Group of each member track
+    member_of: FxHashMap<i64, i64>,
+    /// Last time each individual track was seen (epoch ms)
+    last_seen: FxHashMap<i64, u64>,
+    pruner: StalePruner,
+}
+
+impl GroupDetector {
+    /// Create a detector from `[groups]` (`None` when disabled)
+    pub fn new(config: &Config) -> Option<Self> {
+        let groups = config.groups();
+        if !groups.enabled {
+            return None;
+        }
+        info!(
+            member_radius_m = %groups.member_radius_m,
+            join_ms = %groups.join_ms,
+            acc_extends_to_group = %groups.acc_extends_to_group,
+            "group_membership_enabled"
+        );
+        Some(Self {
+            config: groups.clone(),
+            groups: FxHashMap::default(),
+            member_of: FxHashMap::default(),
+            last_seen: FxHashMap::default(),
+            pruner: StalePruner::default(),
+        })
+    }
+
+    /// Evaluate one sensor message and return GroupJoin / GroupLeave events
+    ///
+    /// `events` are the sensor events of the same message (used to forget
+    /// deleted tracks).
+    pub fn process(
+        &mut self,
+        events: &[ParsedEvent],
+        objects: &[TrackedObject],
+        now_ms: u64,
+        received_at: Instant,
+    ) -> Vec<ParsedEvent> {
+        let mut out = Vec::new();
+        for event in events.iter().filter(|e| e.event_type == EventType::TrackDelete) {
+            let track_id = event.track_id.0;
+            if event.track_id.is_group() {
+                self.remove_group(track_id, now_ms, received_at, &mut out);
+            } else {
+                self.forget_track(track_id, now_ms, received_at, &mut out);
+            }
+        }
+
+        let individuals: SmallVec<[(i64, [f64; 2]); 16]> = objects
+            .iter()
+            .filter(|o| !TrackId(o.track_id).is_group() && o.position.len() >= 2)
+            .map(|o| (o.track_id, [o.position[0], o.position[1]]))
+            .collect();
+        for &(track_id, _) in &individuals {
+            self.last_seen.insert(track_id, now_ms);
+        }
+
+        for group in objects.iter().filter(|o| TrackId(o.track_id).is_group()) {
+            if group.position.len() < 2 {
+                continue;
+            }
+            let group_id = group.track_id;
+            let state = self.groups.entry(group_id).or_default();
+            state.last_seen_ms = now_ms;
+
+            let mut joins: SmallVec<[i64; 4]> = SmallVec::new();
+            let mut leaves: SmallVec<[i64; 4]> = SmallVec::new();
+            for &(track_id, xy) in &individuals {
+                let distance = (xy[0] - group.position[0]).hypot(xy[1] - group.position[1]);
+                if state.members.contains(&track_id) {
+                    if distance > self.config.leave_radius_m {
+                        leaves.push(track_id);
+                    }
+                    continue;
+                }
+                if distance > self.config.member_radius_m {
+                    state.candidates.retain(|(tid, _)| *tid != track_id);
+                    continue;
+                }
+                match state.candidates.iter().find(|(tid, _)| *tid == track_id) {
+                    Some(&(_, since_ms)) => {
+                        if now_ms.saturating_sub(since_ms) >= self.config.join_ms {
+                            joins.push(track_id);
+                        }
+                    }
+                    None => state.candidates.push((track_id, now_ms)),
+                }
+            }
+
+            for track_id in leaves {
+                self.leave(track_id, now_ms, received_at, &mut out);
+            }
+            for track_id in joins {
+                self.join(group_id, track_id, now_ms, received_at, &mut out);
+            }
+        }
+
+        self.prune_stale(now_ms, received_at, &mut out);
+        out
+    }
+
+    fn join(
+        &mut self,
+        group_id: i64,
+        track_id: i64,
+        now_ms: u64,
+        received_at: Instant,
+        out: &mut Vec<ParsedEvent>,
+    ) {
+        // A track walks with one group at a time
+        self.leave(track_id, now_ms, received_at, out);
+        let Some(state) = self.groups.get_mut(&group_id) else { return };
+        state.candidates.retain(|(tid, _)| *tid != track_id);
+        state.members.push(track_id);
+        self.member_of.insert(track_id, group_id);
+        debug!(track_id = %track_id, group = %group_id, "group_member_joined");
+        out.push(group_event(
+            EventType::GroupJoin(TrackId(group_id)),
+            track_id,
+            now_ms,
+            received_at,
+        ));
+    }
+
+    fn leave(
+        &mut self,
+        track_id: i64,
+        now_ms: u64,
+        received_at: Instant,
+        out: &mut Vec<ParsedEvent>,
+    ) {
+        let Some(group_id) = self.member_of.remove(&track_id) else { return };
+        if let Some(state) = self.groups.get_mut(&group_id) {
+            state.members.retain(|tid| *tid != track_id);
+        }
+        debug!(track_id = %track_id, group = %group_id, "group_member_left");
+        out.push(group_event(
+            EventType::GroupLeave(TrackId(group_id)),
+            track_id,
+            now_ms,
+            received_at,
+        ));
+    }
+
+    fn remove_group(
+        &mut self,
+        group_id: i64,
+        now_ms: u64,
+        received_at: Instant,
+        out: &mut Vec<ParsedEvent>,
+    ) {
+        let Some(state) = self.groups.remove(&group_id) else { return };
+        for track_id in state.members {
+            self.member_of.remove(&track_id);
+            out.push(group_event(
+                EventType::GroupLeave(TrackId(group_id)),
+                track_id,
+                now_ms,
+                received_at,
+            ));
+        }
+    }
+
+    /// Drop a deleted or vanished individual track from its group and all candidate lists
+    fn forget_track(
+        &mut self,
+        track_id: i64,
+        now_ms: u64,
+        received_at: Instant,
+        out: &mut Vec<ParsedEvent>,
+    ) {
+        self.last_seen.remove(&track_id);
+        self.leave(track_id, now_ms, received_at, out);
+        for state in self.groups.values_mut() {
+            state.candidates.retain(|(tid, _)| *tid != track_id);
+        }
+    }
+
+    /// Dissolve groups and forget individual tracks that disappeared without a TRACK_DELETE
+    fn prune_stale(&mut self, now_ms: u64, received_at: Instant, out: &mut Vec<ParsedEvent>) {
+        if !self.pruner.due(now_ms) {
+            return;
+        }
+        let stale: SmallVec<[i64; 4]> = self
+            .groups
+            .iter()
+            .filter(|(_, g)| is_stale(g.last_seen_ms, now_ms))
+            .map(|(&id, _)| id)
+            .collect();
+        for group_id in stale {
+            self.remove_group(group_id, now_ms, received_at, out);
+        }
+        let stale: SmallVec<[i64; 4]> = self
+            .last_seen
+            .iter()
+            .filter(|(_, &last_seen_ms)| is_stale(last_seen_ms, now_ms))
+            .map(|(&id, _)| id)
+            .collect();
+        for track_id in stale {
+            self.forget_track(track_id, now_ms, received_at, out);
+        }
+    }
+}
+
+fn group_event(
+    event_type: EventType,
+    track_id: i64,
+    now_ms: u64,
+    received_at: Instant,
+) -> ParsedEvent {
+    ParsedEvent {
+        event_type,
+        track_id: TrackId(track_id),
+        geometry_id: None,
+        direction: None,
+        event_time: now_ms,
+        received_at,
+        position: None,
+        derived: true,
+        obj_type: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::stale_tracks::STALE_TRACK_MS;
+    use smallvec::smallvec;
+    use std::sync::Arc;
+
+    const GROUP: i64 = 7 | TrackId::GROUP_BIT;
+
+    fn object(track_id: i64, x: f64, y: f64) -> TrackedObject {
+        TrackedObject { track_id, obj_type: Arc::from("PERSON"), position: smallvec![x, y, 1.7] }
+    }
+
+    fn step(detector: &mut GroupDetector, member_x: f64, now_ms: u64) -> Vec<ParsedEvent> {
+        let objects = [object(GROUP, 0.0, 0.0), object(100, member_x, 0.0), object(200, 5.0, 0.0)];
+        detector.process(&[], &objects, now_ms, Instant::now())
+    }
+
+    fn create_detector() -> GroupDetector {
+        GroupDetector::new(&Config::default().with_groups(false)).unwrap()
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        assert!(GroupDetector::new(&Config::default()).is_none());
+    }
+
+    #[test]
+    fn test_track_joins_after_staying_close() {
+        let mut detector = create_detector();
+
+        assert!(step(&mut detector, 0.5, 1000).is_empty());
+        assert!(step(&mut detector, 0.6, 2000).is_empty());
+        let out = step(&mut detector, 0.5, 3000);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].track_id, TrackId(100));
+        assert_eq!(out[0].event_type, EventType::GroupJoin(TrackId(GROUP)));
+
+        // Within the leave radius: still a member, no new events
+        assert!(step(&mut detector, 1.5, 4000).is_empty());
+        let out = step(&mut detector, 2.5, 5000);
+        assert_eq!(out[0].event_type, EventType::GroupLeave(TrackId(GROUP)));
+    }
+
+    #[test]
+    fn test_passing_track_does_not_join() {
+        let mut detector = create_detector();
+        step(&mut detector, 0.5, 1000);
+        step(&mut detector, 1.5, 2000);
+        assert!(step(&mut detector, 0.5, 3000).is_empty());
+    }
+
+    #[test]
+    fn test_group_delete_releases_members() {
+        let mut detector = create_detector();
+        step(&mut detector, 0.5, 1000);
+        assert_eq!(step(&mut detector, 0.5, 3000).len(), 1);
+
+        let delete = group_event(EventType::TrackDelete, GROUP, 4000, Instant::now());
+        let out = detector.process(&[delete], &[], 4000, Instant::now());
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].event_type, EventType::GroupLeave(TrackId(GROUP)));
+        assert!(detector.member_of.is_empty());
+    }
+
+    #[test]
+    fn test_vanished_tracks_are_forgotten() {
+        let mut detector = create_detector();
+        step(&mut detector, 0.5, 1000);
+        assert_eq!(step(&mut detector, 0.5, 3000).len(), 1);
+        // Track 300 becomes a candidate, then both individuals vanish without a TRACK_DELETE
+        let objects = [object(GROUP, 0.0, 0.0), object(100, 0.5, 0.0), object(300, 0.3, 0.0)];
+        detector.process(&[], &objects, 4000, Instant::now());
+        assert_eq!(detector.groups[&GROUP].candidates.len(), 1);
+
+        let group_only = [object(GROUP, 0.0, 0.0)];
+        assert!(detector.process(&[], &group_only, 20_000, Instant::now()).is_empty());
+        let out = detector.process(&[], &group_only, 4000 + STALE_TRACK_MS, Instant::now());
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].track_id, TrackId(100));
+        assert_eq!(out[0].event_type, EventType::GroupLeave(TrackId(GROUP)));
+
+        // Track 200 (seen until 3000) is gone as well; the group itself is still live
+        assert!(detector.member_of.is_empty());
+        assert!(detector.last_seen.is_empty());
+        assert!(detector.groups[&GROUP].members.is_empty());
+        assert!(detector.groups[&GROUP].candidates.is_empty());
+    }
+}
@@ -1,8 +1,10 @@
 //! Journey manager for tracking and persisting customer journeys
 
-use crate::domain::journey::{epoch_ms, Journey, JourneyEvent, JourneyEventType, JourneyOutcome};
+use crate::domain::journey::{Journey, JourneyEvent, JourneyEventType, JourneyOutcome};
 use crate::domain::types::TrackId;
+use crate::infra::clock::SharedClock;
 use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
 use std::time::{Duration, Instant};
 use tracing::{debug, info};
 
@@ -15,6 +17,15 @@ struct PendingEgress {
     eligible_at: Instant,
 }
 
+/// Active and egress-pending journeys for a tracker snapshot
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct JourneysSnapshot {
+    /// Active journeys by current track_id
+    pub active: Vec<(TrackId, Journey)>,
+    /// Ended journeys with the egress delay left (ms)
+    pub pending_egress: Vec<(TrackId, Journey, u64)>,
+}
+
 /// Manages active journeys and handles stitching/egress
 pub struct JourneyManager {
     /// Active journeys by current track_id
@@ -23,20 +34,23 @@ pub struct JourneyManager {
     pending_egress: FxHashMap<TrackId, PendingEgress>,
     /// Mapping of track_id to person_id for stitch lookups
     pid_by_track: FxHashMap<TrackId, String>,
+    /// Time source for journey timestamps and the egress delay
+    clock: SharedClock,
 }
 
 impl JourneyManager {
-    pub fn new() -> Self {
+    pub fn new(clock: SharedClock) -> Self {
         Self {
             active: FxHashMap::default(),
             pending_egress: FxHashMap::default(),
             pid_by_track: FxHashMap::default(),
+            clock,
         }
     }
 
     /// Create a new journey for a track
     pub fn new_journey(&mut self, track_id: TrackId) -> &Journey {
-        let journey = Journey::new(track_id);
+        let journey = Journey::new_at(track_id, self.clock.epoch_ms());
         let pid = journey.pid.clone();
 
         debug!(
@@ -58,7 +72,8 @@ impl JourneyManager {
         parent_jid: &str,
         parent_pid: &str,
     ) -> &Journey {
-        let journey = Journey::new_with_parent(track_id, parent_jid, parent_pid);
+        let journey =
+            Journey::new_with_parent(track_id, parent_jid, parent_pid, self.clock.epoch_ms());
         let pid = journey.pid.clone();
 
         info!(
@@ -95,9 +110,11 @@ impl JourneyManager {
             let old_jid = journey.jid.clone();
 
             // Add stitch event
-            journey.add_event(JourneyEvent::new(JourneyEventType::Stitch, epoch_ms()).with_extra(
-                &format!("from={old_track_id},time_ms={time_ms},dist_cm={distance_cm}"),
-            ));
+            journey.add_event(
+                JourneyEvent::new(JourneyEventType::Stitch, self.clock.epoch_ms()).with_extra(
+                    &format!("from={old_track_id},time_ms={time_ms},dist_cm={distance_cm}"),
+                ),
+            );
 
             // Add new track ID to history
             journey.add_track_id(new_track_id);
@@ -133,6 +150,55 @@ impl JourneyManager {
         }
     }
 
+    /// Continue the journey of a deleted track on another sensor's live track
+    ///
+    /// The live track's own journey (started when it was created in the sensor
+    /// overlap) is folded into the continued journey and dropped.
+    /// Returns true if the deleted track had an active journey.
+    pub fn hand_over(
+        &mut self,
+        old_track_id: TrackId,
+        new_track_id: TrackId,
+        distance_cm: u32,
+    ) -> bool {
+        let Some(mut journey) = self.active.remove(&old_track_id) else {
+            debug!(old_track_id = %old_track_id, "handover_failed_no_journey");
+            return false;
+        };
+
+        journey.add_event(
+            JourneyEvent::new(JourneyEventType::Stitch, self.clock.epoch_ms())
+                .with_extra(&format!("from={old_track_id},handover=1,dist_cm={distance_cm}")),
+        );
+        journey.add_track_id(new_track_id);
+
+        if let Some(overlap) = self.active.remove(&new_track_id) {
+            journey.authorized |= overlap.authorized;
+            journey.acc_matched |= overlap.acc_matched;
+            journey.total_dwell_ms += overlap.total_dwell_ms;
+            journey.gate_cmd_at = journey.gate_cmd_at.or(overlap.gate_cmd_at);
+            journey.gate_open_count = journey.gate_open_count.max(overlap.gate_open_count);
+            journey.crossed_entry |= overlap.crossed_entry;
+            journey.events.extend(
+                overlap.events.into_iter().filter(|e| e.t != JourneyEventType::TrackCreate),
+            );
+            journey.events.sort_by_key(|e| e.ts);
+        }
+
+        info!(
+            old_track_id = %old_track_id,
+            new_track_id = %new_track_id,
+            jid = %journey.jid,
+            distance_cm = %distance_cm,
+            "journey_handed_over"
+        );
+
+        self.pid_by_track.remove(&old_track_id);
+        self.pid_by_track.insert(new_track_id, journey.pid.clone());
+        self.active.insert(new_track_id, journey);
+        true
+    }
+
     /// Add an event to a journey (checks both active and pending_egress)
     pub fn add_event(&mut self, track_id: TrackId, event: JourneyEvent) {
         // Try active journeys first
@@ -146,6 +212,25 @@ impl JourneyManager {
         }
     }
 
+    /// Flag journeys overlapping an ingress gap (epoch ms); returns how many
+    ///
+    /// Covers active journeys and ended ones still waiting for egress.
+    pub fn flag_ingress_gap(&mut self, from_ms: u64, to_ms: u64) -> usize {
+        let gap_ms = to_ms.saturating_sub(from_ms);
+        let journeys = self
+            .active
+            .values_mut()
+            .chain(self.pending_egress.values_mut().map(|p| &mut p.journey));
+        let mut flagged = 0;
+        for journey in journeys {
+            if journey.started_at <= to_ms && journey.ended_at.is_none_or(|t| t >= from_ms) {
+                journey.ingress_gap_ms += gap_ms;
+                flagged += 1;
+            }
+        }
+        flagged
+    }
+
     /// Get mutable reference to journey for a track
     pub fn get_mut(&mut self, track_id: TrackId) -> Option<&mut Journey> {
         self.active.get_mut(&track_id)
@@ -182,7 +267,7 @@ impl JourneyManager {
     /// End a journey and move to pending egress
     pub fn end_journey(&mut self, track_id: TrackId, outcome: JourneyOutcome) {
         if let Some(mut journey) = self.active.remove(&track_id) {
-            journey.complete(outcome);
+            journey.complete_at(outcome, self.clock.epoch_ms());
 
             info!(
                 track_id = %track_id,
@@ -195,7 +280,7 @@ impl JourneyManager {
             // Add to pending egress with 10s delay
             self.pending_egress.insert(
                 track_id,
-                PendingEgress { journey, eligible_at: Instant::now() + EGRESS_DELAY },
+                PendingEgress { journey, eligible_at: self.clock.now() + EGRESS_DELAY },
             );
         }
     }
@@ -203,7 +288,7 @@ impl JourneyManager {
     /// Check for journeys ready to emit
     /// Returns journeys that have passed the 10s delay and crossed entry
     pub fn tick(&mut self) -> Vec<Journey> {
-        let now = Instant::now();
+        let now = self.clock.now();
         let mut ready = Vec::new();
 
         // Collect track IDs that are eligible for processing
@@ -247,6 +332,58 @@ impl JourneyManager {
         ready
     }
 
+    /// End every active journey with `outcome` (shutdown)
+    ///
+    /// Returns the number of journeys ended.
+    pub fn end_all(&mut self, outcome: JourneyOutcome) -> usize {
+        let track_ids: Vec<TrackId> = self.active.keys().copied().collect();
+        for &track_id in &track_ids {
+            self.end_journey(track_id, outcome);
+        }
+        track_ids.len()
+    }
+
+    /// Return all pending journeys without waiting for `EGRESS_DELAY` (shutdown)
+    pub fn flush(&mut self) -> Vec<Journey> {
+        let now = self.clock.now();
+        for pending in self.pending_egress.values_mut() {
+            pending.eligible_at = now;
+        }
+        self.tick()
+    }
+
+    /// Copy active and egress-pending journeys for a snapshot
+    pub fn snapshot(&self, now: Instant) -> JourneysSnapshot {
+        JourneysSnapshot {
+            active: self.active.iter().map(|(tid, j)| (*tid, j.clone())).collect(),
+            pending_egress: self
+                .pending_egress
+                .iter()
+                .map(|(tid, p)| {
+                    let delay_ms = p.eligible_at.saturating_duration_since(now).as_millis() as u64;
+                    (*tid, p.journey.clone(), delay_ms)
+                })
+                .collect(),
+        }
+    }
+
+    /// Restore journeys from a snapshot, marking them with `restored_at` (epoch ms)
+    ///
+    /// Journeys already known (by track_id) are kept.
+    pub fn restore(&mut self, snapshot: JourneysSnapshot, now: Instant, restored_at: u64) {
+        for (track_id, mut journey) in snapshot.active {
+            journey.restored_at = Some(restored_at);
+            self.pid_by_track.entry(track_id).or_insert_with(|| journey.pid.clone());
+            self.active.entry(track_id).or_insert(journey);
+        }
+        for (track_id, mut journey, delay_ms) in snapshot.pending_egress {
+            journey.restored_at = Some(restored_at);
+            self.pid_by_track.entry(track_id).or_insert_with(|| journey.pid.clone());
+            let eligible_at = now + Duration::from_millis(delay_ms);
+            self.pending_egress.entry(track_id).or_insert(PendingEgress { journey, eligible_at });
+        }
+    }
+
     /// Number of active journeys
     #[allow(dead_code)]
     pub fn active_count(&self) -> usize {
@@ -266,19 +403,21 @@ impl JourneyManager {
     }
 }
 
-impl Default for JourneyManager {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::infra::clock::{ManualClock, SystemClock};
+    use std::sync::Arc;
+
+    /// Manager on a manual clock, for tests that wait out `EGRESS_DELAY`
+    fn create_manager_with_clock() -> (JourneyManager, Arc<ManualClock>) {
+        let clock = Arc::new(ManualClock::new(1_000_000));
+        (JourneyManager::new(clock.clone()), clock)
+    }
 
     #[test]
     fn test_new_journey() {
-        let mut manager = JourneyManager::new();
+        let mut manager = JourneyManager::new(SystemClock::shared());
 
         let journey = manager.new_journey(TrackId(100));
 
@@ -289,7 +428,7 @@ mod tests {
 
     #[test]
     fn test_add_event() {
-        let mut manager = JourneyManager::new();
+        let mut manager = JourneyManager::new(SystemClock::shared());
         manager.new_journey(TrackId(100));
 
         manager.add_event(
@@ -304,7 +443,7 @@ mod tests {
 
     #[test]
     fn test_end_journey() {
-        let mut manager = JourneyManager::new();
+        let mut manager = JourneyManager::new(SystemClock::shared());
         manager.new_journey(TrackId(100));
 
         manager.end_journey(TrackId(100), JourneyOutcome::Completed);
@@ -314,9 +453,58 @@ mod tests {
         assert_eq!(manager.pending_count(), 1);
     }
 
+    #[test]
+    fn test_flag_ingress_gap_marks_overlapping_journeys() {
+        let mut manager = JourneyManager::new(SystemClock::shared());
+        manager.new_journey(TrackId(100));
+        manager.get_mut(TrackId(100)).unwrap().started_at = 1000;
+        manager.new_journey(TrackId(200));
+        manager.get_mut(TrackId(200)).unwrap().started_at = 1000;
+        manager.end_journey(TrackId(200), JourneyOutcome::Completed);
+        manager.get_mut_any(TrackId(200)).unwrap().ended_at = Some(2000);
+
+        // Gap after 200 ended: only the active journey overlaps
+        assert_eq!(manager.flag_ingress_gap(5000, 8000), 1);
+        assert_eq!(manager.get(TrackId(100)).unwrap().ingress_gap_ms, 3000);
+        assert_eq!(manager.get_any(TrackId(200)).unwrap().ingress_gap_ms, 0);
+
+        assert_eq!(manager.flag_ingress_gap(1500, 2500), 2);
+        assert_eq!(manager.get(TrackId(100)).unwrap().ingress_gap_ms, 4000);
+    }
+
+    #[test]
+    fn test_hand_over_folds_overlap_journey() {
+        let mut manager = JourneyManager::new(SystemClock::shared());
+        let old = TrackId::namespaced(0, 100);
+        let new = TrackId::namespaced(1, 7);
+        manager.new_journey(old);
+        manager.new_journey(new);
+        manager.get_mut(new).unwrap().authorized = true;
+        manager.add_event(new, JourneyEvent::new(JourneyEventType::TrackCreate, 1000));
+        manager.add_event(
+            new,
+            JourneyEvent::new(JourneyEventType::ZoneEntry, 1500).with_zone("POS_1"),
+        );
+
+        assert!(manager.hand_over(old, new, 40));
+
+        assert!(!manager.has_journey(old));
+        assert_eq!(manager.active_count(), 1);
+        let journey = manager.get(new).unwrap();
+        assert_eq!(journey.tids.as_slice(), &[old, new]);
+        assert!(journey.authorized);
+        // The overlap track's TRACK_CREATE is dropped, its zone entry kept
+        assert!(!journey.events.iter().any(|e| e.t == JourneyEventType::TrackCreate));
+        assert!(journey.events.iter().any(|e| e.t == JourneyEventType::ZoneEntry));
+        assert!(journey.events.iter().any(|e| e.t == JourneyEventType::Stitch));
+
+        // No journey to hand over
+        assert!(!manager.hand_over(TrackId(999), new, 10));
+    }
+
     #[test]
     fn test_stitch_from_active() {
-        let mut manager = JourneyManager::new();
+        let mut manager = JourneyManager::new(SystemClock::shared());
         manager.new_journey(TrackId(100));
 
         // Modify journey state
@@ -342,7 +530,7 @@ mod tests {
 
     #[test]
     fn test_stitch_from_pending() {
-        let mut manager = JourneyManager::new();
+        let mut manager = JourneyManager::new(SystemClock::shared());
         manager.new_journey(TrackId(100));
 
         // End journey (moves to pending)
@@ -362,7 +550,7 @@ mod tests {
 
     #[test]
     fn test_stitch_fails_no_journey() {
-        let mut manager = JourneyManager::new();
+        let mut manager = JourneyManager::new(SystemClock::shared());
 
         let result = manager.stitch_journey(TrackId(100), TrackId(200), 500, 42);
 
@@ -372,16 +560,13 @@ mod tests {
 
     #[test]
     fn test_tick_filters_no_entry() {
-        let mut manager = JourneyManager::new();
+        let (mut manager, clock) = create_manager_with_clock();
         manager.new_journey(TrackId(100));
 
         // End journey without crossing entry
         manager.end_journey(TrackId(100), JourneyOutcome::Lost);
 
-        // Manually set eligible_at to past
-        if let Some(pending) = manager.pending_egress.get_mut(&TrackId(100)) {
-            pending.eligible_at = Instant::now() - Duration::from_secs(1);
-        }
+        clock.advance(EGRESS_DELAY);
 
         // Tick should discard (no crossed_entry)
         let ready = manager.tick();
@@ -392,7 +577,7 @@ mod tests {
 
     #[test]
     fn test_tick_emits_with_entry() {
-        let mut manager = JourneyManager::new();
+        let (mut manager, clock) = create_manager_with_clock();
         manager.new_journey(TrackId(100));
 
         // Mark as crossed entry and add meaningful activity (dwell time)
@@ -403,10 +588,7 @@ mod tests {
 
         manager.end_journey(TrackId(100), JourneyOutcome::Completed);
 
-        // Manually set eligible_at to past
-        if let Some(pending) = manager.pending_egress.get_mut(&TrackId(100)) {
-            pending.eligible_at = Instant::now() - Duration::from_secs(1);
-        }
+        clock.advance(EGRESS_DELAY);
 
         let ready = manager.tick();
 
@@ -417,7 +599,7 @@ mod tests {
 
     #[test]
     fn test_tick_emits_entry_only() {
-        let mut manager = JourneyManager::new();
+        let (mut manager, clock) = create_manager_with_clock();
         manager.new_journey(TrackId(100));
 
         // Mark as crossed entry but NO meaningful activity (only STORE zone)
@@ -428,10 +610,7 @@ mod tests {
 
         manager.end_journey(TrackId(100), JourneyOutcome::Lost);
 
-        // Manually set eligible_at to past
-        if let Some(pending) = manager.pending_egress.get_mut(&TrackId(100)) {
-            pending.eligible_at = Instant::now() - Duration::from_secs(1);
-        }
+        clock.advance(EGRESS_DELAY);
 
         // Should emit - has crossed_entry (OR logic)
         let ready = manager.tick();
@@ -441,7 +620,7 @@ mod tests {
 
     #[test]
     fn test_tick_emits_meaningful_activity_only() {
-        let mut manager = JourneyManager::new();
+        let (mut manager, clock) = create_manager_with_clock();
         manager.new_journey(TrackId(100));
 
         // NO crossed entry but HAS meaningful activity (ACC match)
@@ -452,10 +631,7 @@ mod tests {
 
         manager.end_journey(TrackId(100), JourneyOutcome::Lost);
 
-        // Manually set eligible_at to past
-        if let Some(pending) = manager.pending_egress.get_mut(&TrackId(100)) {
-            pending.eligible_at = Instant::now() - Duration::from_secs(1);
-        }
+        clock.advance(EGRESS_DELAY);
 
         // Should emit - has meaningful activity (OR logic)
         let ready = manager.tick();
@@ -465,7 +641,7 @@ mod tests {
 
     #[test]
     fn test_tick_respects_delay() {
-        let mut manager = JourneyManager::new();
+        let (mut manager, clock) = create_manager_with_clock();
         manager.new_journey(TrackId(100));
 
         if let Some(j) = manager.get_mut(TrackId(100)) {
@@ -475,16 +651,19 @@ mod tests {
 
         manager.end_journey(TrackId(100), JourneyOutcome::Completed);
 
-        // Don't modify eligible_at - should still be in future
+        clock.advance(EGRESS_DELAY - Duration::from_millis(1));
         let ready = manager.tick();
 
         assert!(ready.is_empty());
         assert_eq!(manager.pending_count(), 1);
+
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(manager.tick().len(), 1);
     }
 
     #[test]
     fn test_journey_state_preserved_on_stitch() {
-        let mut manager = JourneyManager::new();
+        let mut manager = JourneyManager::new(SystemClock::shared());
         manager.new_journey(TrackId(100));
 
         // Set various state
@@ -505,4 +684,25 @@ mod tests {
         assert!(journey.crossed_entry);
         assert_eq!(journey.gate_cmd_at, Some(1234567890));
     }
+
+    #[test]
+    fn test_end_all_and_flush_ignore_delay() {
+        let mut manager = JourneyManager::new(SystemClock::shared());
+        manager.new_journey(TrackId(100));
+        manager.new_journey(TrackId(200));
+        if let Some(j) = manager.get_mut(TrackId(100)) {
+            j.crossed_entry = true;
+        }
+        manager.end_journey(TrackId(200), JourneyOutcome::Completed);
+
+        assert_eq!(manager.end_all(JourneyOutcome::Interrupted), 1);
+        assert_eq!(manager.active_count(), 0);
+
+        // Track 200 has no entry or activity and is still filtered
+        let flushed = manager.flush();
+        assert_eq!(flushed.len(), 1);
+        assert_eq!(flushed[0].outcome, JourneyOutcome::Interrupted);
+        assert!(flushed[0].ended_at.is_some());
+        assert_eq!(manager.pending_count(), 0);
+    }
 }
@@ -9,18 +9,39 @@
 //! - `acc_collector` - ACC payment correlation
 //! - `gate` - Gate controller interface
 //! - `gate_worker` - Async gate command worker
+//! - `gate_trigger` - Virtual pre-gate triggers on positions
+//! - `zone_engine` - Zone entry/exit derived from positions
+//! - `group_membership` - Xovis GROUP membership from positions
+//! - `object_filter` - Object type allow/deny lists at ingest
+//! - `frame_reorder` - Reorder and de-duplicate sensor frames by frame time
+//! - `config_reload` - Hot reload of site configuration (SIGHUP, admin API)
+//! - `replay` - Offline replay of a gateway-analysis capture through the tracker
+//! - `sweep` - Replay of one capture under a grid of tuning parameters
+//! - `shadow_policy` - Candidate authorization policy evaluated next to the live one
+//! - `stale_tracks` - Stale track pruning shared by the position-driven services
 
 pub mod acc_collector;
+pub mod config_reload;
 pub mod door_correlator;
+pub mod frame_reorder;
 pub mod gate;
+pub mod gate_trigger;
 pub mod gate_worker;
+pub mod group_membership;
 pub mod journey_manager;
+pub mod object_filter;
 pub mod pos_occupancy;
 pub mod reentry_detector;
+pub mod replay;
+pub mod sensor_watchdog;
+pub mod shadow_policy;
+pub mod stale_tracks;
 pub mod stitcher;
+pub mod sweep;
 pub mod tracker;
+pub mod zone_engine;
 
 // Re-export commonly used types
 pub use gate::GateController;
 pub use gate_worker::{create_gate_worker, GateCmd, GateCmdWorker};
-pub use tracker::Tracker;
+pub use tracker::{RestoredTracks, Tracker};
@@ -0,0 +1,150 @@
+//! Object type filtering at ingest
+//!
+//! Xovis classifies every tracked object, but the gateway used to treat all of
+//! them as adults. With `[object_types]` allow/deny lists, objects of other
+//! types are removed from position processing and their events are dropped
+//! before they reach the tracker.
+//!
+//! Events only carry the type when the track is in the frame's tracked
+//! objects, so the last seen type of each track is remembered until its
+//! TRACK_DELETE.
+
+use crate::domain::types::{EventType, ParsedEvent, TrackId, TrackedObject};
+use crate::infra::config::{Config, ObjectTypesConfig};
+use crate::infra::metrics::Metrics;
+use rustc_hash::FxHashSet;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+/// Drops objects and events of track types not allowed by `[object_types]`
+pub struct ObjectTypeFilter {
+    config: ObjectTypesConfig,
+    /// Tracks of a filtered type
+    denied: FxHashSet<TrackId>,
+    metrics: Arc<Metrics>,
+}
+
+impl ObjectTypeFilter {
+    /// Create the filter (`None` if no allow or deny list is configured)
+    pub fn new(config: &Config, metrics: Arc<Metrics>) -> Option<Self> {
+        let object_types = config.object_types();
+        if !object_types.filters() {
+            return None;
+        }
+        info!(
+            allow = ?object_types.allow,
+            deny = ?object_types.deny,
+            "object_type_filter_enabled"
+        );
+        Some(Self { config: object_types.clone(), denied: FxHashSet::default(), metrics })
+    }
+
+    /// Remove filtered objects and the events of filtered tracks
+    pub fn process(&mut self, events: &mut Vec<ParsedEvent>, objects: &mut Vec<TrackedObject>) {
+        objects.retain(|obj| {
+            if self.config.is_allowed(&obj.obj_type) {
+                return true;
+            }
+            let track_id = TrackId(obj.track_id);
+            if self.denied.insert(track_id) {
+                debug!(track_id = %track_id, obj_type = %obj.obj_type, "object_type_filtered");
+            }
+            false
+        });
+
+        events.retain(|event| {
+            let allowed = match &event.obj_type {
+                Some(obj_type) => self.config.is_allowed(obj_type),
+                None => !self.denied.contains(&event.track_id),
+            };
+            if event.event_type == EventType::TrackDelete {
+                self.denied.remove(&event.track_id);
+            }
+            if !allowed {
+                self.metrics.record_object_event_filtered();
+            }
+            allowed
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use smallvec::smallvec;
+    use std::time::Instant;
+
+    fn create_filter() -> (ObjectTypeFilter, Arc<Metrics>) {
+        let object_types =
+            ObjectTypesConfig { deny: vec!["CART".to_string()], ..Default::default() };
+        let config = Config::default().with_object_types(object_types);
+        let metrics = Arc::new(Metrics::new());
+        (ObjectTypeFilter::new(&config, metrics.clone()).unwrap(), metrics)
+    }
+
+    fn event(event_type: EventType, track_id: i64, obj_type: Option<&str>) -> ParsedEvent {
+        ParsedEvent {
+            event_type,
+            track_id: TrackId(track_id),
+            geometry_id: None,
+            direction: None,
+            event_time: 0,
+            received_at: Instant::now(),
+            position: None,
+            derived: false,
+            obj_type: obj_type.map(Arc::from),
+        }
+    }
+
+    fn object(track_id: i64, obj_type: &str) -> TrackedObject {
+        TrackedObject {
+            track_id,
+            obj_type: Arc::from(obj_type),
+            position: smallvec![1.0, 1.0, 1.0],
+        }
+    }
+
+    #[test]
+    fn test_no_lists_configured() {
+        let metrics = Arc::new(Metrics::new());
+        assert!(ObjectTypeFilter::new(&Config::default(), metrics).is_none());
+    }
+
+    #[test]
+    fn test_allow_and_deny_lists() {
+        let object_types = ObjectTypesConfig {
+            allow: vec!["PERSON".to_string(), "CHILD".to_string()],
+            deny: vec!["child".to_string()],
+            ..Default::default()
+        };
+        assert!(object_types.is_allowed("PERSON"));
+        assert!(!object_types.is_allowed("CHILD"));
+        assert!(!object_types.is_allowed("CART"));
+    }
+
+    #[test]
+    fn test_denied_track_dropped_until_delete() {
+        let (mut filter, metrics) = create_filter();
+
+        let mut events = vec![
+            event(EventType::TrackCreate, 100, Some("CART")),
+            event(EventType::TrackCreate, 200, Some("PERSON")),
+        ];
+        let mut objects = vec![object(100, "CART"), object(200, "PERSON")];
+        filter.process(&mut events, &mut objects);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].track_id, TrackId(200));
+        assert_eq!(objects.len(), 1);
+
+        // Type unknown in this frame: remembered from the earlier one
+        let mut events = vec![event(EventType::ZoneEntry, 100, None)];
+        filter.process(&mut events, &mut Vec::new());
+        assert!(events.is_empty());
+
+        let mut events = vec![event(EventType::TrackDelete, 100, None)];
+        filter.process(&mut events, &mut Vec::new());
+        assert!(filter.denied.is_empty());
+
+        assert_eq!(metrics.report(0, 0).object_events_filtered_total, 3);
+    }
+}
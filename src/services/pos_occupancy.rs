@@ -11,8 +11,9 @@
 //! - get_candidates() returns present tracks first, then recent exits
 
 use crate::domain::types::TrackId;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 /// State for a single track at a single POS zone
 #[derive(Debug, Clone)]
@@ -33,6 +34,17 @@ impl PosState {
     }
 }
 
+/// One track's session at a POS zone in a tracker snapshot (times as ages in ms)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PosSessionSnapshot {
+    pub zone: String,
+    pub track_id: i64,
+    pub is_present: bool,
+    pub entered_ms_ago: u64,
+    pub exited_ms_ago: Option<u64>,
+    pub accumulated_dwell_ms: u64,
+}
+
 /// Per-zone POS occupancy tracker
 ///
 /// Outer key is zone name (e.g. "POS_1"), inner key is track_id
@@ -111,6 +123,70 @@ impl PosOccupancyState {
         Some((session_dwell_ms, state.accumulated_dwell_ms))
     }
 
+    /// Move `old`'s sessions to `new` (sensor handover)
+    ///
+    /// A session still open on `old` is closed at `now`, since the old sensor
+    /// lost the track. Where `new` already has a session in the same zone
+    /// (overlap area), it keeps the larger accumulated dwell of the two.
+    pub fn hand_over(&mut self, old: TrackId, new: TrackId, now: Instant) {
+        for zone_tracks in self.zones.values_mut() {
+            let Some(mut state) = zone_tracks.remove(&old.0) else {
+                continue;
+            };
+            if state.is_present {
+                state.accumulated_dwell_ms +=
+                    now.saturating_duration_since(state.entry_time).as_millis() as u64;
+                state.is_present = false;
+                state.exit_time = Some(now);
+            }
+            match zone_tracks.get_mut(&new.0) {
+                Some(existing) => {
+                    existing.accumulated_dwell_ms =
+                        existing.accumulated_dwell_ms.max(state.accumulated_dwell_ms);
+                }
+                None => {
+                    zone_tracks.insert(new.0, state);
+                }
+            }
+        }
+    }
+
+    /// Copy all sessions for a snapshot
+    pub fn snapshot(&self, now: Instant) -> Vec<PosSessionSnapshot> {
+        let age_ms = |t: Instant| now.saturating_duration_since(t).as_millis() as u64;
+        let mut sessions = Vec::new();
+        for (zone, tracks) in &self.zones {
+            for (&track_id, state) in tracks {
+                sessions.push(PosSessionSnapshot {
+                    zone: zone.clone(),
+                    track_id,
+                    is_present: state.is_present,
+                    entered_ms_ago: age_ms(state.entry_time),
+                    exited_ms_ago: state.exit_time.map(age_ms),
+                    accumulated_dwell_ms: state.accumulated_dwell_ms,
+                });
+            }
+        }
+        sessions
+    }
+
+    /// Restore sessions from a snapshot, aged by `offline_ms` (time since it was taken)
+    ///
+    /// A present track's dwell keeps counting across the restart.
+    pub fn restore(&mut self, sessions: Vec<PosSessionSnapshot>, now: Instant, offline_ms: u64) {
+        let ago = |ms: u64| now.checked_sub(Duration::from_millis(ms + offline_ms)).unwrap_or(now);
+        for session in sessions {
+            let state = PosState {
+                is_present: session.is_present,
+                entry_time: ago(session.entered_ms_ago),
+                exit_time: session.exited_ms_ago.map(ago),
+                accumulated_dwell_ms: session.accumulated_dwell_ms,
+            };
+            let zone_tracks = self.zones.entry(session.zone).or_default();
+            zone_tracks.entry(session.track_id).or_insert(state);
+        }
+    }
+
     /// Get candidate tracks for ACC matching at a specific zone
     ///
     /// Returns (track_id, dwell_ms) pairs sorted by:
@@ -143,8 +219,8 @@ impl PosOccupancyState {
         }
 
         // Sort both by dwell descending
-        present.sort_by(|a, b| b.1.cmp(&a.1));
-        recent_exits.sort_by(|a, b| b.1.cmp(&a.1));
+        present.sort_by_key(|c| std::cmp::Reverse(c.1));
+        recent_exits.sort_by_key(|c| std::cmp::Reverse(c.1));
 
         // Concatenate: present first, then recent exits
         present.extend(recent_exits);
@@ -183,6 +259,12 @@ impl PosOccupancyState {
     pub fn min_dwell_ms(&self) -> u64 {
         self.min_dwell_ms
     }
+
+    /// Change the grace window and minimum dwell (config reload); sessions are kept
+    pub fn set_thresholds(&mut self, exit_grace_ms: u64, min_dwell_ms: u64) {
+        self.exit_grace_ms = exit_grace_ms;
+        self.min_dwell_ms = min_dwell_ms;
+    }
 }
 
 #[cfg(test)]
@@ -207,6 +289,28 @@ mod tests {
         assert!(pos_state.exit_time.is_none());
     }
 
+    #[test]
+    fn test_hand_over_moves_and_closes_sessions() {
+        let mut state = create_state();
+        let now = Instant::now();
+        let later = now + std::time::Duration::from_millis(3000);
+
+        state.record_entry("POS_1", TrackId(100), now);
+        state.record_entry("POS_2", TrackId(100), now);
+        state.record_entry("POS_2", TrackId(200), now);
+        state.record_exit("POS_2", TrackId(200), now + std::time::Duration::from_millis(1000));
+        state.hand_over(TrackId(100), TrackId(200), later);
+
+        // The open session is closed at the handover and continues on the new track
+        let pos_1 = &state.zones["POS_1"][&200];
+        assert!(!pos_1.is_present);
+        assert_eq!(pos_1.accumulated_dwell_ms, 3000);
+        assert_eq!(state.get_candidates("POS_1", later), vec![(TrackId(200), 3000)]);
+        // Both tracks had a session: the larger dwell is kept
+        assert_eq!(state.zones["POS_2"][&200].accumulated_dwell_ms, 3000);
+        assert!(!state.zones.values().any(|tracks| tracks.contains_key(&100)));
+    }
+
     #[test]
     fn test_exit_sets_not_present_and_accumulates() {
         let mut state = create_state();
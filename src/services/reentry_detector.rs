@@ -5,6 +5,7 @@
 //! - Time: new track within 30s of exit
 //! - Height: within +/- 10cm of previous track
 
+use crate::infra::clock::SharedClock;
 use std::time::{Duration, Instant};
 use tracing::{debug, info};
 
@@ -34,11 +35,12 @@ pub struct ReentryMatch {
 pub struct ReentryDetector {
     /// Recent exits for potential matching
     recent_exits: Vec<RecentExit>,
+    clock: SharedClock,
 }
 
 impl ReentryDetector {
-    pub fn new() -> Self {
-        Self { recent_exits: Vec::new() }
+    pub fn new(clock: SharedClock) -> Self {
+        Self { recent_exits: Vec::new(), clock }
     }
 
     /// Record a journey exit for potential re-entry matching
@@ -59,7 +61,7 @@ impl ReentryDetector {
             jid: jid.to_string(),
             pid: pid.to_string(),
             height: h,
-            exited_at: Instant::now(),
+            exited_at: self.clock.now(),
         });
     }
 
@@ -70,7 +72,7 @@ impl ReentryDetector {
         self.cleanup_old_exits();
 
         let h = height?;
-        let now = Instant::now();
+        let now = self.clock.now();
 
         // Find best match by height within time window
         let mut best_match: Option<(usize, f64)> = None;
@@ -114,7 +116,7 @@ impl ReentryDetector {
 
     /// Cleanup exits older than the matching window
     fn cleanup_old_exits(&mut self) {
-        let now = Instant::now();
+        let now = self.clock.now();
         self.recent_exits
             .retain(|exit| now.duration_since(exit.exited_at) <= MAX_REENTRY_WINDOW * 2);
     }
@@ -126,19 +128,15 @@ impl ReentryDetector {
     }
 }
 
-impl Default for ReentryDetector {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::infra::clock::{ManualClock, SystemClock};
+    use std::sync::Arc;
 
     #[test]
     fn test_record_exit() {
-        let mut detector = ReentryDetector::new();
+        let mut detector = ReentryDetector::new(SystemClock::shared());
 
         detector.record_exit("jid1", "pid1", Some(1.75));
 
@@ -147,7 +145,7 @@ mod tests {
 
     #[test]
     fn test_record_exit_no_height() {
-        let mut detector = ReentryDetector::new();
+        let mut detector = ReentryDetector::new(SystemClock::shared());
 
         detector.record_exit("jid1", "pid1", None);
 
@@ -156,7 +154,7 @@ mod tests {
 
     #[test]
     fn test_match_by_height() {
-        let mut detector = ReentryDetector::new();
+        let mut detector = ReentryDetector::new(SystemClock::shared());
 
         detector.record_exit("jid1", "pid1", Some(1.75));
 
@@ -172,7 +170,7 @@ mod tests {
 
     #[test]
     fn test_match_within_height_tolerance() {
-        let mut detector = ReentryDetector::new();
+        let mut detector = ReentryDetector::new(SystemClock::shared());
 
         detector.record_exit("jid1", "pid1", Some(1.75));
 
@@ -185,7 +183,7 @@ mod tests {
 
     #[test]
     fn test_no_match_height_too_different() {
-        let mut detector = ReentryDetector::new();
+        let mut detector = ReentryDetector::new(SystemClock::shared());
 
         detector.record_exit("jid1", "pid1", Some(1.75));
 
@@ -198,7 +196,7 @@ mod tests {
 
     #[test]
     fn test_no_match_without_height() {
-        let mut detector = ReentryDetector::new();
+        let mut detector = ReentryDetector::new(SystemClock::shared());
 
         detector.record_exit("jid1", "pid1", Some(1.75));
 
@@ -209,7 +207,7 @@ mod tests {
 
     #[test]
     fn test_best_height_match() {
-        let mut detector = ReentryDetector::new();
+        let mut detector = ReentryDetector::new(SystemClock::shared());
 
         detector.record_exit("jid1", "pid1", Some(1.75));
         detector.record_exit("jid2", "pid2", Some(1.80));
@@ -223,16 +221,17 @@ mod tests {
 
     #[test]
     fn test_no_match_timeout() {
-        let mut detector = ReentryDetector::new();
-
-        // Add exit with artificially old timestamp
-        detector.recent_exits.push(RecentExit {
-            jid: "jid1".to_string(),
-            pid: "pid1".to_string(),
-            height: 1.75,
-            exited_at: Instant::now() - Duration::from_secs(60), // 60s ago
-        });
+        let clock = Arc::new(ManualClock::new(1_000_000));
+        let mut detector = ReentryDetector::new(clock.clone());
 
+        detector.record_exit("jid1", "pid1", Some(1.75));
+        detector.record_exit("jid2", "pid2", Some(1.75));
+
+        // Exactly at the window edge still matches
+        clock.advance(MAX_REENTRY_WINDOW);
+        assert_eq!(detector.try_match(Some(1.75)).unwrap().parent_jid, "jid1");
+
+        clock.advance(Duration::from_millis(1));
         let result = detector.try_match(Some(1.75));
 
         assert!(result.is_none());
@@ -240,15 +239,12 @@ mod tests {
 
     #[test]
     fn test_cleanup_old_exits() {
-        let mut detector = ReentryDetector::new();
+        let clock = Arc::new(ManualClock::new(1_000_000));
+        let mut detector = ReentryDetector::new(clock.clone());
 
         // Add old exit
-        detector.recent_exits.push(RecentExit {
-            jid: "jid_old".to_string(),
-            pid: "pid_old".to_string(),
-            height: 1.75,
-            exited_at: Instant::now() - Duration::from_secs(120), // 2 min ago
-        });
+        detector.record_exit("jid_old", "pid_old", Some(1.75));
+        clock.advance(Duration::from_secs(120));
 
         // Add recent exit
         detector.record_exit("jid_new", "pid_new", Some(1.80));
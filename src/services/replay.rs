@@ -0,0 +1,491 @@
+//! Offline replay of a gateway-analysis capture through the tracker
+//!
+//! Reads the `*.jsonl` capture written by `gateway-analysis` (MQTT, ACC and
+//! RS485 records), orders it by receive time and feeds it through the same
+//! ingest pipeline and `Tracker` the gateway runs, on a `ManualClock` that
+//! jumps to each record's `ts_recv`. The tracker tick runs every simulated
+//! second, so egress delays and dwell windows behave as they did live but
+//! the replay runs as fast as the CPU allows. Gate commands go to a
+//! `RecordingGate` instead of the controller.
+
+use crate::domain::journey::{Journey, JourneyOutcome};
+use crate::domain::types::{DoorStatus, EventType, ParsedEvent, TrackId};
+use crate::infra::clock::{Clock, ManualClock};
+use crate::infra::config::Config;
+use crate::infra::metrics::Metrics;
+use crate::io::mqtt::FramePipeline;
+use crate::services::gate::GateCommand;
+use crate::services::gate_worker::GateCmd;
+use crate::services::sensor_watchdog::SensorHealth;
+use crate::services::stitcher::LivePositions;
+use crate::services::tracker::Tracker;
+use anyhow::Context;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::{mpsc, watch};
+use tracing::{debug, info};
+
+/// Simulated tracker tick (the live tracker ticks every second)
+const TICK_MS: u64 = 1000;
+
+/// Channel capacity; channels are drained after every record
+const CHANNEL_CAPACITY: usize = 4096;
+
+/// One input of a capture
+#[derive(Debug, Clone, PartialEq)]
+pub enum CaptureInput {
+    /// Sensor message on `topic`
+    Mqtt { topic: String, payload: String },
+    /// ACC payment from the kiosk at `kiosk_ip`
+    Acc { kiosk_ip: String },
+    /// Door state read over RS485
+    Door(DoorStatus),
+}
+
+/// One capture record with its receive time (epoch ms)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaptureRecord {
+    pub ts_ms: u64,
+    pub input: CaptureInput,
+}
+
+/// A capture ordered by receive time
+#[derive(Debug, Clone, Default)]
+pub struct Capture {
+    pub records: Vec<CaptureRecord>,
+    /// Lines that were not valid records or not replayable
+    pub skipped: usize,
+}
+
+/// Unified analysis log schema (see `analysis_logger`)
+#[derive(Deserialize)]
+struct LogLine {
+    ts_recv: String,
+    src: String,
+    payload_raw: String,
+    #[serde(default)]
+    fields: serde_json::Value,
+}
+
+impl Capture {
+    /// Load every `*.jsonl` file under `dir` (recursively)
+    ///
+    /// Files are read in path order and records are stably sorted by
+    /// `ts_recv`, so records with equal receive times keep their file order.
+    pub fn load(dir: &Path) -> anyhow::Result<Self> {
+        let mut files = Vec::new();
+        collect_jsonl(dir, &mut files)
+            .with_context(|| format!("reading capture dir {}", dir.display()))?;
+        files.sort();
+
+        let mut capture = Capture::default();
+        for path in &files {
+            let file =
+                std::fs::File::open(path).with_context(|| format!("opening {}", path.display()))?;
+            for line in BufReader::new(file).lines() {
+                let line = line.with_context(|| format!("reading {}", path.display()))?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match parse_line(&line) {
+                    Some(record) => capture.records.push(record),
+                    None => capture.skipped += 1,
+                }
+            }
+        }
+        capture.records.sort_by_key(|r| r.ts_ms);
+        info!(
+            files = %files.len(),
+            records = %capture.records.len(),
+            skipped = %capture.skipped,
+            "replay_capture_loaded"
+        );
+        Ok(capture)
+    }
+}
+
+fn collect_jsonl(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_jsonl(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "jsonl") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Parse one capture line; None for anything the gateway would not have acted on
+fn parse_line(line: &str) -> Option<CaptureRecord> {
+    let log: LogLine = serde_json::from_str(line).ok()?;
+    let ts_ms = chrono::DateTime::parse_from_rfc3339(&log.ts_recv).ok()?.timestamp_millis();
+    let ts_ms = u64::try_from(ts_ms).ok()?;
+    let input = match log.src.as_str() {
+        "mqtt" => {
+            // Non-UTF-8 payloads are logged hex-encoded and were never parsed
+            if log.payload_raw.starts_with("HEX:") {
+                return None;
+            }
+            let topic = log.fields.get("topic")?.as_str()?.to_string();
+            CaptureInput::Mqtt { topic, payload: log.payload_raw }
+        }
+        "acc" => {
+            // Same acceptance as the ACC listener: "ACC <receipt_id>"
+            let receipt_id = log.payload_raw.trim().strip_prefix("ACC ")?;
+            if receipt_id.trim().is_empty() {
+                return None;
+            }
+            let kiosk_ip = log.fields.get("kiosk_ip")?.as_str()?.to_string();
+            CaptureInput::Acc { kiosk_ip }
+        }
+        "rs485" => {
+            // A failed read is reported as Unknown, like the RS485 monitor does
+            let status = match log.fields.get("door_status").and_then(|s| s.as_str()) {
+                Some("closed") => DoorStatus::Closed,
+                Some("moving") => DoorStatus::Moving,
+                Some("open") => DoorStatus::Open,
+                _ => DoorStatus::Unknown,
+            };
+            CaptureInput::Door(status)
+        }
+        _ => return None,
+    };
+    Some(CaptureRecord { ts_ms, input })
+}
+
+/// A gate open the tracker decided on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GateDecision {
+    /// Simulated time of the command (epoch ms)
+    pub ts: u64,
+    pub track_id: TrackId,
+}
+
+/// Gate controller that records open commands instead of sending them
+pub struct RecordingGate {
+    clock: Arc<ManualClock>,
+    decisions: Mutex<Vec<GateDecision>>,
+}
+
+impl RecordingGate {
+    pub fn new(clock: Arc<ManualClock>) -> Self {
+        Self { clock, decisions: Mutex::new(Vec::new()) }
+    }
+
+    /// Take the decisions recorded since the last call
+    pub fn take(&self) -> Vec<GateDecision> {
+        std::mem::take(&mut *self.decisions.lock())
+    }
+}
+
+#[async_trait]
+impl GateCommand for RecordingGate {
+    async fn send_open_command(&self, track_id: TrackId) -> u64 {
+        let ts = self.clock.epoch_ms();
+        debug!(track_id = %track_id, ts = %ts, "replay_gate_open");
+        self.decisions.lock().push(GateDecision { ts, track_id });
+        0
+    }
+}
+
+/// Replay output, in the order it occurred
+#[derive(Debug, Clone)]
+pub enum ReplayEntry {
+    Journey(Box<Journey>),
+    GateOpen(GateDecision),
+}
+
+impl ReplayEntry {
+    /// One JSONL line: the journey egress JSON or a gate decision, tagged with `type`
+    pub fn to_json(&self) -> String {
+        match self {
+            ReplayEntry::Journey(journey) => {
+                let mut value: serde_json::Value =
+                    serde_json::from_str(&journey.to_json()).unwrap_or_default();
+                if let Some(obj) = value.as_object_mut() {
+                    obj.insert("type".to_string(), "journey".into());
+                }
+                value.to_string()
+            }
+            ReplayEntry::GateOpen(decision) => serde_json::json!({
+                "type": "gate_open",
+                "ts": decision.ts,
+                "tid": decision.track_id.0,
+            })
+            .to_string(),
+        }
+    }
+}
+
+/// Result of a replay
+pub struct ReplayOutcome {
+    pub entries: Vec<ReplayEntry>,
+    /// Tracker metrics at the end of the replay (ACC matching, stitches, ...)
+    pub metrics: Arc<Metrics>,
+}
+
+impl ReplayOutcome {
+    pub fn journeys(&self) -> impl Iterator<Item = &Journey> {
+        self.entries.iter().filter_map(|e| match e {
+            ReplayEntry::Journey(journey) => Some(journey.as_ref()),
+            ReplayEntry::GateOpen(_) => None,
+        })
+    }
+
+    pub fn gate_opens(&self) -> impl Iterator<Item = &GateDecision> {
+        self.entries.iter().filter_map(|e| match e {
+            ReplayEntry::GateOpen(decision) => Some(decision),
+            ReplayEntry::Journey(_) => None,
+        })
+    }
+
+    /// Write the entries as JSONL to `path`
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        let file =
+            std::fs::File::create(path).with_context(|| format!("creating {}", path.display()))?;
+        let mut out = std::io::BufWriter::new(file);
+        for entry in &self.entries {
+            writeln!(out, "{}", entry.to_json())?;
+        }
+        out.flush()?;
+        Ok(())
+    }
+}
+
+/// Replay `capture` through a tracker built from `config`
+///
+/// At the end, held frames are released and every journey is emitted: ended
+/// ones without waiting for the egress delay, active ones as `Interrupted`.
+pub async fn replay(config: &Config, capture: &Capture) -> ReplayOutcome {
+    let metrics = Arc::new(Metrics::new());
+    let Some(first) = capture.records.first() else {
+        return ReplayOutcome { entries: Vec::new(), metrics };
+    };
+    let clock = Arc::new(ManualClock::new(first.ts_ms));
+
+    let (event_tx, mut event_rx) = mpsc::channel::<ParsedEvent>(CHANNEL_CAPACITY);
+    let (gate_cmd_tx, gate_cmd_rx) = mpsc::channel::<GateCmd>(CHANNEL_CAPACITY);
+    let (journey_tx, journey_rx) = mpsc::channel::<Journey>(CHANNEL_CAPACITY);
+    let (_door_tx, door_rx) = watch::channel(DoorStatus::Unknown);
+
+    let mut tracker = Tracker::new(
+        config.clone(),
+        gate_cmd_tx,
+        journey_tx,
+        metrics.clone(),
+        None,
+        door_rx,
+        clock.clone(),
+    );
+    // Several sensors: ingest shares live positions with the tracker for handover
+    let live_positions = (config.sensors().len() > 1).then(|| Arc::new(LivePositions::default()));
+    if let Some(ref live) = live_positions {
+        tracker = tracker.with_live_positions(live.clone());
+    }
+    let sensor_health = SensorHealth::new(config.sensors().len(), first.ts_ms);
+    let mut pipeline =
+        FramePipeline::new(config, &metrics, &event_tx, &sensor_health, clock.as_ref());
+    pipeline.live_positions = live_positions.as_deref();
+    let gate = RecordingGate::new(clock.clone());
+    let mut sink = ReplaySink { tracker, gate_cmd_rx, journey_rx, gate, entries: Vec::new() };
+
+    let mut next_tick = first.ts_ms + TICK_MS;
+    for record in &capture.records {
+        while next_tick <= record.ts_ms {
+            clock.advance_to(next_tick);
+            pipeline.flush_reorder(clock.now());
+            sink.drain(&mut event_rx).await;
+            sink.tracker.tick_and_egress();
+            sink.drain(&mut event_rx).await;
+            next_tick += TICK_MS;
+        }
+        clock.advance_to(record.ts_ms);
+
+        match &record.input {
+            CaptureInput::Mqtt { topic, payload } => {
+                pipeline.ingest(topic, payload, clock.now());
+            }
+            CaptureInput::Acc { kiosk_ip } => {
+                sink.tracker.process_event(ParsedEvent {
+                    event_type: EventType::AccEvent(kiosk_ip.clone()),
+                    track_id: TrackId(0),
+                    geometry_id: None,
+                    direction: None,
+                    event_time: 0,
+                    received_at: clock.now(),
+                    position: None,
+                    derived: false,
+                    obj_type: None,
+                });
+            }
+            CaptureInput::Door(status) => {
+                if *status != sink.tracker.last_door_status {
+                    sink.tracker.handle_door_state_change(*status);
+                    sink.tracker.last_door_status = *status;
+                }
+            }
+        }
+        sink.drain(&mut event_rx).await;
+    }
+
+    pipeline.drain_reorder();
+    sink.drain(&mut event_rx).await;
+    let journey_manager = &mut sink.tracker.journey_manager;
+    let ended = journey_manager.flush();
+    journey_manager.end_all(JourneyOutcome::Interrupted);
+    let interrupted = journey_manager.flush();
+    let mut entries = sink.entries;
+    entries.extend(ended.into_iter().chain(interrupted).map(|j| ReplayEntry::Journey(Box::new(j))));
+
+    info!(
+        records = %capture.records.len(),
+        journeys = %entries.iter().filter(|e| matches!(e, ReplayEntry::Journey(_))).count(),
+        gate_opens = %entries.iter().filter(|e| matches!(e, ReplayEntry::GateOpen(_))).count(),
+        "replay_finished"
+    );
+    ReplayOutcome { entries, metrics }
+}
+
+/// Tracker side of a replay: the tracker and the outputs it feeds
+struct ReplaySink {
+    tracker: Tracker,
+    gate_cmd_rx: mpsc::Receiver<GateCmd>,
+    journey_rx: mpsc::Receiver<Journey>,
+    gate: RecordingGate,
+    entries: Vec<ReplayEntry>,
+}
+
+impl ReplaySink {
+    /// Process queued events, record gate commands and collect emitted journeys
+    async fn drain(&mut self, event_rx: &mut mpsc::Receiver<ParsedEvent>) {
+        while let Ok(event) = event_rx.try_recv() {
+            self.tracker.process_event(event);
+        }
+        while let Ok(cmd) = self.gate_cmd_rx.try_recv() {
+            self.gate.send_open_command(cmd.track_id).await;
+        }
+        self.entries.extend(self.gate.take().into_iter().map(ReplayEntry::GateOpen));
+        while let Ok(journey) = self.journey_rx.try_recv() {
+            self.entries.push(ReplayEntry::Journey(Box::new(journey)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// 2026-01-05T12:00:00Z
+    const START_MS: u64 = 1767614400000;
+
+    fn ts(offset_ms: u64) -> String {
+        chrono::DateTime::from_timestamp_millis((START_MS + offset_ms) as i64).unwrap().to_rfc3339()
+    }
+
+    fn mqtt_line(offset_ms: u64, event: &str, geometry_id: Option<i32>) -> String {
+        let mut attributes = serde_json::json!({ "track_id": 100 });
+        if let Some(id) = geometry_id {
+            attributes["geometry_id"] = id.into();
+        }
+        let payload = serde_json::json!({
+            "live_data": { "frames": [{
+                "time": ts(offset_ms),
+                "tracked_objects": [{ "track_id": 100, "type": "PERSON", "position": [1.0, 1.0, 1.7] }],
+                "events": [{ "type": event, "attributes": attributes }],
+            }]}
+        });
+        serde_json::json!({
+            "ts_recv": ts(offset_ms),
+            "src": "mqtt",
+            "site": "test",
+            "payload_raw": payload.to_string(),
+            "fields": { "topic": "xovis/live", "parsed": payload },
+        })
+        .to_string()
+    }
+
+    fn acc_line(offset_ms: u64, raw: &str) -> String {
+        serde_json::json!({
+            "ts_recv": ts(offset_ms),
+            "src": "acc",
+            "site": "test",
+            "payload_raw": raw,
+            "fields": { "kiosk_ip": "127.0.0.1", "receipt_id": null, "pos_zone": null },
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_parse_line_skips_unreplayable_records() {
+        let door = serde_json::json!({
+            "ts_recv": ts(0), "src": "rs485", "site": "test",
+            "payload_raw": "7f", "fields": { "door_status": "open", "checksum_ok": true },
+        });
+        assert_eq!(
+            parse_line(&door.to_string()),
+            Some(CaptureRecord { ts_ms: START_MS, input: CaptureInput::Door(DoorStatus::Open) })
+        );
+
+        let hex = serde_json::json!({
+            "ts_recv": ts(0), "src": "mqtt", "site": "test",
+            "payload_raw": "HEX:ff00", "fields": { "topic": "xovis/live", "parsed": null },
+        });
+        assert_eq!(parse_line(&hex.to_string()), None);
+        assert_eq!(parse_line(&acc_line(0, "ACC ")), None);
+        assert_eq!(parse_line(&acc_line(0, "PING")), None);
+        assert_eq!(parse_line("not json"), None);
+    }
+
+    #[tokio::test]
+    async fn test_replay_orders_capture_and_records_gate_open() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("mqtt")).unwrap();
+        std::fs::create_dir_all(dir.path().join("acc")).unwrap();
+        // Files are split by source; records are interleaved only by ts_recv
+        let mqtt = [
+            mqtt_line(0, "TRACK_CREATE", None),
+            mqtt_line(100, "ZONE_ENTRY", Some(1001)),
+            mqtt_line(3100, "ZONE_EXIT", Some(1001)),
+            mqtt_line(5000, "ZONE_ENTRY", Some(1007)),
+            mqtt_line(8000, "TRACK_DELETE", None),
+        ];
+        std::fs::write(dir.path().join("mqtt/xovis_live-20260105.jsonl"), mqtt.join("\n")).unwrap();
+        std::fs::write(dir.path().join("acc/acc-20260105.jsonl"), acc_line(2000, "ACC 4711"))
+            .unwrap();
+
+        let capture = Capture::load(dir.path()).unwrap();
+        assert_eq!(capture.records.len(), 6);
+        assert_eq!(capture.skipped, 0);
+        assert_eq!(capture.records[2].input, CaptureInput::Acc { kiosk_ip: "127.0.0.1".into() });
+
+        let config = Config::default()
+            .with_min_dwell_ms(1000)
+            .with_acc_ip_to_pos(HashMap::from([("127.0.0.1".to_string(), "POS_1".to_string())]));
+        let outcome = replay(&config, &capture).await;
+
+        let gate_opens: Vec<_> = outcome.gate_opens().collect();
+        assert_eq!(gate_opens, vec![&GateDecision { ts: START_MS + 5000, track_id: TrackId(100) }]);
+        let journeys: Vec<_> = outcome.journeys().collect();
+        assert_eq!(journeys.len(), 1);
+        assert!(journeys[0].authorized);
+        assert!(journeys[0].acc_matched);
+
+        let output = dir.path().join("replay.jsonl");
+        outcome.write(&output).unwrap();
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(&output)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["type"], "gate_open");
+        assert_eq!(lines[0]["tid"], 100);
+        assert_eq!(lines[1]["type"], "journey");
+    }
+}
@@ -0,0 +1,344 @@
+//! Sensor liveness watchdog
+//!
+//! Ingest stamps every message with the time it arrived on its sensor's topic
+//! (`SensorHealth`). The watchdog checks the stamps periodically: a sensor
+//! without frames for `frame_timeout_ms` goes offline, which is logged,
+//! published as `sensor_offline` on the egress channel and puts the gateway in
+//! degraded mode until frames resume. The tracker applies the configured
+//! `DegradedPolicy` while degraded.
+//!
+//! Event age is exported for diagnosis only: a quiet store legitimately
+//! produces frames without events.
+//!
+//! Clock skew is estimated as the minimum of (receive wall clock - Xovis event
+//! time) over a 10s window: the minimum strips queueing noise and leaves the
+//! sensor's fixed pipeline lag (~1.2s, see pos.md) plus any clock offset. An
+//! estimate beyond `max_clock_skew_ms` raises a `clock_skew` alert, which
+//! usually means NTP broke on the sensor or the Pi.
+
+use crate::domain::journey::epoch_ms;
+use crate::infra::config::{Config, DegradedPolicy};
+use crate::infra::metrics::Metrics;
+use crate::io::egress_channel::{EgressSender, SensorStatePayload};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+/// How often sensor clocks are checked
+const CHECK_INTERVAL_MS: u64 = 500;
+
+/// Window over which the minimum lag is taken as the skew estimate
+const SKEW_WINDOW_MS: u64 = 10_000;
+
+#[derive(Debug)]
+struct SensorClock {
+    last_frame_ms: AtomicU64,
+    last_event_ms: AtomicU64,
+    /// Minimum lag in the current skew window (i64::MAX if none)
+    min_lag_ms: AtomicI64,
+}
+
+/// Last frame/event arrival per sensor, written by ingest
+///
+/// Indexed like `Config::sensors()`. Clocks start at creation time so a sensor
+/// that never publishes goes offline after the timeout too.
+#[derive(Debug)]
+pub struct SensorHealth {
+    clocks: Vec<SensorClock>,
+}
+
+impl SensorHealth {
+    pub fn new(sensor_count: usize, now_ms: u64) -> Self {
+        let clocks = (0..sensor_count)
+            .map(|_| SensorClock {
+                last_frame_ms: AtomicU64::new(now_ms),
+                last_event_ms: AtomicU64::new(now_ms),
+                min_lag_ms: AtomicI64::new(i64::MAX),
+            })
+            .collect();
+        Self { clocks }
+    }
+
+    /// Record a message from the sensor at `index`
+    #[inline]
+    pub fn record_frame(&self, index: usize, now_ms: u64, has_events: bool) {
+        if let Some(clock) = self.clocks.get(index) {
+            clock.last_frame_ms.store(now_ms, Ordering::Release);
+            if has_events {
+                clock.last_event_ms.store(now_ms, Ordering::Release);
+            }
+        }
+    }
+
+    /// Record the lag of an event (receive time minus event time) from the sensor at `index`
+    #[inline]
+    pub fn record_lag(&self, index: usize, lag_ms: i64) {
+        if let Some(clock) = self.clocks.get(index) {
+            clock.min_lag_ms.fetch_min(lag_ms, Ordering::AcqRel);
+        }
+    }
+
+    /// Minimum lag since the last call, if any event arrived
+    fn take_min_lag(&self, index: usize) -> Option<i64> {
+        let min = self.clocks[index].min_lag_ms.swap(i64::MAX, Ordering::AcqRel);
+        (min != i64::MAX).then_some(min)
+    }
+
+    /// (frame age, event age) in ms for the sensor at `index`
+    fn ages(&self, index: usize, now_ms: u64) -> (u64, u64) {
+        let clock = &self.clocks[index];
+        (
+            now_ms.saturating_sub(clock.last_frame_ms.load(Ordering::Acquire)),
+            now_ms.saturating_sub(clock.last_event_ms.load(Ordering::Acquire)),
+        )
+    }
+}
+
+/// Periodically checks sensor liveness and publishes the degraded state
+pub struct SensorWatchdog {
+    /// (sensor ID, name) in config order
+    sensors: Vec<(u8, String)>,
+    online: Vec<bool>,
+    /// Latest clock skew estimate per sensor
+    skew: Vec<Option<i64>>,
+    skew_alert: Vec<bool>,
+    skew_window_start_ms: u64,
+    health: Arc<SensorHealth>,
+    frame_timeout_ms: u64,
+    max_clock_skew_ms: i64,
+    policy: DegradedPolicy,
+    metrics: Arc<Metrics>,
+    egress_sender: Option<EgressSender>,
+    degraded_tx: watch::Sender<bool>,
+}
+
+impl SensorWatchdog {
+    /// Create a watchdog over `health`
+    ///
+    /// Returns the watchdog and a receiver that is `true` while any sensor is offline.
+    pub fn new(
+        config: &Config,
+        health: Arc<SensorHealth>,
+        metrics: Arc<Metrics>,
+        egress_sender: Option<EgressSender>,
+    ) -> (Self, watch::Receiver<bool>) {
+        let sensors: Vec<(u8, String)> =
+            config.sensors().iter().map(|s| (s.id, s.name.clone())).collect();
+        let (degraded_tx, degraded_rx) = watch::channel(false);
+        let watchdog = Self {
+            online: vec![true; sensors.len()],
+            skew: vec![None; sensors.len()],
+            skew_alert: vec![false; sensors.len()],
+            skew_window_start_ms: 0,
+            sensors,
+            health,
+            frame_timeout_ms: config.sensor_watchdog().frame_timeout_ms,
+            max_clock_skew_ms: config.sensor_watchdog().max_clock_skew_ms,
+            policy: config.sensor_watchdog().degraded_policy,
+            metrics,
+            egress_sender,
+            degraded_tx,
+        };
+        (watchdog, degraded_rx)
+    }
+
+    /// Run the check loop until shutdown
+    pub async fn run(mut self, mut shutdown: watch::Receiver<bool>) {
+        info!(
+            sensors = %self.sensors.len(),
+            frame_timeout_ms = %self.frame_timeout_ms,
+            policy = %self.policy.as_str(),
+            "sensor_watchdog_started"
+        );
+        let mut interval = tokio::time::interval(Duration::from_millis(CHECK_INTERVAL_MS));
+        loop {
+            tokio::select! {
+                _ = shutdown.changed() => {
+                    if *shutdown.borrow() {
+                        break;
+                    }
+                }
+                _ = interval.tick() => {
+                    self.check(epoch_ms());
+                }
+            }
+        }
+    }
+
+    /// Update liveness of all sensors; returns true while degraded
+    pub fn check(&mut self, now_ms: u64) -> bool {
+        let skew_window_done = now_ms.saturating_sub(self.skew_window_start_ms) >= SKEW_WINDOW_MS;
+        if skew_window_done {
+            self.skew_window_start_ms = now_ms;
+        }
+
+        for index in 0..self.sensors.len() {
+            let (frame_age_ms, event_age_ms) = self.health.ages(index, now_ms);
+            let online = frame_age_ms <= self.frame_timeout_ms;
+            self.metrics.set_sensor_liveness(index, frame_age_ms, event_age_ms, online);
+            if online != self.online[index] {
+                self.online[index] = online;
+                self.log_liveness_change(index, online, frame_age_ms);
+                let t = if online { "sensor_online" } else { "sensor_offline" };
+                self.publish(index, t, now_ms, frame_age_ms, event_age_ms);
+            }
+
+            if !skew_window_done {
+                continue;
+            }
+            let Some(skew_ms) = self.health.take_min_lag(index) else { continue };
+            self.skew[index] = Some(skew_ms);
+            self.metrics.set_sensor_clock_skew(index, skew_ms);
+            let alert = skew_ms.abs() > self.max_clock_skew_ms;
+            if alert != self.skew_alert[index] {
+                self.skew_alert[index] = alert;
+                self.log_skew_change(index, alert, skew_ms);
+                let t = if alert { "clock_skew" } else { "clock_skew_ok" };
+                self.publish(index, t, now_ms, frame_age_ms, event_age_ms);
+            }
+        }
+
+        let degraded = self.online.contains(&false);
+        self.degraded_tx.send_if_modified(|current| {
+            let changed = *current != degraded;
+            *current = degraded;
+            changed
+        });
+        degraded
+    }
+
+    fn log_liveness_change(&self, index: usize, online: bool, frame_age_ms: u64) {
+        let (sensor_id, ref name) = self.sensors[index];
+        if online {
+            info!(sensor = %sensor_id, name = %name, "sensor_online");
+        } else {
+            self.metrics.record_sensor_offline();
+            warn!(
+                sensor = %sensor_id,
+                name = %name,
+                frame_age_ms = %frame_age_ms,
+                policy = %self.policy.as_str(),
+                "sensor_offline"
+            );
+        }
+    }
+
+    fn log_skew_change(&self, index: usize, alert: bool, skew_ms: i64) {
+        let (sensor_id, ref name) = self.sensors[index];
+        if alert {
+            self.metrics.record_clock_skew_alert();
+            warn!(
+                sensor = %sensor_id,
+                name = %name,
+                clock_skew_ms = %skew_ms,
+                max_clock_skew_ms = %self.max_clock_skew_ms,
+                "clock_skew_alert"
+            );
+        } else {
+            info!(sensor = %sensor_id, name = %name, clock_skew_ms = %skew_ms, "clock_skew_ok");
+        }
+    }
+
+    /// Publish a sensor state change on the egress channel
+    fn publish(&self, index: usize, t: &str, now_ms: u64, frame_age_ms: u64, event_age_ms: u64) {
+        let Some(ref sender) = self.egress_sender else { return };
+        let (sensor_id, ref name) = self.sensors[index];
+        sender.send_sensor_state(SensorStatePayload {
+            site: None,
+            ts: now_ms,
+            t: t.to_string(),
+            sensor: sensor_id,
+            name: name.clone(),
+            frame_age_ms,
+            event_age_ms,
+            policy: self.policy.as_str().to_string(),
+            clock_skew_ms: self.skew[index],
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_watchdog() -> (SensorWatchdog, watch::Receiver<bool>, Arc<SensorHealth>, Arc<Metrics>)
+    {
+        let config = Config::default();
+        let health = Arc::new(SensorHealth::new(config.sensors().len(), 0));
+        let metrics = Arc::new(Metrics::new());
+        metrics.set_sensors(&[0]);
+        let (watchdog, rx) = SensorWatchdog::new(&config, health.clone(), metrics.clone(), None);
+        (watchdog, rx, health, metrics)
+    }
+
+    #[test]
+    fn test_sensor_goes_offline_and_recovers() {
+        let (mut watchdog, degraded_rx, health, metrics) = create_watchdog();
+
+        health.record_frame(0, 1000, true);
+        assert!(!watchdog.check(3000));
+        assert!(!*degraded_rx.borrow());
+
+        // No frames for longer than the 5s default timeout
+        assert!(watchdog.check(6500));
+        assert!(*degraded_rx.borrow());
+        let liveness = metrics.sensor_liveness();
+        assert_eq!(liveness[0].frame_age_ms, 5500);
+        assert!(!liveness[0].online);
+        assert_eq!(metrics.report(0, 0).sensor_offline_total, 1);
+
+        // Still offline: no repeated transition
+        assert!(watchdog.check(8000));
+        assert_eq!(metrics.report(0, 0).sensor_offline_total, 1);
+
+        // Frames resume (without events)
+        health.record_frame(0, 8200, false);
+        assert!(!watchdog.check(8500));
+        assert!(!*degraded_rx.borrow());
+        let liveness = metrics.sensor_liveness();
+        assert!(liveness[0].online);
+        assert_eq!(liveness[0].event_age_ms, 7500);
+    }
+
+    #[test]
+    fn test_clock_skew_estimate_and_alert() {
+        let (mut watchdog, _rx, health, metrics) = create_watchdog();
+        watchdog.check(0);
+
+        // Normal pipeline lag: minimum over the window is the estimate
+        for lag in [1400, 1250, 1800] {
+            health.record_frame(0, 5000, true);
+            health.record_lag(0, lag);
+        }
+        watchdog.check(5000);
+        assert_eq!(watchdog.skew[0], None, "window not complete yet");
+        watchdog.check(10_000);
+        assert_eq!(watchdog.skew[0], Some(1250));
+        assert_eq!(metrics.sensor_liveness()[0].clock_skew_ms, 1250);
+        assert!(!watchdog.skew_alert[0]);
+
+        // Sensor clock falls 8s behind
+        health.record_frame(0, 15_000, true);
+        health.record_lag(0, 9300);
+        watchdog.check(20_000);
+        assert!(watchdog.skew_alert[0]);
+        let summary = metrics.report(0, 0);
+        assert_eq!(summary.clock_skew_alerts_total, 1);
+        assert_eq!(summary.clock_skew_ms, 9300);
+
+        // No events in a window: estimate and alert are kept
+        health.record_frame(0, 25_000, false);
+        watchdog.check(30_000);
+        assert_eq!(watchdog.skew[0], Some(9300));
+        assert!(watchdog.skew_alert[0]);
+    }
+
+    #[test]
+    fn test_silent_sensor_goes_offline_after_startup() {
+        let (mut watchdog, _rx, _health, _metrics) = create_watchdog();
+        assert!(!watchdog.check(4000));
+        assert!(watchdog.check(5001));
+    }
+}
@@ -0,0 +1,239 @@
+//! Shadow authorization policy
+//!
+//! Evaluates a candidate policy (`[shadow]` overrides of min dwell, exit grace
+//! and the ACC IP to POS mapping) on the live event stream. It keeps its own
+//! POS occupancy, dwell totals and authorization per track, and never sends a
+//! gate command: the tracker compares its decisions with the live ones at the
+//! gate and publishes the differences (see `tracker::shadow`).
+//!
+//! Identity changes (stitch, handover) and authorization that is not a policy
+//! decision (group extension, inheritance, snapshot restore) are mirrored from
+//! the live tracker.
+
+use crate::domain::types::TrackId;
+use crate::infra::config::Config;
+use crate::services::acc_collector::AccCollector;
+use crate::services::pos_occupancy::{PosOccupancyState, PosSessionSnapshot};
+use rustc_hash::FxHashMap;
+use std::time::{Duration, Instant};
+
+/// How long a track's shadow state outlives its live person (stitch window margin)
+const TRACK_RETENTION: Duration = Duration::from_secs(60);
+
+/// Shadow state of one track
+#[derive(Debug, Default, Clone, Copy)]
+struct ShadowTrack {
+    authorized: bool,
+    /// Dwell accumulated over all POS and DWELL zones (ms)
+    total_dwell_ms: u64,
+    /// When the track was last found missing from the live persons
+    absent_since: Option<Instant>,
+}
+
+/// Candidate policy run next to the live one
+pub struct ShadowPolicy {
+    /// Live config with the `[shadow]` overrides applied
+    config: Config,
+    pos_occupancy: PosOccupancyState,
+    acc_collector: AccCollector,
+    tracks: FxHashMap<TrackId, ShadowTrack>,
+}
+
+impl ShadowPolicy {
+    pub fn new(live: &Config) -> Self {
+        let config = live.shadow().apply(live);
+        let pos_occupancy =
+            PosOccupancyState::new(config.pos_exit_grace_ms(), config.min_dwell_ms());
+        let acc_collector = AccCollector::new(&config);
+        Self { config, pos_occupancy, acc_collector, tracks: FxHashMap::default() }
+    }
+
+    /// Re-apply the overrides to a reloaded live config; sessions and tracks are kept
+    pub fn apply_config(&mut self, live: &Config) {
+        self.config = live.shadow().apply(live);
+        self.acc_collector = AccCollector::new(&self.config);
+        self.pos_occupancy
+            .set_thresholds(self.config.pos_exit_grace_ms(), self.config.min_dwell_ms());
+    }
+
+    /// Minimum dwell of the candidate policy (ms)
+    pub fn min_dwell_ms(&self) -> u64 {
+        self.config.min_dwell_ms()
+    }
+
+    /// Record a track entering a POS or DWELL zone
+    pub fn zone_entry(&mut self, zone: &str, track_id: TrackId, now: Instant) {
+        self.pos_occupancy.record_entry(zone, track_id, now);
+    }
+
+    /// Record a track exiting a POS or DWELL zone
+    ///
+    /// Exiting a DWELL zone with enough total dwell authorizes the track.
+    pub fn zone_exit(&mut self, zone: &str, track_id: TrackId, now: Instant, is_dwell: bool) {
+        let Some((session_dwell_ms, _)) = self.pos_occupancy.record_exit(zone, track_id, now)
+        else {
+            return;
+        };
+        let min_dwell_ms = self.config.min_dwell_ms();
+        let track = self.tracks.entry(track_id).or_default();
+        track.total_dwell_ms += session_dwell_ms;
+        if is_dwell && track.total_dwell_ms >= min_dwell_ms {
+            track.authorized = true;
+        }
+    }
+
+    /// POS zone of an ACC terminal under the candidate mapping
+    pub fn pos_for_ip(&self, ip: &str) -> Option<&str> {
+        self.acc_collector.pos_for_ip(ip)
+    }
+
+    /// Match an ACC event at `pos_zone` and authorize the qualified tracks
+    ///
+    /// Same selection as the live policy: present and recently exited tracks
+    /// with at least the minimum dwell.
+    pub fn acc(&mut self, pos_zone: &str, now: Instant) -> Vec<TrackId> {
+        let candidates = self.pos_occupancy.get_candidates(pos_zone, now);
+        self.pos_occupancy.prune_expired(pos_zone, now);
+        let min_dwell_ms = self.pos_occupancy.min_dwell_ms();
+        let qualified: Vec<TrackId> = candidates
+            .into_iter()
+            .filter(|(_, dwell)| *dwell >= min_dwell_ms)
+            .map(|(tid, _)| tid)
+            .collect();
+        for &track_id in &qualified {
+            self.authorize(track_id);
+        }
+        qualified
+    }
+
+    /// Authorize a track outside a policy decision (group, inheritance, restore)
+    pub fn authorize(&mut self, track_id: TrackId) {
+        self.tracks.entry(track_id).or_default().authorized = true;
+    }
+
+    /// Authorize `track_id` if `from` is authorized (inheritance)
+    pub fn copy_authorization(&mut self, from: TrackId, track_id: TrackId) {
+        if self.is_authorized(from) {
+            self.authorize(track_id);
+        }
+    }
+
+    /// Continue `old`'s state on `new` (stitch, sensor handover)
+    pub fn carry_over(&mut self, old: TrackId, new: TrackId) {
+        let Some(old) = self.tracks.remove(&old) else {
+            return;
+        };
+        let track = self.tracks.entry(new).or_default();
+        track.authorized |= old.authorized;
+        track.total_dwell_ms = track.total_dwell_ms.max(old.total_dwell_ms);
+        track.absent_since = None;
+    }
+
+    /// Continue `old`'s state and POS sessions on `new` (sensor handover)
+    pub fn hand_over(&mut self, old: TrackId, new: TrackId, now: Instant) {
+        self.carry_over(old, new);
+        self.pos_occupancy.hand_over(old, new, now);
+    }
+
+    pub fn is_authorized(&self, track_id: TrackId) -> bool {
+        self.tracks.get(&track_id).is_some_and(|t| t.authorized)
+    }
+
+    /// Total dwell under the candidate policy (ms)
+    pub fn dwell(&self, track_id: TrackId) -> u64 {
+        self.tracks.get(&track_id).map_or(0, |t| t.total_dwell_ms)
+    }
+
+    /// Restore POS sessions from a tracker snapshot, aged by `offline_ms`
+    pub fn restore(&mut self, sessions: Vec<PosSessionSnapshot>, now: Instant, offline_ms: u64) {
+        self.pos_occupancy.restore(sessions, now, offline_ms);
+    }
+
+    /// Drop tracks that have not been live for `TRACK_RETENTION`
+    pub fn prune(&mut self, is_live: impl Fn(TrackId) -> bool, now: Instant) {
+        self.tracks.retain(|&track_id, track| {
+            if is_live(track_id) {
+                track.absent_since = None;
+                return true;
+            }
+            let absent_since = *track.absent_since.get_or_insert(now);
+            now.saturating_duration_since(absent_since) <= TRACK_RETENTION
+        });
+    }
+
+    /// Number of tracks with shadow state
+    pub fn track_count(&self) -> usize {
+        self.tracks.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::config::ShadowConfig;
+    use std::collections::HashMap;
+
+    fn create_policy(min_dwell_ms: u64) -> ShadowPolicy {
+        let mut ip_to_pos = HashMap::new();
+        ip_to_pos.insert("192.168.1.10".to_string(), "POS_1".to_string());
+        let config = Config::default()
+            .with_min_dwell_ms(7000)
+            .with_acc_ip_to_pos(ip_to_pos)
+            .with_shadow(ShadowConfig {
+                enabled: true,
+                min_dwell_ms: Some(min_dwell_ms),
+                ..Default::default()
+            });
+        ShadowPolicy::new(&config)
+    }
+
+    #[test]
+    fn test_overrides_applied() {
+        let policy = create_policy(3000);
+        assert_eq!(policy.min_dwell_ms(), 3000);
+        // Unset overrides take the live value
+        assert_eq!(policy.pos_for_ip("192.168.1.10"), Some("POS_1"));
+    }
+
+    #[test]
+    fn test_acc_uses_shadow_min_dwell() {
+        let mut policy = create_policy(3000);
+        let now = Instant::now();
+        policy.zone_entry("POS_1", TrackId(100), now);
+
+        let authorized = policy.acc("POS_1", now + Duration::from_millis(4000));
+        assert_eq!(authorized, vec![TrackId(100)]);
+        assert!(policy.is_authorized(TrackId(100)));
+    }
+
+    #[test]
+    fn test_dwell_zone_auto_authorizes() {
+        let mut policy = create_policy(3000);
+        let now = Instant::now();
+        policy.zone_entry("DWELL_1", TrackId(100), now);
+        policy.zone_exit("DWELL_1", TrackId(100), now + Duration::from_millis(2000), true);
+        assert!(!policy.is_authorized(TrackId(100)));
+
+        policy.zone_entry("DWELL_1", TrackId(100), now + Duration::from_millis(2500));
+        policy.zone_exit("DWELL_1", TrackId(100), now + Duration::from_millis(3500), true);
+        assert_eq!(policy.dwell(TrackId(100)), 3000);
+        assert!(policy.is_authorized(TrackId(100)));
+    }
+
+    #[test]
+    fn test_carry_over_and_prune() {
+        let mut policy = create_policy(3000);
+        let now = Instant::now();
+        policy.authorize(TrackId(100));
+        policy.carry_over(TrackId(100), TrackId(200));
+        assert!(!policy.is_authorized(TrackId(100)));
+        assert!(policy.is_authorized(TrackId(200)));
+
+        // Absent tracks are kept for the retention window, then dropped
+        policy.prune(|_| false, now);
+        policy.prune(|_| false, now + TRACK_RETENTION);
+        assert_eq!(policy.track_count(), 1);
+        policy.prune(|_| false, now + TRACK_RETENTION + Duration::from_millis(1));
+        assert_eq!(policy.track_count(), 0);
+    }
+}
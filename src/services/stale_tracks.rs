@@ -0,0 +1,76 @@
+//! Stale track pruning for the position-driven services
+//!
+//! The zone engine, virtual gate triggers, GROUP membership and the position
+//! stream throttle keep state per track seen in sensor positions. Tracks normally end with a TRACK_DELETE;
+//! state of a track that disappears without one is dropped once it has not
+//! been seen for `STALE_TRACK_MS`, checked at most once a second.
+
+use rustc_hash::FxHashMap;
+use std::hash::Hash;
+
+/// Forget tracks without positions or events for this long
+pub const STALE_TRACK_MS: u64 = 30_000;
+
+/// Minimum time between prune passes
+const PRUNE_INTERVAL_MS: u64 = 1000;
+
+/// Whether a track last seen at `last_seen_ms` is stale at `now_ms` (epoch ms)
+#[inline]
+pub fn is_stale(last_seen_ms: u64, now_ms: u64) -> bool {
+    now_ms.saturating_sub(last_seen_ms) >= STALE_TRACK_MS
+}
+
+/// Rate limit for stale track prune passes
+#[derive(Debug, Default)]
+pub struct StalePruner {
+    last_prune_ms: u64,
+}
+
+impl StalePruner {
+    /// Whether a prune pass is due at `now_ms`; starts a new interval if so
+    pub fn due(&mut self, now_ms: u64) -> bool {
+        if now_ms.saturating_sub(self.last_prune_ms) < PRUNE_INTERVAL_MS {
+            return false;
+        }
+        self.last_prune_ms = now_ms;
+        true
+    }
+
+    /// Drop the stale entries of `tracks` if a prune pass is due
+    pub fn retain<K: Eq + Hash, V>(
+        &mut self,
+        tracks: &mut FxHashMap<K, V>,
+        now_ms: u64,
+        last_seen_ms: impl Fn(&V) -> u64,
+    ) {
+        if self.due(now_ms) {
+            tracks.retain(|_, v| !is_stale(last_seen_ms(v), now_ms));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retain_drops_stale_at_most_once_per_second() {
+        let mut pruner = StalePruner::default();
+        let mut tracks: FxHashMap<i64, u64> = FxHashMap::default();
+        tracks.insert(1, 1_000);
+        tracks.insert(2, 20_000);
+
+        pruner.retain(&mut tracks, 31_000, |&seen| seen);
+        assert_eq!(tracks.len(), 1);
+        assert!(tracks.contains_key(&2));
+
+        // A stale track is kept until the next pass is due
+        tracks.insert(3, 0);
+        pruner.retain(&mut tracks, 31_500, |&seen| seen);
+        assert_eq!(tracks.len(), 2);
+        pruner.retain(&mut tracks, 32_000, |&seen| seen);
+        assert_eq!(tracks.len(), 1);
+        pruner.retain(&mut tracks, 20_000 + STALE_TRACK_MS, |&seen| seen);
+        assert!(tracks.is_empty());
+    }
+}
@@ -3,22 +3,23 @@
 //! Enhanced features:
 //! - Extended grace time for POS zones (people linger at checkout)
 //! - POS zone memory: matching preference for tracks lost in same zone
+//! - Sensor handover: continue a person on another sensor's live track in the overlap
 
-use crate::domain::types::{Person, TrackId};
+use crate::domain::types::{Person, TrackId, ZoneKind};
+use crate::infra::clock::SharedClock;
+use crate::infra::config::StitcherConfig;
 use crate::infra::metrics::Metrics;
+use parking_lot::RwLock;
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tracing::{debug, info};
 
-/// Stitch criteria
-const MAX_TIME_MS: u64 = 4500; // 4.5 seconds base grace time
-const MAX_TIME_POS_ZONE_MS: u64 = 8000; // 8 seconds for tracks lost in POS zones
+/// Fixed stitch criteria (the rest is `[stitcher]`, see `StitcherConfig`)
 const MAX_TIME_SPAWN_HINT_MS: u64 = 10000; // 10 seconds for spawn-hint POS matches
-const MAX_DISTANCE_CM: f64 = 180.0; // 180cm
-const MAX_DISTANCE_SAME_ZONE_CM: f64 = 300.0; // 300cm if same zone context
 const MAX_DISTANCE_SPAWN_HINT_CM: f64 = 190.0; // 190cm for spawn-hint matches
-const MAX_HEIGHT_DIFF_CM: f64 = 10.0; // ±10cm
-const MAX_HEIGHT_DIFF_POS_CM: f64 = 15.0; // ±15cm for POS zones (people bend)
+const MAX_HANDOVER_DISTANCE_CM: f64 = 100.0; // 100cm between two sensors' tracks of one person
 
 /// Result of a successful stitch match
 #[derive(Debug)]
@@ -28,6 +29,42 @@ pub struct StitchMatch {
     pub distance_cm: u32,
 }
 
+/// Result of a cross-sensor handover match
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HandoverMatch {
+    /// Live track on the other sensor that continues the person
+    pub track_id: TrackId,
+    pub distance_cm: u32,
+}
+
+/// Latest store-frame position per live track, written by ingest
+///
+/// Only used with several sensors: the tracker reads it on TRACK_DELETE to find
+/// the other sensor's track of the same person for a handover.
+#[derive(Debug, Default)]
+pub struct LivePositions {
+    positions: RwLock<FxHashMap<TrackId, ([f64; 3], Instant)>>,
+}
+
+impl LivePositions {
+    /// Record the current position of a track
+    pub fn update(&self, track_id: TrackId, position: [f64; 3], now: Instant) {
+        self.positions.write().insert(track_id, (position, now));
+    }
+
+    /// Forget a deleted track
+    pub fn remove(&self, track_id: TrackId) {
+        self.positions.write().remove(&track_id);
+    }
+
+    /// Tracks seen within `max_age` of `now`, dropping older entries
+    pub fn recent(&self, max_age: Duration, now: Instant) -> Vec<(TrackId, [f64; 3])> {
+        let mut positions = self.positions.write();
+        positions.retain(|_, (_, seen)| now.duration_since(*seen) <= max_age);
+        positions.iter().map(|(id, (pos, _))| (*id, *pos)).collect()
+    }
+}
+
 /// A track pending potential stitching
 #[derive(Debug, Clone)]
 struct PendingTrack {
@@ -35,6 +72,17 @@ struct PendingTrack {
     deleted_at: Instant,
     position: Option<[f64; 3]>,
     last_zone: Option<String>,
+    last_zone_kind: ZoneKind,
+}
+
+/// A pending track in a tracker snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingTrackSnapshot {
+    pub person: Person,
+    pub deleted_ms_ago: u64,
+    pub position: Option<[f64; 3]>,
+    pub last_zone: Option<String>,
+    pub last_zone_kind: ZoneKind,
 }
 
 /// Debug info about a pending track (for ACC debugging)
@@ -51,23 +99,29 @@ pub struct PendingTrackInfo {
 pub struct Stitcher {
     pending: Vec<PendingTrack>,
     metrics: Option<Arc<Metrics>>,
-}
-
-impl Default for Stitcher {
-    fn default() -> Self {
-        Self::new()
-    }
+    clock: SharedClock,
+    config: StitcherConfig,
 }
 
 impl Stitcher {
     #[allow(dead_code)]
-    pub fn new() -> Self {
-        Self { pending: Vec::new(), metrics: None }
+    pub fn new(clock: SharedClock) -> Self {
+        Self { pending: Vec::new(), metrics: None, clock, config: StitcherConfig::default() }
     }
 
     /// Create a stitcher with metrics recording
-    pub fn with_metrics(metrics: Arc<Metrics>) -> Self {
-        Self { pending: Vec::new(), metrics: Some(metrics) }
+    pub fn with_metrics(metrics: Arc<Metrics>, clock: SharedClock) -> Self {
+        Self {
+            pending: Vec::new(),
+            metrics: Some(metrics),
+            clock,
+            config: StitcherConfig::default(),
+        }
+    }
+
+    /// Replace the stitch thresholds (startup, config reload)
+    pub fn set_config(&mut self, config: &StitcherConfig) {
+        self.config = config.clone();
     }
 
     /// Add a deleted track as pending for potential stitching
@@ -76,15 +130,23 @@ impl Stitcher {
         person: Person,
         position: Option<[f64; 3]>,
         last_zone: Option<String>,
+        last_zone_kind: ZoneKind,
     ) {
         debug!(
             track_id = %person.track_id,
             authorized = %person.authorized,
             last_zone = ?last_zone,
+            last_zone_kind = %last_zone_kind.as_str(),
             "pending_stitch_added"
         );
 
-        self.pending.push(PendingTrack { person, deleted_at: Instant::now(), position, last_zone });
+        self.pending.push(PendingTrack {
+            person,
+            deleted_at: self.clock.now(),
+            position,
+            last_zone,
+            last_zone_kind,
+        });
     }
 
     /// Try to find and remove a stitch candidate for a new track at given position
@@ -124,12 +186,12 @@ impl Stitcher {
         self.cleanup_expired();
 
         let new_pos = new_position?;
-        let now = Instant::now();
+        let now = self.clock.now();
 
         let mut best_match: Option<(usize, f64, bool)> = None; // (idx, distance, same_zone)
 
         for (i, pending) in self.pending.iter().enumerate() {
-            let is_pos_zone = pending.last_zone.as_ref().is_some_and(|z| z.starts_with("POS_"));
+            let is_pos_zone = pending.last_zone_kind == ZoneKind::Pos;
             let same_zone = current_zone.is_some() && pending.last_zone.as_deref() == current_zone;
 
             // Time check - use extended time for POS zones, even more for spawn-hint
@@ -137,9 +199,9 @@ impl Stitcher {
             let max_time = if spawn_hint && is_pos_zone && same_zone {
                 MAX_TIME_SPAWN_HINT_MS // 10s for spawn-hint same-zone POS
             } else if is_pos_zone {
-                MAX_TIME_POS_ZONE_MS // 8s for POS zones
+                self.config.max_time_pos_zone_ms // 8s for POS zones by default
             } else {
-                MAX_TIME_MS // 4.5s base
+                self.config.max_time_ms // 4.5s base by default
             };
             if age_ms > max_time {
                 continue;
@@ -153,9 +215,9 @@ impl Stitcher {
             // Height check - relaxed for POS zones (people bend at checkout)
             let height_diff_cm = (new_pos[2] - old_pos[2]).abs() * 100.0;
             let max_height = if is_pos_zone {
-                MAX_HEIGHT_DIFF_POS_CM // 15cm for POS zones
+                self.config.max_height_diff_pos_cm // 15cm for POS zones by default
             } else {
-                MAX_HEIGHT_DIFF_CM // 10cm base
+                self.config.max_height_diff_cm // 10cm base by default
             };
             if height_diff_cm > max_height {
                 debug!(
@@ -181,9 +243,9 @@ impl Stitcher {
             let max_distance = if same_zone && spawn_hint {
                 MAX_DISTANCE_SPAWN_HINT_CM // 190cm for spawn-hint same-zone
             } else if same_zone {
-                MAX_DISTANCE_SAME_ZONE_CM // 300cm for same-zone
+                self.config.max_distance_same_zone_cm // 300cm for same-zone by default
             } else {
-                MAX_DISTANCE_CM // 180cm base
+                self.config.max_distance_cm // 180cm base by default
             };
 
             if distance_cm > max_distance {
@@ -233,19 +295,88 @@ impl Stitcher {
         })
     }
 
+    /// Find a live track on another sensor that continues a deleted track
+    ///
+    /// In the overlap between two sensors both track the person, so the other
+    /// sensor's track already exists when this one is deleted and the pending
+    /// pool (which only matches tracks created later) cannot pair them.
+    /// `candidates` are the live tracks with their last store-frame position;
+    /// the closest one on a different sensor within 100cm and ±10cm height wins.
+    pub fn find_handover(
+        &self,
+        deleted: TrackId,
+        position: [f64; 3],
+        candidates: impl IntoIterator<Item = (TrackId, [f64; 3])>,
+    ) -> Option<HandoverMatch> {
+        let best = candidates
+            .into_iter()
+            .filter(|(track_id, _)| track_id.sensor_id() != deleted.sensor_id())
+            .filter(|(_, pos)| {
+                (pos[2] - position[2]).abs() * 100.0 <= self.config.max_height_diff_cm
+            })
+            .map(|(track_id, pos)| {
+                let (dx, dy) = (pos[0] - position[0], pos[1] - position[1]);
+                (track_id, (dx * dx + dy * dy).sqrt() * 100.0)
+            })
+            .filter(|&(_, distance_cm)| distance_cm <= MAX_HANDOVER_DISTANCE_CM)
+            .min_by(|a, b| a.1.total_cmp(&b.1))?;
+
+        info!(
+            old_track_id = %deleted,
+            new_track_id = %best.0,
+            distance_cm = %best.1 as u32,
+            "sensor_handover_found"
+        );
+        if let Some(ref m) = self.metrics {
+            m.record_stitch_handover();
+        }
+        Some(HandoverMatch { track_id: best.0, distance_cm: best.1 as u32 })
+    }
+
+    /// Copy the pending pool for a snapshot
+    pub fn snapshot(&self, now: Instant) -> Vec<PendingTrackSnapshot> {
+        self.pending
+            .iter()
+            .map(|p| PendingTrackSnapshot {
+                person: p.person.clone(),
+                deleted_ms_ago: now.saturating_duration_since(p.deleted_at).as_millis() as u64,
+                position: p.position,
+                last_zone: p.last_zone.clone(),
+                last_zone_kind: p.last_zone_kind,
+            })
+            .collect()
+    }
+
+    /// Restore the pending pool, aged by `offline_ms` (time since the snapshot)
+    ///
+    /// Entries older than the stitch window are dropped on the next match.
+    pub fn restore(&mut self, pending: Vec<PendingTrackSnapshot>, now: Instant, offline_ms: u64) {
+        for p in pending {
+            let age = Duration::from_millis(p.deleted_ms_ago + offline_ms);
+            self.pending.push(PendingTrack {
+                person: p.person,
+                deleted_at: now.checked_sub(age).unwrap_or(now),
+                position: p.position,
+                last_zone: p.last_zone,
+                last_zone_kind: p.last_zone_kind,
+            });
+        }
+    }
+
     /// Remove expired pending tracks
     /// Uses extended time for tracks that were in POS zones
     fn cleanup_expired(&mut self) {
-        let now = Instant::now();
+        let now = self.clock.now();
         let before = self.pending.len();
         let metrics = self.metrics.clone();
+        let config = &self.config;
 
         self.pending.retain(|p| {
             let age_ms = now.duration_since(p.deleted_at).as_millis() as u64;
-            let max_time = if p.last_zone.as_ref().is_some_and(|z| z.starts_with("POS_")) {
-                MAX_TIME_POS_ZONE_MS
+            let max_time = if p.last_zone_kind == ZoneKind::Pos {
+                config.max_time_pos_zone_ms
             } else {
-                MAX_TIME_MS
+                config.max_time_ms
             };
             if age_ms > max_time {
                 info!(
@@ -279,7 +410,7 @@ impl Stitcher {
 
     /// Get debug info about all pending tracks (for ACC debugging)
     pub fn get_pending_info(&self) -> Vec<PendingTrackInfo> {
-        let now = Instant::now();
+        let now = self.clock.now();
         self.pending
             .iter()
             .map(|p| PendingTrackInfo {
@@ -297,16 +428,22 @@ impl Stitcher {
 mod tests {
     use super::*;
     use crate::domain::types::{Person, TrackId};
+    use crate::infra::clock::{ManualClock, SystemClock};
 
     #[test]
     fn test_stitch_within_criteria() {
-        let mut stitcher = Stitcher::new();
+        let mut stitcher = Stitcher::new(SystemClock::shared());
 
         let mut person = Person::new(TrackId(100));
         person.authorized = true;
 
         // Add pending at position [1.0, 1.0, 1.7]
-        stitcher.add_pending(person, Some([1.0, 1.0, 1.70]), Some("POS_1".to_string()));
+        stitcher.add_pending(
+            person,
+            Some([1.0, 1.0, 1.70]),
+            Some("POS_1".to_string()),
+            ZoneKind::Pos,
+        );
 
         // New track at [1.5, 1.0, 1.72] - within 180cm and ±10cm height (50cm away)
         let result = stitcher.find_match(Some([1.5, 1.0, 1.72]));
@@ -319,12 +456,61 @@ mod tests {
         assert!(stitch.time_ms < 100); // Should be near-instant in test
     }
 
+    #[test]
+    fn test_pos_zone_grace_window_edges() {
+        let clock = Arc::new(ManualClock::new(1_000_000));
+        let mut stitcher = Stitcher::new(clock.clone());
+        let max_time_ms = StitcherConfig::default().max_time_pos_zone_ms;
+        let pending = |stitcher: &mut Stitcher, track_id| {
+            stitcher.add_pending(
+                Person::new(TrackId(track_id)),
+                Some([1.0, 1.0, 1.70]),
+                Some("POS_1".to_string()),
+                ZoneKind::Pos,
+            );
+        };
+
+        pending(&mut stitcher, 100);
+        clock.advance(Duration::from_millis(max_time_ms));
+        let stitch = stitcher.find_match(Some([1.2, 1.0, 1.70])).unwrap();
+        assert_eq!(stitch.person.track_id, TrackId(100));
+        assert_eq!(stitch.time_ms, max_time_ms);
+
+        pending(&mut stitcher, 200);
+        clock.advance(Duration::from_millis(max_time_ms + 1));
+        assert!(stitcher.find_match(Some([1.2, 1.0, 1.70])).is_none());
+        assert_eq!(stitcher.pending_count(), 0);
+    }
+
+    #[test]
+    fn test_configured_thresholds() {
+        let clock = Arc::new(ManualClock::new(1_000_000));
+        let mut stitcher = Stitcher::new(clock.clone());
+        stitcher.set_config(&StitcherConfig {
+            max_time_ms: 6000,
+            max_distance_cm: 250.0,
+            ..StitcherConfig::default()
+        });
+
+        stitcher.add_pending(
+            Person::new(TrackId(100)),
+            Some([1.0, 1.0, 1.70]),
+            None,
+            ZoneKind::Other,
+        );
+        clock.advance(Duration::from_millis(5000));
+        // 2m away after 5s: outside the default 180cm / 4.5s
+        let stitch = stitcher.find_match(Some([3.0, 1.0, 1.70])).unwrap();
+        assert_eq!(stitch.person.track_id, TrackId(100));
+        assert_eq!(stitch.distance_cm, 200);
+    }
+
     #[test]
     fn test_stitch_too_far() {
-        let mut stitcher = Stitcher::new();
+        let mut stitcher = Stitcher::new(SystemClock::shared());
 
         let person = Person::new(TrackId(100));
-        stitcher.add_pending(person, Some([1.0, 1.0, 1.70]), None);
+        stitcher.add_pending(person, Some([1.0, 1.0, 1.70]), None, ZoneKind::Other);
 
         // New track at [4.0, 1.0, 1.70] - 300cm away, too far
         let result = stitcher.find_match(Some([4.0, 1.0, 1.70]));
@@ -335,10 +521,10 @@ mod tests {
 
     #[test]
     fn test_stitch_height_mismatch() {
-        let mut stitcher = Stitcher::new();
+        let mut stitcher = Stitcher::new(SystemClock::shared());
 
         let person = Person::new(TrackId(100));
-        stitcher.add_pending(person, Some([1.0, 1.0, 1.70]), None);
+        stitcher.add_pending(person, Some([1.0, 1.0, 1.70]), None, ZoneKind::Other);
 
         // New track same location but 20cm taller
         let result = stitcher.find_match(Some([1.0, 1.0, 1.90]));
@@ -348,10 +534,10 @@ mod tests {
 
     #[test]
     fn test_no_position_no_match() {
-        let mut stitcher = Stitcher::new();
+        let mut stitcher = Stitcher::new(SystemClock::shared());
 
         let person = Person::new(TrackId(100));
-        stitcher.add_pending(person, Some([1.0, 1.0, 1.70]), None);
+        stitcher.add_pending(person, Some([1.0, 1.0, 1.70]), None, ZoneKind::Other);
 
         // New track without position
         let result = stitcher.find_match(None);
@@ -361,11 +547,11 @@ mod tests {
 
     #[test]
     fn test_pending_without_position() {
-        let mut stitcher = Stitcher::new();
+        let mut stitcher = Stitcher::new(SystemClock::shared());
 
         // Pending track without position (rare but possible)
         let person = Person::new(TrackId(100));
-        stitcher.add_pending(person, None, None);
+        stitcher.add_pending(person, None, None, ZoneKind::Other);
 
         // New track with position - can't match pending without position
         let result = stitcher.find_match(Some([1.0, 1.0, 1.70]));
@@ -376,16 +562,16 @@ mod tests {
 
     #[test]
     fn test_best_match_selected() {
-        let mut stitcher = Stitcher::new();
+        let mut stitcher = Stitcher::new(SystemClock::shared());
 
         // Add two pending tracks
         let mut person1 = Person::new(TrackId(100));
         person1.authorized = false;
-        stitcher.add_pending(person1, Some([1.0, 1.0, 1.70]), None);
+        stitcher.add_pending(person1, Some([1.0, 1.0, 1.70]), None, ZoneKind::Other);
 
         let mut person2 = Person::new(TrackId(200));
         person2.authorized = true;
-        stitcher.add_pending(person2, Some([1.2, 1.0, 1.70]), None); // Closer
+        stitcher.add_pending(person2, Some([1.2, 1.0, 1.70]), None, ZoneKind::Other); // Closer
 
         // New track - should match closer one (person2, 10cm away vs 30cm)
         let result = stitcher.find_match(Some([1.3, 1.0, 1.70]));
@@ -400,13 +586,18 @@ mod tests {
 
     #[test]
     fn test_absolutely_no_stitch() {
-        let mut stitcher = Stitcher::new();
+        let mut stitcher = Stitcher::new(SystemClock::shared());
 
         let mut person = Person::new(TrackId(100));
         person.authorized = true;
 
         // Pending at one corner of the store
-        stitcher.add_pending(person, Some([0.0, 0.0, 1.50]), Some("POS_2".to_string()));
+        stitcher.add_pending(
+            person,
+            Some([0.0, 0.0, 1.50]),
+            Some("POS_2".to_string()),
+            ZoneKind::Pos,
+        );
 
         // New track at opposite corner, completely different height
         // Distance: 10m away (1000cm >> 180cm limit)
@@ -420,11 +611,16 @@ mod tests {
 
     #[test]
     fn test_get_pending_info() {
-        let mut stitcher = Stitcher::new();
+        let mut stitcher = Stitcher::new(SystemClock::shared());
 
         let mut person = Person::new(TrackId(100));
         person.authorized = true;
-        stitcher.add_pending(person, Some([1.0, 1.0, 1.70]), Some("POS_1".to_string()));
+        stitcher.add_pending(
+            person,
+            Some([1.0, 1.0, 1.70]),
+            Some("POS_1".to_string()),
+            ZoneKind::Pos,
+        );
 
         let info = stitcher.get_pending_info();
         assert_eq!(info.len(), 1);
@@ -442,12 +638,17 @@ mod tests {
     fn test_spawn_hint_relaxed_height_in_pos_zone() {
         // POS zones allow ±15cm height difference (vs 10cm base)
         // People bend at checkout
-        let mut stitcher = Stitcher::new();
+        let mut stitcher = Stitcher::new(SystemClock::shared());
 
         let mut person = Person::new(TrackId(100));
         person.authorized = true;
         // Pending at POS zone
-        stitcher.add_pending(person, Some([1.0, 1.0, 1.70]), Some("POS_1".to_string()));
+        stitcher.add_pending(
+            person,
+            Some([1.0, 1.0, 1.70]),
+            Some("POS_1".to_string()),
+            ZoneKind::Pos,
+        );
 
         // New track same location but 12cm height difference
         // Would fail with 10cm threshold, passes with 15cm
@@ -460,10 +661,15 @@ mod tests {
     #[test]
     fn test_spawn_hint_height_still_rejected_if_too_far() {
         // Even in POS zone, >15cm height should be rejected
-        let mut stitcher = Stitcher::new();
+        let mut stitcher = Stitcher::new(SystemClock::shared());
 
         let person = Person::new(TrackId(100));
-        stitcher.add_pending(person, Some([1.0, 1.0, 1.70]), Some("POS_1".to_string()));
+        stitcher.add_pending(
+            person,
+            Some([1.0, 1.0, 1.70]),
+            Some("POS_1".to_string()),
+            ZoneKind::Pos,
+        );
 
         // 18cm height difference - too much even for POS zone
         let result = stitcher.find_match_with_context(Some([1.0, 1.0, 1.88]), Some("POS_1"), true);
@@ -474,11 +680,16 @@ mod tests {
     #[test]
     fn test_spawn_hint_uses_190cm_distance_for_same_zone() {
         // Spawn hint with same zone: 190cm distance allowed
-        let mut stitcher = Stitcher::new();
+        let mut stitcher = Stitcher::new(SystemClock::shared());
 
         let mut person = Person::new(TrackId(100));
         person.authorized = true;
-        stitcher.add_pending(person, Some([1.0, 1.0, 1.70]), Some("POS_1".to_string()));
+        stitcher.add_pending(
+            person,
+            Some([1.0, 1.0, 1.70]),
+            Some("POS_1".to_string()),
+            ZoneKind::Pos,
+        );
 
         // 185cm away - would pass normal 180cm but let's verify spawn-hint works
         let result = stitcher.find_match_with_context(Some([2.85, 1.0, 1.70]), Some("POS_1"), true);
@@ -490,10 +701,15 @@ mod tests {
     #[test]
     fn test_spawn_hint_rejects_beyond_190cm() {
         // Spawn hint limits distance to 190cm for same zone
-        let mut stitcher = Stitcher::new();
+        let mut stitcher = Stitcher::new(SystemClock::shared());
 
         let person = Person::new(TrackId(100));
-        stitcher.add_pending(person, Some([1.0, 1.0, 1.70]), Some("POS_1".to_string()));
+        stitcher.add_pending(
+            person,
+            Some([1.0, 1.0, 1.70]),
+            Some("POS_1".to_string()),
+            ZoneKind::Pos,
+        );
 
         // 195cm away - beyond 190cm limit for spawn-hint
         let result = stitcher.find_match_with_context(Some([2.95, 1.0, 1.70]), Some("POS_1"), true);
@@ -504,10 +720,15 @@ mod tests {
     #[test]
     fn test_spawn_hint_without_same_zone_uses_base_distance() {
         // spawn_hint but different zones: uses base 180cm
-        let mut stitcher = Stitcher::new();
+        let mut stitcher = Stitcher::new(SystemClock::shared());
 
         let person = Person::new(TrackId(100));
-        stitcher.add_pending(person, Some([1.0, 1.0, 1.70]), Some("POS_1".to_string()));
+        stitcher.add_pending(
+            person,
+            Some([1.0, 1.0, 1.70]),
+            Some("POS_1".to_string()),
+            ZoneKind::Pos,
+        );
 
         // 185cm away, spawn_hint but different zone - should fail (>180cm)
         let result = stitcher.find_match_with_context(Some([2.85, 1.0, 1.70]), Some("POS_2"), true);
@@ -518,10 +739,15 @@ mod tests {
     #[test]
     fn test_base_height_check_for_non_pos_zones() {
         // Non-POS zones use base 10cm height threshold
-        let mut stitcher = Stitcher::new();
+        let mut stitcher = Stitcher::new(SystemClock::shared());
 
         let person = Person::new(TrackId(100));
-        stitcher.add_pending(person, Some([1.0, 1.0, 1.70]), Some("STORE".to_string()));
+        stitcher.add_pending(
+            person,
+            Some([1.0, 1.0, 1.70]),
+            Some("STORE".to_string()),
+            ZoneKind::Store,
+        );
 
         // 12cm height diff - should fail for non-POS zone
         let result = stitcher.find_match_with_context(Some([1.0, 1.0, 1.82]), Some("STORE"), false);
@@ -532,11 +758,16 @@ mod tests {
     #[test]
     fn test_same_zone_without_spawn_hint_uses_300cm() {
         // Same zone without spawn hint: uses existing 300cm distance
-        let mut stitcher = Stitcher::new();
+        let mut stitcher = Stitcher::new(SystemClock::shared());
 
         let mut person = Person::new(TrackId(100));
         person.authorized = true;
-        stitcher.add_pending(person, Some([1.0, 1.0, 1.70]), Some("POS_1".to_string()));
+        stitcher.add_pending(
+            person,
+            Some([1.0, 1.0, 1.70]),
+            Some("POS_1".to_string()),
+            ZoneKind::Pos,
+        );
 
         // 250cm away, same zone, no spawn hint - should pass (300cm limit)
         let result = stitcher.find_match_with_context(Some([3.5, 1.0, 1.70]), Some("POS_1"), false);
@@ -544,4 +775,58 @@ mod tests {
         assert!(result.is_some(), "250cm should match for same zone without spawn_hint");
         assert_eq!(result.unwrap().distance_cm, 250);
     }
+
+    #[test]
+    fn test_pos_tolerance_follows_zone_kind_not_name() {
+        // A POS zone named without the "POS_" prefix still gets POS tolerances
+        let mut stitcher = Stitcher::new(SystemClock::shared());
+
+        let person = Person::new(TrackId(100));
+        stitcher.add_pending(
+            person,
+            Some([1.0, 1.0, 1.70]),
+            Some("KASSE_1".to_string()),
+            ZoneKind::Pos,
+        );
+
+        // 12cm height diff - only allowed for POS zones (limit 15cm)
+        let result =
+            stitcher.find_match_with_context(Some([1.0, 1.0, 1.82]), Some("KASSE_1"), true);
+
+        assert!(result.is_some(), "12cm should match in a POS-kind zone regardless of name");
+    }
+
+    #[test]
+    fn test_handover_picks_nearest_track_on_other_sensor() {
+        let stitcher = Stitcher::new(SystemClock::shared());
+        let deleted = TrackId::namespaced(0, 100);
+        let candidates = [
+            // Same sensor: never a handover
+            (TrackId::namespaced(0, 101), [5.0, 1.0, 1.70]),
+            (TrackId::namespaced(1, 7), [5.6, 1.0, 1.72]),
+            (TrackId::namespaced(1, 8), [5.2, 1.0, 1.70]),
+            // Close but a different height (child vs adult)
+            (TrackId::namespaced(1, 9), [5.0, 1.0, 1.20]),
+        ];
+
+        let handover = stitcher.find_handover(deleted, [5.0, 1.0, 1.70], candidates).unwrap();
+        assert_eq!(handover.track_id, TrackId::namespaced(1, 8));
+        assert_eq!(handover.distance_cm, 20);
+
+        // Nothing on the other sensor within 100cm
+        let far = [(TrackId::namespaced(1, 7), [6.5, 1.0, 1.70])];
+        assert!(stitcher.find_handover(deleted, [5.0, 1.0, 1.70], far).is_none());
+    }
+
+    #[test]
+    fn test_live_positions_expire() {
+        let live = LivePositions::default();
+        let now = Instant::now();
+        live.update(TrackId(1), [1.0, 1.0, 1.7], now);
+        live.update(TrackId(2), [2.0, 1.0, 1.7], now - Duration::from_secs(5));
+
+        assert_eq!(live.recent(Duration::from_secs(1), now), vec![(TrackId(1), [1.0, 1.0, 1.7])]);
+        live.remove(TrackId(1));
+        assert!(live.recent(Duration::from_secs(10), now).is_empty());
+    }
 }
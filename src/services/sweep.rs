@@ -0,0 +1,356 @@
+//! Parameter sweep: one capture replayed under a grid of tuning parameters
+//!
+//! The grid lists candidate values for `min_dwell_ms`, `exit_grace_ms`, the
+//! `[stitcher]` thresholds and `[exit_detection]` rectangles; every
+//! combination is replayed (see `replay`) and scored on ACC matching, gate
+//! opens, blocked customers, lost journeys and stitches.
+
+use crate::domain::journey::{Journey, JourneyOutcome};
+use crate::infra::config::{Config, ExitDetectionConfig, StitcherConfig};
+use crate::services::replay::{self, Capture, ReplayOutcome};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::path::Path;
+use tracing::info;
+
+/// Candidate values per parameter (`--grid` TOML)
+///
+/// An empty list keeps the value from the site config.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SweepGrid {
+    pub min_dwell_ms: Vec<u64>,
+    pub exit_grace_ms: Vec<u64>,
+    pub stitcher: StitcherGrid,
+    /// Exit rectangles (`[[exit_detection]]`)
+    pub exit_detection: Vec<ExitDetectionConfig>,
+}
+
+/// Candidate `[stitcher]` thresholds
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StitcherGrid {
+    pub max_time_ms: Vec<u64>,
+    pub max_time_pos_zone_ms: Vec<u64>,
+    pub max_distance_cm: Vec<f64>,
+    pub max_distance_same_zone_cm: Vec<f64>,
+    pub max_height_diff_cm: Vec<f64>,
+    pub max_height_diff_pos_cm: Vec<f64>,
+}
+
+/// One parameter set of a sweep
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SweepParams {
+    pub min_dwell_ms: u64,
+    pub exit_grace_ms: u64,
+    pub stitcher: StitcherConfig,
+    pub exit_detection: ExitDetectionConfig,
+}
+
+impl SweepParams {
+    /// The parameters `config` runs with
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            min_dwell_ms: config.min_dwell_ms(),
+            exit_grace_ms: config.pos_exit_grace_ms(),
+            stitcher: config.stitcher().clone(),
+            exit_detection: config.exit_detection().clone(),
+        }
+    }
+
+    /// `config` with these parameters
+    pub fn apply(&self, config: &Config) -> Config {
+        config
+            .clone()
+            .with_min_dwell_ms(self.min_dwell_ms)
+            .with_pos_exit_grace_ms(self.exit_grace_ms)
+            .with_stitcher(self.stitcher.clone())
+            .with_exit_detection(self.exit_detection.clone())
+    }
+}
+
+impl SweepGrid {
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("reading grid {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("parsing grid {}", path.display()))
+    }
+
+    /// Every combination of the grid values, starting from `config`'s parameters
+    pub fn expand(&self, config: &Config) -> Vec<SweepParams> {
+        let mut sets = vec![SweepParams::from_config(config)];
+        let stitcher = &self.stitcher;
+        vary(&mut sets, &self.min_dwell_ms, |p, v| p.min_dwell_ms = *v);
+        vary(&mut sets, &self.exit_grace_ms, |p, v| p.exit_grace_ms = *v);
+        vary(&mut sets, &stitcher.max_time_ms, |p, v| p.stitcher.max_time_ms = *v);
+        vary(&mut sets, &stitcher.max_time_pos_zone_ms, |p, v| {
+            p.stitcher.max_time_pos_zone_ms = *v
+        });
+        vary(&mut sets, &stitcher.max_distance_cm, |p, v| p.stitcher.max_distance_cm = *v);
+        vary(&mut sets, &stitcher.max_distance_same_zone_cm, |p, v| {
+            p.stitcher.max_distance_same_zone_cm = *v
+        });
+        vary(&mut sets, &stitcher.max_height_diff_cm, |p, v| p.stitcher.max_height_diff_cm = *v);
+        vary(&mut sets, &stitcher.max_height_diff_pos_cm, |p, v| {
+            p.stitcher.max_height_diff_pos_cm = *v
+        });
+        vary(&mut sets, &self.exit_detection, |p, v| p.exit_detection = v.clone());
+        sets
+    }
+}
+
+/// Replace `sets` by each set combined with each of `values` (no-op if empty)
+fn vary<T>(sets: &mut Vec<SweepParams>, values: &[T], set: impl Fn(&mut SweepParams, &T)) {
+    if values.is_empty() {
+        return;
+    }
+    *sets = sets
+        .iter()
+        .flat_map(|params| {
+            values.iter().map(|value| {
+                let mut params = params.clone();
+                set(&mut params, value);
+                params
+            })
+        })
+        .collect();
+}
+
+/// Scores of one parameter set
+#[derive(Debug, Clone, Serialize)]
+pub struct SweepResult {
+    pub params: SweepParams,
+    pub acc_events: u64,
+    pub acc_matched: u64,
+    /// Matched share of ACC events (0 without ACC events)
+    pub acc_match_rate: f64,
+    pub unmatched_accs: u64,
+    pub gate_opens: u64,
+    /// Authorized journeys that entered the gate zone but never got a gate open
+    pub blocked_authorized: u64,
+    pub journeys: u64,
+    /// Journeys whose track disappeared between ENTRY and EXIT
+    pub lost_journeys: u64,
+    pub stitches: u64,
+    /// Deleted tracks that expired without a stitch
+    pub stitches_expired: u64,
+    pub handovers: u64,
+}
+
+impl SweepResult {
+    fn score(params: SweepParams, outcome: &ReplayOutcome) -> Self {
+        let summary = outcome.metrics.report(0, 0);
+        let count =
+            |pred: fn(&Journey) -> bool| outcome.journeys().filter(|j| pred(j)).count() as u64;
+        let acc_events = summary.acc_events_total;
+        let acc_matched = summary.acc_matched_total;
+        Self {
+            params,
+            acc_events,
+            acc_matched,
+            acc_match_rate: if acc_events == 0 {
+                0.0
+            } else {
+                acc_matched as f64 / acc_events as f64
+            },
+            unmatched_accs: acc_events.saturating_sub(acc_matched),
+            gate_opens: outcome.gate_opens().count() as u64,
+            blocked_authorized: count(|j| {
+                j.authorized && j.gate_entry_at.is_some() && j.gate_cmd_at.is_none()
+            }),
+            journeys: count(|_| true),
+            lost_journeys: count(|j| j.outcome == JourneyOutcome::Lost),
+            stitches: summary.stitch_matched_total,
+            stitches_expired: summary.stitch_expired_total,
+            handovers: summary.stitch_handover_total,
+        }
+    }
+}
+
+/// Replay `capture` once per parameter set
+pub async fn sweep(config: &Config, capture: &Capture, sets: Vec<SweepParams>) -> Vec<SweepResult> {
+    let total = sets.len();
+    let mut results = Vec::with_capacity(total);
+    for (i, params) in sets.into_iter().enumerate() {
+        let outcome = replay::replay(&params.apply(config), capture).await;
+        let result = SweepResult::score(params, &outcome);
+        info!(
+            set = %(i + 1),
+            total = %total,
+            acc_match_rate = %format!("{:.3}", result.acc_match_rate),
+            gate_opens = %result.gate_opens,
+            "sweep_set_done"
+        );
+        results.push(result);
+    }
+    results
+}
+
+/// Table columns
+const COLUMNS: [&str; 14] = [
+    "set",
+    "min_dwell",
+    "exit_grace",
+    "stitch_ms",
+    "stitch_cm",
+    "height_cm",
+    "exit_rect",
+    "acc_match",
+    "unmatched",
+    "opens",
+    "blocked",
+    "lost",
+    "stitches",
+    "expired",
+];
+
+/// Plain-text table, one row per parameter set
+///
+/// Stitch columns are base/POS-zone (time, height) and base/same-zone (distance).
+pub fn format_table(results: &[SweepResult]) -> String {
+    let rows: Vec<[String; 14]> = results
+        .iter()
+        .enumerate()
+        .map(|(i, r)| {
+            let (p, s, e) = (&r.params, &r.params.stitcher, &r.params.exit_detection);
+            [
+                (i + 1).to_string(),
+                p.min_dwell_ms.to_string(),
+                p.exit_grace_ms.to_string(),
+                format!("{}/{}", s.max_time_ms, s.max_time_pos_zone_ms),
+                format!("{}/{}", s.max_distance_cm, s.max_distance_same_zone_cm),
+                format!("{}/{}", s.max_height_diff_cm, s.max_height_diff_pos_cm),
+                format!(
+                    "y{} x{}-{}",
+                    e.position_threshold_y_m,
+                    e.position_threshold_x_min_m,
+                    e.position_threshold_x_max_m
+                ),
+                format!("{:.1}%", r.acc_match_rate * 100.0),
+                r.unmatched_accs.to_string(),
+                r.gate_opens.to_string(),
+                r.blocked_authorized.to_string(),
+                r.lost_journeys.to_string(),
+                r.stitches.to_string(),
+                r.stitches_expired.to_string(),
+            ]
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..COLUMNS.len())
+        .map(|c| rows.iter().map(|row| row[c].len()).fold(COLUMNS[c].len(), usize::max))
+        .collect();
+    let mut out = String::new();
+    let header = COLUMNS.map(String::from);
+    for row in std::iter::once(&header).chain(&rows) {
+        let cells: Vec<String> =
+            row.iter().zip(&widths).map(|(cell, &w)| format!("{cell:>w$}")).collect();
+        let _ = writeln!(out, "{}", cells.join("  "));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::types::DoorStatus;
+    use crate::services::replay::{CaptureInput, CaptureRecord};
+    use std::collections::HashMap;
+
+    /// 2026-01-05T12:00:00Z
+    const START_MS: u64 = 1767614400000;
+
+    fn xovis(offset_ms: u64, event: &str, geometry_id: Option<i32>) -> CaptureRecord {
+        let mut attributes = serde_json::json!({ "track_id": 100 });
+        if let Some(id) = geometry_id {
+            attributes["geometry_id"] = id.into();
+        }
+        let time = chrono::DateTime::from_timestamp_millis((START_MS + offset_ms) as i64).unwrap();
+        let payload = serde_json::json!({
+            "live_data": { "frames": [{
+                "time": time.to_rfc3339(),
+                "tracked_objects": [{ "track_id": 100, "type": "PERSON", "position": [1.0, 1.0, 1.7] }],
+                "events": [{ "type": event, "attributes": attributes }],
+            }]}
+        });
+        CaptureRecord {
+            ts_ms: START_MS + offset_ms,
+            input: CaptureInput::Mqtt { topic: "xovis/live".into(), payload: payload.to_string() },
+        }
+    }
+
+    /// A customer dwelling 3s at POS_1, paying and walking to the gate
+    fn capture() -> Capture {
+        let records = vec![
+            xovis(0, "TRACK_CREATE", None),
+            xovis(100, "ZONE_ENTRY", Some(1001)),
+            CaptureRecord {
+                ts_ms: START_MS + 2900,
+                input: CaptureInput::Acc { kiosk_ip: "127.0.0.1".into() },
+            },
+            xovis(3100, "ZONE_EXIT", Some(1001)),
+            CaptureRecord { ts_ms: START_MS + 4000, input: CaptureInput::Door(DoorStatus::Closed) },
+            xovis(5000, "ZONE_ENTRY", Some(1007)),
+            xovis(8000, "TRACK_DELETE", None),
+        ];
+        Capture { records, skipped: 0 }
+    }
+
+    #[test]
+    fn test_grid_expands_to_every_combination() {
+        let grid: SweepGrid = toml::from_str(
+            r#"
+            min_dwell_ms = [2000, 5000]
+            [stitcher]
+            max_distance_cm = [150.0, 180.0, 250.0]
+            [[exit_detection]]
+            position_threshold_y_m = 2.0
+            "#,
+        )
+        .unwrap();
+        let config = Config::default();
+        let sets = grid.expand(&config);
+
+        assert_eq!(sets.len(), 6);
+        assert_eq!(sets[0].min_dwell_ms, 2000);
+        assert_eq!(sets[0].stitcher.max_distance_cm, 150.0);
+        assert_eq!(sets[5].min_dwell_ms, 5000);
+        assert_eq!(sets[5].stitcher.max_distance_cm, 250.0);
+        // Values not in the grid come from the config
+        assert!(sets.iter().all(|p| p.exit_grace_ms == config.pos_exit_grace_ms()));
+        assert!(sets.iter().all(|p| p.stitcher.max_time_ms == config.stitcher().max_time_ms));
+        assert!(sets.iter().all(|p| p.exit_detection.position_threshold_y_m == 2.0));
+        assert!(sets.iter().all(|p| p.exit_detection.position_threshold_x_max_m
+            == config.exit_detection().position_threshold_x_max_m));
+    }
+
+    #[tokio::test]
+    async fn test_sweep_scores_each_parameter_set() {
+        let config = Config::default()
+            .with_acc_ip_to_pos(HashMap::from([("127.0.0.1".to_string(), "POS_1".to_string())]));
+        let grid = SweepGrid { min_dwell_ms: vec![2000, 5000], ..SweepGrid::default() };
+
+        let results = sweep(&config, &capture(), grid.expand(&config)).await;
+
+        assert_eq!(results.len(), 2);
+        let (short, long) = (&results[0], &results[1]);
+        assert_eq!(short.params.min_dwell_ms, 2000);
+        assert_eq!((short.acc_events, short.acc_matched, short.unmatched_accs), (1, 1, 0));
+        assert_eq!(short.acc_match_rate, 1.0);
+        assert_eq!(short.gate_opens, 1);
+        assert_eq!(short.journeys, 1);
+
+        // 3s at the POS is too short to be matched with a 5s minimum dwell
+        assert_eq!(long.params.min_dwell_ms, 5000);
+        assert_eq!((long.acc_matched, long.unmatched_accs), (0, 1));
+        assert_eq!(long.gate_opens, 0);
+
+        let table = format_table(&results);
+        assert_eq!(table.lines().count(), 3);
+        assert!(table.lines().nth(1).unwrap().contains("100.0%"));
+        let json = serde_json::to_value(&results).unwrap();
+        assert_eq!(json[1]["params"]["min_dwell_ms"], 5000);
+        assert_eq!(json[1]["unmatched_accs"], 1);
+    }
+}
@@ -4,31 +4,30 @@
 //! journey state, and triggering side effects (gate commands, etc.)
 
 use super::Tracker;
-use crate::domain::journey::{epoch_ms, JourneyEvent, JourneyEventType, JourneyOutcome};
-use crate::domain::types::{DoorStatus, GeometryId, ParsedEvent, Person, TrackId};
+use crate::domain::journey::{JourneyEvent, JourneyEventType, JourneyOutcome};
+use crate::domain::types::{
+    Direction, DoorStatus, GeometryId, ParsedEvent, Person, TrackId, ZoneKind,
+};
+use crate::infra::config::DegradedPolicy;
 use crate::infra::metrics::{GATE_STATE_CLOSED, GATE_STATE_MOVING, GATE_STATE_OPEN};
 use crate::io::{
     AccDebugPending, AccDebugTrack, AccEventPayload, GateStatePayload, TrackEventPayload,
     ZoneEventPayload,
 };
+use crate::services::gate::GateCommand;
 use crate::services::gate_worker::GateCmd;
-use crate::services::stitcher::StitchMatch;
+use crate::services::stitcher::{HandoverMatch, StitchMatch};
 use std::sync::Arc;
 use std::time::Instant;
 use tracing::{debug, info, warn};
 
-/// Xovis GROUP track bit - track IDs with this bit set are group aggregates, not individuals
-const XOVIS_GROUP_BIT: i64 = 0x80000000;
-
-/// Check if a track ID represents a Xovis GROUP aggregate (not an individual person)
-///
-/// Retained for future metrics/logging use. Group tracks now flow through all handlers
-/// like regular tracks (can accumulate dwell, be authorized, and trigger gate opens).
-#[inline]
-#[allow(dead_code)]
-fn is_group_track(track_id: TrackId) -> bool {
-    track_id.0 & XOVIS_GROUP_BIT != 0
-}
+/// How long a gate command started by a virtual trigger covers the following
+/// GATE zone entry. A later first entry (person turned back after the trigger)
+/// gets its own open.
+const GATE_TRIGGER_COVER_MS: u64 = 5000;
+
+/// Live positions older than this are not handover candidates
+const HANDOVER_MAX_POSITION_AGE: std::time::Duration = std::time::Duration::from_millis(1000);
 
 impl Tracker {
     /// Handle a new track being created by the sensor
@@ -39,7 +38,7 @@ impl Tracker {
     /// 3. Create a fresh new journey
     pub(crate) fn handle_track_create(&mut self, event: &ParsedEvent) {
         let track_id = event.track_id;
-        let ts = epoch_ms();
+        let ts = self.clock.epoch_ms();
 
         // Determine if this looks like a "spawn" (re-detection)
         // If the track appears in a zone (geometry_id present), it might be spawned
@@ -47,11 +46,12 @@ impl Tracker {
         // relaxed height matching here for all track creates to help catch re-detections
         let current_zone: Option<Arc<str>> =
             event.geometry_id.map(|gid| self.config.zone_name(gid));
-        let spawn_hint = current_zone.as_deref().is_some_and(|z| z.starts_with("POS_"));
+        let current_kind = event.geometry_id.map(|gid| self.config.zone_kind(gid));
+        let spawn_hint = current_kind == Some(ZoneKind::Pos);
 
         // Fresh store entry: track appears in STORE zone = new customer from store side.
         // Don't stitch these to pending tracks - they're starting a fresh journey.
-        let is_fresh_store_entry = current_zone.as_deref() == Some("STORE");
+        let is_fresh_store_entry = current_kind == Some(ZoneKind::Store);
 
         // Try to find a stitch candidate with spawn-hint context
         // When spawn_hint is true and pending was in POS zone, uses:
@@ -82,6 +82,7 @@ impl Tracker {
             let old_has_zone_events = person.has_zone_events;
             person.track_id = track_id;
             person.last_position = event.position;
+            person.last_position_at = event.position.map(|_| ts);
             // Update max_y from new position if higher
             if let Some(pos) = event.position {
                 if pos[1] as f32 > person.max_y {
@@ -126,6 +127,9 @@ impl Tracker {
 
             // Stitch in journey manager (handles event recording)
             self.journey_manager.stitch_journey(old_track_id, track_id, time_ms, distance_cm);
+            if let Some(ref mut shadow) = self.shadow {
+                shadow.carry_over(old_track_id, track_id);
+            }
 
             if let Some(journey) = self.journey_manager.get_any(track_id) {
                 if journey.authorized {
@@ -142,6 +146,7 @@ impl Tracker {
             debug!(track_id = %track_id, reentry = %reentry_match.is_some(), "track_created");
             let mut person = Person::new(track_id);
             person.last_position = event.position;
+            person.last_position_at = event.position.map(|_| ts);
             // Update max_y from initial position
             if let Some(pos) = event.position {
                 if pos[1] as f32 > person.max_y {
@@ -209,22 +214,31 @@ impl Tracker {
     /// If no stitch occurs within the time window, the journey will be finalized.
     pub(crate) fn handle_track_delete(&mut self, event: &ParsedEvent) {
         let track_id = event.track_id;
-        let ts = epoch_ms();
+        let ts = self.clock.epoch_ms();
 
         if let Some(mut person) = self.persons.remove(&track_id) {
             // Update last position from event if available
             if let Some(pos) = event.position {
                 person.last_position = Some(pos);
+                person.last_position_at = Some(ts);
                 // Update max_y if this position is higher
                 if pos[1] as f32 > person.max_y {
                     person.max_y = pos[1] as f32;
                 }
             }
 
+            // Multi-sensor: another sensor may already track this person in the overlap
+            if let Some(handover) = self.find_sensor_handover(&person) {
+                self.hand_over(person, handover, ts);
+                return;
+            }
+
             let last_zone: String = person
                 .current_zone
                 .map(|id| self.config.zone_name(id).to_string())
                 .unwrap_or_default();
+            let last_zone_kind =
+                person.current_zone.map_or(ZoneKind::Other, |id| self.config.zone_kind(id));
 
             let journey_dwell = self.journey_manager.get_dwell(track_id);
 
@@ -283,7 +297,7 @@ impl Tracker {
             // The reentry_detector handles proper re-entry matching by height for store returns.
             if outcome == JourneyOutcome::Lost {
                 let last_zone_name = if last_zone.is_empty() { None } else { Some(last_zone) };
-                self.stitcher.add_pending(person, event.position, last_zone_name);
+                self.stitcher.add_pending(person, event.position, last_zone_name, last_zone_kind);
             } else {
                 debug!(
                     track_id = %track_id,
@@ -294,6 +308,64 @@ impl Tracker {
         }
     }
 
+    /// Find another sensor's live track continuing `person` (multi-sensor only)
+    fn find_sensor_handover(&self, person: &Person) -> Option<HandoverMatch> {
+        let live_positions = self.live_positions.as_ref()?;
+        let position = person.last_position?;
+        let candidates = live_positions
+            .recent(HANDOVER_MAX_POSITION_AGE, self.clock.now())
+            .into_iter()
+            .filter(|(id, _)| self.persons.contains_key(id));
+        self.stitcher.find_handover(person.track_id, position, candidates)
+    }
+
+    /// Continue a deleted track's person and journey on another sensor's live track
+    fn hand_over(&mut self, old: Person, handover: HandoverMatch, ts: u64) {
+        let old_track_id = old.track_id;
+        let track_id = handover.track_id;
+
+        self.journey_manager.hand_over(old_track_id, track_id, handover.distance_cm);
+        // Open POS sessions follow the track so an ACC after the handover finds the payer
+        let now = self.clock.now();
+        self.pos_occupancy.hand_over(old_track_id, track_id, now);
+        if let Some(ref mut shadow) = self.shadow {
+            shadow.hand_over(old_track_id, track_id, now);
+        }
+        let journey_authorized =
+            self.journey_manager.get(track_id).map(|j| j.authorized).unwrap_or(false);
+        let dwell_ms = self.journey_manager.get_dwell(track_id);
+
+        let Some(person) = self.persons.get_mut(&track_id) else {
+            return;
+        };
+        person.authorized |= old.authorized || journey_authorized;
+        person.has_zone_events |= old.has_zone_events;
+        person.max_y = person.max_y.max(old.max_y);
+
+        info!(
+            old_track_id = %old_track_id,
+            new_track_id = %track_id,
+            authorized = %person.authorized,
+            distance_cm = %handover.distance_cm,
+            "track_handed_over"
+        );
+
+        if let Some(ref sender) = self.egress_sender {
+            sender.send_track_event(TrackEventPayload {
+                site: None,
+                ts,
+                t: "handover".to_string(),
+                tid: track_id.0,
+                prev_tid: Some(old_track_id.0),
+                auth: person.authorized,
+                dwell_ms,
+                stitch_dist_cm: Some(handover.distance_cm as u64),
+                stitch_time_ms: Some(0),
+                parent_jid: None,
+            });
+        }
+    }
+
     /// Handle a person entering a zone
     ///
     /// Special handling for:
@@ -303,12 +375,14 @@ impl Tracker {
         let track_id = event.track_id;
         let geometry_id = event.geometry_id.unwrap_or(GeometryId(0));
         let zone = self.config.zone_name(geometry_id);
-        let ts = epoch_ms();
+        let kind = self.config.zone_kind(geometry_id);
+        let ts = self.clock.epoch_ms();
 
         debug!(
             track_id = %track_id,
             zone = %zone,
             event_time = %event.event_time,
+            derived = %event.derived,
             "zone_entry"
         );
 
@@ -317,14 +391,17 @@ impl Tracker {
         person.current_zone = Some(geometry_id);
         // Mark that this person has received zone events
         person.has_zone_events = true;
-        let journey_authorized =
-            self.journey_manager.get_any(track_id).map(|j| j.authorized).unwrap_or(false);
-        let authorized = person.authorized || journey_authorized;
+        if let Some(pos) = event.position {
+            person.last_position = Some(pos);
+            person.last_position_at = Some(ts);
+        }
 
         // Add to journey manager
         self.journey_manager.add_event(
             track_id,
-            JourneyEvent::new(JourneyEventType::ZoneEntry, ts).with_zone(&zone),
+            JourneyEvent::new(JourneyEventType::ZoneEntry, ts)
+                .with_zone(&zone)
+                .with_derived(event.derived),
         );
 
         let journey_dwell = self.journey_manager.get_dwell(track_id);
@@ -344,31 +421,53 @@ impl Tracker {
             });
         }
 
-        if self.config.is_pos_zone(geometry_id.0) || self.config.is_dwell_zone(geometry_id.0) {
+        if kind.tracks_dwell() {
             // Record POS/DWELL entry in per-zone occupancy state
-            self.pos_occupancy.record_entry(&zone, track_id, event.received_at);
+            self.pos_occupancy.record_entry(&zone, track_id, self.clock.now());
+            if let Some(ref mut shadow) = self.shadow {
+                shadow.zone_entry(&zone, track_id, self.clock.now());
+            }
             // Update POS occupancy metric (for both POS and DWELL zones)
             self.metrics.pos_zone_enter(geometry_id.0);
-        } else if geometry_id == self.config.gate_zone() {
+        } else if kind == ZoneKind::Gate && !event.derived {
+            // Only sensor-reported GATE entries decide on the gate (the zone
+            // engine does not derive them)
+            let authorized = self.resolve_gate_authorization(event, ts);
             // Gate zone - check authorization and whether gate can open
             // "Second chance" logic: allow up to 2 gate opens per journey
             // - First open: always allowed if authorized
             // - Second open: only if they exited gate zone without exiting store
-            let (gate_open_count, gate_zone_exited) = self
+            let (gate_open_count, gate_zone_exited, trigger_open_expired) = self
                 .journey_manager
                 .get_any(track_id)
-                .map(|j| (j.gate_open_count, j.gate_zone_exited))
-                .unwrap_or((0, false));
-
-            // First chance (count=0) or second chance (count=1 and exited gate zone then returned)
-            let can_open = gate_open_count == 0 || (gate_open_count == 1 && gate_zone_exited);
+                .map(|j| {
+                    let expired = j.gate_entry_at.is_none()
+                        && j.gate_trigger_at.is_some()
+                        && j.gate_cmd_at
+                            .is_some_and(|at| ts.saturating_sub(at) > GATE_TRIGGER_COVER_MS);
+                    (j.gate_open_count, j.gate_zone_exited, expired)
+                })
+                .unwrap_or((0, false, false));
+            self.record_gate_entry(track_id, ts);
+
+            // First chance (count=0) or second chance (count=1 and exited gate zone then returned).
+            // An open started by a virtual trigger that this entry came too late for does not
+            // use up the first chance.
+            let can_open = gate_open_count == 0
+                || (gate_open_count == 1 && (gate_zone_exited || trigger_open_expired));
+            // An open already started by a virtual trigger was compared there
+            if can_open {
+                self.compare_shadow(track_id, "gate_entry", authorized);
+            }
 
             if authorized && can_open {
-                self.send_gate_open_command(track_id, ts, "tracker", event.received_at);
-                // Update gate_open_count and reset gate_zone_exited
-                if let Some(journey) = self.journey_manager.get_mut(track_id) {
-                    journey.gate_open_count += 1;
-                    journey.gate_zone_exited = false;
+                // Update gate_open_count and reset gate_zone_exited; a suppressed
+                // or dropped command keeps the chance for a later entry
+                if self.send_gate_open_command(track_id, ts, "tracker", event.received_at) {
+                    if let Some(journey) = self.journey_manager.get_mut(track_id) {
+                        journey.gate_open_count += 1;
+                        journey.gate_zone_exited = false;
+                    }
                 }
             } else if !authorized {
                 // Emit gate blocked event for TUI visibility
@@ -403,12 +502,14 @@ impl Tracker {
         let track_id = event.track_id;
         let geometry_id = event.geometry_id.unwrap_or(GeometryId(0));
         let zone = self.config.zone_name(geometry_id);
-        let ts = epoch_ms();
+        let kind = self.config.zone_kind(geometry_id);
+        let ts = self.clock.epoch_ms();
 
         debug!(
             track_id = %track_id,
             zone = %zone,
             event_time = %event.event_time,
+            derived = %event.derived,
             "zone_exit"
         );
 
@@ -420,11 +521,13 @@ impl Tracker {
         person.has_zone_events = true;
 
         // Calculate dwell time if exiting a POS or DWELL zone (from PosOccupancyState)
-        let is_pos = self.config.is_pos_zone(geometry_id.0);
-        let is_dwell = self.config.is_dwell_zone(geometry_id.0);
-        let zone_dwell_ms = if is_pos || is_dwell {
+        let is_dwell = kind == ZoneKind::Dwell;
+        let zone_dwell_ms = if kind.tracks_dwell() {
             // Record zone exit in per-zone occupancy state and get dwell
-            let dwell_result = self.pos_occupancy.record_exit(&zone, track_id, Instant::now());
+            let dwell_result = self.pos_occupancy.record_exit(&zone, track_id, self.clock.now());
+            if let Some(ref mut shadow) = self.shadow {
+                shadow.zone_exit(&zone, track_id, self.clock.now(), is_dwell);
+            }
             // Update POS occupancy metric (for both POS and DWELL zones)
             self.metrics.pos_zone_exit(geometry_id.0);
 
@@ -434,7 +537,8 @@ impl Tracker {
                     track_id,
                     JourneyEvent::new(JourneyEventType::ZoneExit, ts)
                         .with_zone(&zone)
-                        .with_extra(&format!("dwell={session_dwell_ms}")),
+                        .with_extra(&format!("dwell={session_dwell_ms}"))
+                        .with_derived(event.derived),
                 );
                 // Journey tracks total dwell across ALL zones
                 let journey_total = if let Some(journey) = self.journey_manager.get_mut(track_id) {
@@ -472,7 +576,9 @@ impl Tracker {
         } else {
             self.journey_manager.add_event(
                 track_id,
-                JourneyEvent::new(JourneyEventType::ZoneExit, ts).with_zone(&zone),
+                JourneyEvent::new(JourneyEventType::ZoneExit, ts)
+                    .with_zone(&zone)
+                    .with_derived(event.derived),
             );
             None
         };
@@ -496,7 +602,7 @@ impl Tracker {
 
         // Track gate zone exits for "second chance" logic
         // If they exit the gate zone without exiting the store, they get one more try
-        if geometry_id == self.config.gate_zone() {
+        if kind == ZoneKind::Gate {
             if let Some(journey) = self.journey_manager.get_mut(track_id) {
                 if journey.gate_open_count > 0 && !journey.gate_zone_exited {
                     journey.gate_zone_exited = true;
@@ -517,11 +623,12 @@ impl Tracker {
     /// Special handling for:
     /// - Entry line: marks journey as having crossed entry
     /// - Exit line (forward): completes the journey
-    pub(crate) fn handle_line_cross(&mut self, event: &ParsedEvent, direction: &str) {
+    pub(crate) fn handle_line_cross(&mut self, event: &ParsedEvent, direction: Direction) {
         let track_id = event.track_id;
         let geometry_id = event.geometry_id.unwrap_or(GeometryId(0));
         let line = self.config.zone_name(geometry_id);
-        let ts = epoch_ms();
+        let kind = self.config.zone_kind(geometry_id);
+        let ts = self.clock.epoch_ms();
 
         debug!(
             track_id = %track_id,
@@ -532,14 +639,11 @@ impl Tracker {
         );
 
         // Determine event type based on line
-        let event_type = if self.config.entry_line() == Some(geometry_id.0) {
-            JourneyEventType::EntryCross
-        } else if geometry_id.0 == self.config.exit_line() {
-            JourneyEventType::ExitCross
-        } else if self.config.approach_line() == Some(geometry_id.0) {
-            JourneyEventType::ApproachCross
-        } else {
-            JourneyEventType::LineCross
+        let event_type = match kind {
+            ZoneKind::Entry => JourneyEventType::EntryCross,
+            ZoneKind::Exit => JourneyEventType::ExitCross,
+            ZoneKind::Approach => JourneyEventType::ApproachCross,
+            _ => JourneyEventType::LineCross,
         };
 
         // Add line cross event to journey manager
@@ -550,12 +654,12 @@ impl Tracker {
 
         // Mark crossed_entry if this is the entry line (forward direction)
         // Backward crossing means person is returning to store
-        if self.config.entry_line() == Some(geometry_id.0) {
-            if direction == "forward" {
+        if kind == ZoneKind::Entry {
+            if direction == Direction::Forward {
                 if let Some(journey) = self.journey_manager.get_mut(track_id) {
                     journey.crossed_entry = true;
                 }
-            } else if direction == "backward" {
+            } else if direction == Direction::Backward {
                 debug!(
                     track_id = %track_id,
                     "entry_line_backward_returning_to_store"
@@ -568,7 +672,7 @@ impl Tracker {
         };
 
         // Journey complete if crossing exit line forward
-        if geometry_id.0 == self.config.exit_line() && direction == "forward" {
+        if kind == ZoneKind::Exit && direction == Direction::Forward {
             // Get journey info for logging
             let (gate_cmd_at, event_count, started_at, journey_dwell) = self
                 .journey_manager
@@ -576,7 +680,7 @@ impl Tracker {
                 .map(|j| (j.gate_cmd_at, j.events.len(), j.started_at, j.total_dwell_ms))
                 .unwrap_or((None, 0, 0, 0));
             let duration_ms =
-                if started_at > 0 { epoch_ms().saturating_sub(started_at) } else { 0 };
+                if started_at > 0 { self.clock.epoch_ms().saturating_sub(started_at) } else { 0 };
 
             info!(
                 track_id = %track_id,
@@ -606,6 +710,275 @@ impl Tracker {
         }
     }
 
+    /// Handle a track reaching a virtual pre-gate trigger
+    ///
+    /// Starts the gate open for authorized tracks on their first chance, ahead
+    /// of the sensor's GATE zone entry (which then finds the open already used).
+    /// The trigger time is kept on the journey to measure the lead over the entry.
+    pub(crate) fn handle_gate_trigger(&mut self, event: &ParsedEvent) {
+        let track_id = event.track_id;
+        let gate_zone = event.geometry_id.unwrap_or(self.config.gate_zone());
+        let zone = self.config.zone_name(gate_zone);
+        let ts = self.clock.epoch_ms();
+
+        if !self.persons.contains_key(&track_id) {
+            return;
+        }
+        let authorized = self.resolve_gate_authorization(event, ts);
+        let gate_open_count =
+            self.journey_manager.get_any(track_id).map(|j| j.gate_open_count).unwrap_or(0);
+        let opened = authorized && gate_open_count == 0;
+        if gate_open_count == 0 {
+            self.compare_shadow(track_id, "gate_trigger", opened);
+        }
+
+        info!(
+            track_id = %track_id,
+            zone = %zone,
+            authorized = %authorized,
+            opened = %opened,
+            "gate_trigger"
+        );
+        self.metrics.record_gate_trigger(opened);
+
+        if let Some(journey) = self.journey_manager.get_mut_any(track_id) {
+            journey.gate_trigger_at.get_or_insert(ts);
+        }
+        self.journey_manager.add_event(
+            track_id,
+            JourneyEvent::new(JourneyEventType::GateTrigger, ts).with_zone(&zone),
+        );
+
+        if opened && self.send_gate_open_command(track_id, ts, "virtual_trigger", event.received_at)
+        {
+            if let Some(journey) = self.journey_manager.get_mut(track_id) {
+                journey.gate_open_count += 1;
+                journey.gate_zone_exited = false;
+            }
+        }
+    }
+
+    /// Record the first GATE zone entry and the virtual trigger's lead over it
+    fn record_gate_entry(&mut self, track_id: TrackId, ts: u64) {
+        let Some(journey) = self.journey_manager.get_mut_any(track_id) else {
+            return;
+        };
+        if journey.gate_entry_at.is_some() {
+            return;
+        }
+        journey.gate_entry_at = Some(ts);
+        if let Some(trigger_at) = journey.gate_trigger_at {
+            let lead_ms = ts.saturating_sub(trigger_at);
+            debug!(track_id = %track_id, lead_ms = %lead_ms, "gate_trigger_lead");
+            self.metrics.record_gate_trigger_lead(lead_ms);
+        }
+    }
+
+    /// Whether a track at the gate is authorized (GATE zone entry, virtual trigger)
+    ///
+    /// Besides its own authorization, a track can take over an authorized
+    /// neighbour's (object type inheritance) or a paying group member's.
+    fn resolve_gate_authorization(&mut self, event: &ParsedEvent, ts: u64) -> bool {
+        let track_id = event.track_id;
+        let person_authorized = self.persons.get(&track_id).is_some_and(|p| p.authorized);
+        let journey_authorized =
+            self.journey_manager.get_any(track_id).is_some_and(|j| j.authorized);
+        person_authorized
+            || journey_authorized
+            || self.inherit_authorization(event, ts)
+            || self.inherit_group_authorization(track_id, ts)
+    }
+
+    /// Authorize a track whose group has an authorized member
+    ///
+    /// Covers members who joined the group after the ACC was extended to it.
+    /// Only with `[groups].acc_extends_to_group`.
+    fn inherit_group_authorization(&mut self, track_id: TrackId, ts: u64) -> bool {
+        if !self.config.groups().acc_extends_to_group {
+            return false;
+        }
+        let persons = &self.persons;
+        let Some((group, payer)) = self.groups.iter().find_map(|(&group, members)| {
+            if !members.contains(&track_id) {
+                return None;
+            }
+            members
+                .iter()
+                .find(|&&m| m != track_id && persons.get(&m).is_some_and(|p| p.authorized))
+                .map(|&payer| (group, payer))
+        }) else {
+            return false;
+        };
+
+        if let Some(person) = self.persons.get_mut(&track_id) {
+            person.authorized = true;
+        }
+        if let Some(journey) = self.journey_manager.get_mut_any(track_id) {
+            journey.authorized = true;
+        }
+        if let Some(ref mut shadow) = self.shadow {
+            shadow.copy_authorization(payer, track_id);
+        }
+        self.journey_manager.add_event(
+            track_id,
+            JourneyEvent::new(JourneyEventType::Acc, ts)
+                .with_extra(&format!("group={group},payer={payer}")),
+        );
+        self.metrics.record_acc_group_extension();
+        info!(track_id = %track_id, group = %group, payer = %payer, "gate_extended_to_group");
+        true
+    }
+
+    /// Object type policy: authorize a track of an `inherit_authorization` type
+    /// (e.g. CHILD) from the nearest authorized track within `inherit_radius_m`
+    ///
+    /// Only positions reported within `inherit_max_position_age_ms` are compared,
+    /// so an adult who already left does not pass on their authorization.
+    /// Returns true if the track is now authorized.
+    fn inherit_authorization(&mut self, event: &ParsedEvent, ts: u64) -> bool {
+        let track_id = event.track_id;
+        let object_types = self.config.object_types();
+        let Some(person) = self.persons.get(&track_id) else {
+            return false;
+        };
+        let obj_type = event.obj_type.as_ref().or(person.obj_type.as_ref());
+        if !obj_type.is_some_and(|t| object_types.inherits_authorization(t)) {
+            return false;
+        }
+        let max_age_ms = object_types.inherit_max_position_age_ms;
+        let recent = |p: &Person| {
+            p.last_position.filter(|_| {
+                p.last_position_at.is_some_and(|at| ts.saturating_sub(at) <= max_age_ms)
+            })
+        };
+        let Some(position) = event.position.or_else(|| recent(person)) else {
+            return false;
+        };
+
+        // Nearest authorized track that does not itself inherit
+        let from = self
+            .persons
+            .values()
+            .filter(|p| p.track_id != track_id && p.authorized)
+            .filter(|p| {
+                !p.obj_type.as_ref().is_some_and(|t| object_types.inherits_authorization(t))
+            })
+            .filter_map(|p| {
+                let pos = recent(p)?;
+                let distance = (pos[0] - position[0]).hypot(pos[1] - position[1]);
+                (distance <= object_types.inherit_radius_m).then_some((p.track_id, distance))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        let Some((from, distance)) = from else {
+            return false;
+        };
+
+        if let Some(person) = self.persons.get_mut(&track_id) {
+            person.authorized = true;
+        }
+        if let Some(journey) = self.journey_manager.get_mut_any(track_id) {
+            journey.authorized = true;
+        }
+        if let Some(ref mut shadow) = self.shadow {
+            shadow.copy_authorization(from, track_id);
+        }
+        self.journey_manager.add_event(
+            track_id,
+            JourneyEvent::new(JourneyEventType::Acc, ts).with_extra(&format!("inherit={from}")),
+        );
+        self.metrics.record_auth_inherited();
+        info!(
+            track_id = %track_id,
+            from = %from,
+            distance_cm = %((distance * 100.0) as u64),
+            "authorization_inherited"
+        );
+        true
+    }
+
+    /// Handle a track joining a Xovis GROUP track
+    ///
+    /// The group is kept on the journey and used to extend ACC authorization.
+    pub(crate) fn handle_group_join(&mut self, event: &ParsedEvent, group: TrackId) {
+        let track_id = event.track_id;
+        // Group tracks flow through the other handlers like regular tracks
+        // (dwell, authorization, gate opens); here they are only the group
+        if !group.is_group() || track_id.is_group() {
+            return;
+        }
+        let members = self.groups.entry(group).or_default();
+        if !members.contains(&track_id) {
+            members.push(track_id);
+        }
+        if let Some(journey) = self.journey_manager.get_mut_any(track_id) {
+            journey.group = Some(group);
+        }
+        debug!(track_id = %track_id, group = %group, "group_joined");
+    }
+
+    /// Handle a track leaving a Xovis GROUP track (the journey keeps the group)
+    pub(crate) fn handle_group_leave(&mut self, event: &ParsedEvent, group: TrackId) {
+        let Some(members) = self.groups.get_mut(&group) else {
+            return;
+        };
+        members.retain(|tid| *tid != event.track_id);
+        if members.is_empty() {
+            self.groups.remove(&group);
+        }
+        debug!(track_id = %event.track_id, group = %group, "group_left");
+    }
+
+    /// Add present members of the payers' groups to the authorized tracks
+    ///
+    /// Only with `[groups].acc_extends_to_group` (families paying once).
+    fn extend_acc_to_groups(&mut self, authorized: &mut Vec<TrackId>, pos_zone: &str, ts: u64) {
+        if !self.config.groups().acc_extends_to_group {
+            return;
+        }
+        let mut extended: Vec<(TrackId, TrackId, TrackId)> = Vec::new();
+        for &payer in authorized.iter() {
+            let Some((&group, members)) = self.groups.iter().find(|(_, m)| m.contains(&payer))
+            else {
+                continue;
+            };
+            for &member in members {
+                let known = authorized.contains(&member)
+                    || extended.iter().any(|(tid, _, _)| *tid == member);
+                if !known && self.persons.contains_key(&member) {
+                    extended.push((member, group, payer));
+                }
+            }
+        }
+
+        for (track_id, group, payer) in extended {
+            if let Some(journey) = self.journey_manager.get_mut_any(track_id) {
+                journey.acc_matched = true;
+            }
+            self.journey_manager.add_event(
+                track_id,
+                JourneyEvent::new(JourneyEventType::Acc, ts)
+                    .with_zone(pos_zone)
+                    .with_extra(&format!("group={group},payer={payer}")),
+            );
+            self.metrics.record_acc_group_extension();
+            info!(track_id = %track_id, group = %group, payer = %payer, "acc_extended_to_group");
+            authorized.push(track_id);
+        }
+    }
+
+    /// Handle frames lost across an MQTT reconnect (frame time, epoch ms)
+    ///
+    /// Journeys overlapping the gap may miss zone exits or line crossings and
+    /// are flagged for analysis.
+    pub(crate) fn handle_ingress_gap(&mut self, from_ms: u64, to_ms: u64) {
+        let flagged = self.journey_manager.flag_ingress_gap(from_ms, to_ms);
+        warn!(
+            gap_ms = %to_ms.saturating_sub(from_ms),
+            journeys = %flagged,
+            "journeys_overlap_ingress_gap"
+        );
+    }
+
     /// Handle door state change from RS485 monitor
     pub(crate) fn handle_door_state_change(&mut self, status: DoorStatus) {
         info!(door_status = %status.as_str(), "door_state_change");
@@ -621,7 +994,7 @@ impl Tracker {
         if let Some(ref sender) = self.egress_sender {
             info!(door_status = %status.as_str(), "door_state_publishing_to_egress");
             sender.send_gate_state(GateStatePayload::new(
-                epoch_ms(),
+                self.clock.epoch_ms(),
                 status.as_str(),
                 self.door_correlator.last_gate_cmd_track_id().map(|t| t.0),
                 "rs485",
@@ -640,12 +1013,14 @@ impl Tracker {
     /// It's mapped to a POS zone via the ip_to_pos config.
     /// Uses PosOccupancyState to find candidates with accumulated_dwell >= min_dwell.
     pub(crate) fn handle_acc_event(&mut self, ip: &str, received_at: Instant) {
-        let ts = epoch_ms();
-        let now = Instant::now();
+        let ts = self.clock.epoch_ms();
+        let now = self.clock.now();
+        let shadow_acc = self.shadow_acc_ip(ip);
 
         // Look up POS zone from IP - early return if unknown
         let Some(pos_zone) = self.acc_collector.pos_for_ip(ip).map(|s| s.to_string()) else {
             self.publish_unmatched_acc_event(ip, None, ts);
+            self.compare_shadow_acc(shadow_acc, &[]);
             return;
         };
 
@@ -661,12 +1036,13 @@ impl Tracker {
         if qualified.is_empty() {
             self.metrics.record_acc_event(false);
             self.publish_unmatched_acc_event(ip, Some(&pos_zone), ts);
+            self.compare_shadow_acc(shadow_acc, &[]);
             return;
         }
 
         // Primary is first (highest dwell among present, or highest dwell among recent exits)
         let primary = qualified[0].0;
-        let authorized_tracks: Vec<TrackId> = qualified.iter().map(|(tid, _)| *tid).collect();
+        let mut authorized_tracks: Vec<TrackId> = qualified.iter().map(|(tid, _)| *tid).collect();
 
         // Record ACC match on all authorized journeys
         for &(track_id, dwell_ms) in &qualified {
@@ -680,6 +1056,7 @@ impl Tracker {
                     .with_extra(&format!("kiosk={ip},count={},dwell={dwell_ms}", qualified.len())),
             );
         }
+        self.extend_acc_to_groups(&mut authorized_tracks, &pos_zone, ts);
 
         // Record ACC metric
         self.metrics.record_acc_event(true);
@@ -724,6 +1101,7 @@ impl Tracker {
         for &track_id in &authorized_tracks {
             self.open_gate_if_waiting(track_id, gate_zone, ts, received_at);
         }
+        self.compare_shadow_acc(shadow_acc, &authorized_tracks);
 
         info!(
             ip = %ip,
@@ -770,11 +1148,20 @@ impl Tracker {
             .and_then(|p| p.current_zone)
             .is_some_and(|z| z == gate_zone);
 
-        if !in_gate_zone {
-            return;
+        if in_gate_zone
+            && self.gate_can_open(track_id)
+            && self.send_gate_open_command(track_id, ts, "acc", received_at)
+        {
+            // Update gate_open_count and reset gate_zone_exited
+            if let Some(journey) = self.journey_manager.get_mut(track_id) {
+                journey.gate_open_count += 1;
+                journey.gate_zone_exited = false;
+            }
         }
+    }
 
-        // Check if gate can open using "second chance" logic
+    /// Check if the gate can open for a track using "second chance" logic
+    pub(crate) fn gate_can_open(&self, track_id: TrackId) -> bool {
         let (gate_open_count, gate_zone_exited) = self
             .journey_manager
             .get_any(track_id)
@@ -782,16 +1169,7 @@ impl Tracker {
             .unwrap_or((0, false));
 
         // First chance (count=0) or second chance (count=1 and exited gate zone then returned)
-        let can_open = gate_open_count == 0 || (gate_open_count == 1 && gate_zone_exited);
-
-        if can_open {
-            self.send_gate_open_command(track_id, ts, "acc", received_at);
-            // Update gate_open_count and reset gate_zone_exited
-            if let Some(journey) = self.journey_manager.get_mut(track_id) {
-                journey.gate_open_count += 1;
-                journey.gate_zone_exited = false;
-            }
-        }
+        gate_open_count == 0 || (gate_open_count == 1 && gate_zone_exited)
     }
 
     /// Publish an unmatched ACC event with debug info
@@ -854,8 +1232,9 @@ impl Tracker {
     /// Unlike handle_acc_event, this receives the POS zone name directly
     /// instead of looking it up from an IP address.
     pub(crate) fn handle_acc_event_simulated(&mut self, pos_zone: &str, received_at: Instant) {
-        let ts = epoch_ms();
-        let now = Instant::now();
+        let ts = self.clock.epoch_ms();
+        let now = self.clock.now();
+        let shadow_acc = self.shadow_acc(Some(pos_zone));
 
         // Get candidates sorted by: present first (dwell desc), then recent exits (dwell desc)
         let candidates = self.pos_occupancy.get_candidates(pos_zone, now);
@@ -869,12 +1248,13 @@ impl Tracker {
         if qualified.is_empty() {
             self.metrics.record_acc_event(false);
             self.publish_unmatched_acc_event("simulated", Some(pos_zone), ts);
+            self.compare_shadow_acc(shadow_acc, &[]);
             return;
         }
 
         // Primary is first (highest dwell among present, or highest dwell among recent exits)
         let primary = qualified[0].0;
-        let authorized_tracks: Vec<TrackId> = qualified.iter().map(|(tid, _)| *tid).collect();
+        let mut authorized_tracks: Vec<TrackId> = qualified.iter().map(|(tid, _)| *tid).collect();
 
         // Record ACC match on all authorized journeys
         for &(track_id, dwell_ms) in &qualified {
@@ -888,6 +1268,7 @@ impl Tracker {
                     .with_extra(&format!("simulated,count={},dwell={dwell_ms}", qualified.len())),
             );
         }
+        self.extend_acc_to_groups(&mut authorized_tracks, pos_zone, ts);
 
         // Record ACC metric
         self.metrics.record_acc_event(true);
@@ -914,6 +1295,7 @@ impl Tracker {
         for &track_id in &authorized_tracks {
             self.open_gate_if_waiting(track_id, gate_zone, ts, received_at);
         }
+        self.compare_shadow_acc(shadow_acc, &authorized_tracks);
 
         info!(
             pos = %pos_zone,
@@ -954,33 +1336,61 @@ impl Tracker {
     ///
     /// State/metrics are only updated on successful enqueue to avoid recording
     /// commands that were dropped due to channel full.
+    ///
+    /// Returns whether the command was enqueued; callers only use up a gate
+    /// open chance when it was (not when suppressed while degraded or dropped).
     fn send_gate_open_command(
         &mut self,
         track_id: TrackId,
         ts: u64,
         src: &str,
         received_at: Instant,
-    ) {
+    ) -> bool {
+        if self.is_degraded()
+            && self.config.sensor_watchdog().degraded_policy == DegradedPolicy::KeepLocked
+        {
+            self.metrics.record_gate_open_suppressed();
+            warn!(track_id = %track_id, src = %src, "gate_open_suppressed_degraded");
+            if let Some(ref sender) = self.egress_sender {
+                sender.send_gate_state(GateStatePayload::new(
+                    ts,
+                    "cmd_suppressed",
+                    Some(track_id.0),
+                    src,
+                ));
+            }
+            return false;
+        }
+
         // Enqueue command to worker - never blocks on network I/O
         let cmd = GateCmd { track_id, enqueued_at: Instant::now() };
+        let dry_run = self.gate.as_ref().is_some_and(|gate| gate.is_dry_run());
         match self.gate_cmd_tx.try_send(cmd) {
             Ok(()) => {
-                // Record E2E gate latency only on successful enqueue
-                let e2e_latency_us = received_at.elapsed().as_micros() as u64;
-                self.metrics.record_gate_latency(e2e_latency_us);
+                // Record E2E gate latency only on successful enqueue of a real command
+                let e2e_latency_us =
+                    self.clock.now().saturating_duration_since(received_at).as_micros() as u64;
+                if !dry_run {
+                    self.metrics.record_gate_latency(e2e_latency_us);
+                }
                 self.metrics.record_gate_command();
 
                 // Update journey state
                 if let Some(journey) = self.journey_manager.get_mut_any(track_id) {
                     journey.gate_cmd_at = Some(ts);
                 }
+                let extra = if dry_run {
+                    "dry_run".to_string()
+                } else {
+                    format!("e2e_us={e2e_latency_us}")
+                };
                 self.journey_manager.add_event(
                     track_id,
-                    JourneyEvent::new(JourneyEventType::GateCmd, ts)
-                        .with_extra(&format!("e2e_us={e2e_latency_us}")),
+                    JourneyEvent::new(JourneyEventType::GateCmd, ts).with_extra(&extra),
                 );
 
-                // Emit state for TUI and record for door correlation
+                // Emit state for TUI and record for door correlation (the
+                // door can't follow a dry run command)
                 if let Some(ref sender) = self.egress_sender {
                     sender.send_gate_state(GateStatePayload::new(
                         ts,
@@ -989,7 +1399,10 @@ impl Tracker {
                         src,
                     ));
                 }
-                self.door_correlator.record_gate_cmd(track_id);
+                if !dry_run {
+                    self.door_correlator.record_gate_cmd(track_id);
+                }
+                true
             }
             Err(e) => {
                 // Command dropped - gate won't open for this customer
@@ -1005,10 +1418,43 @@ impl Tracker {
                         src,
                     ));
                 }
+                false
             }
         }
     }
 
+    /// Whether the sensor watchdog reports a sensor offline
+    pub(crate) fn is_degraded(&self) -> bool {
+        self.degraded_rx.as_ref().is_some_and(|rx| *rx.borrow())
+    }
+
+    /// Pulse the gate open while degraded with the `free_exit` policy
+    ///
+    /// Called from the 1s tick. Pulses are not tied to a track (track_id 0).
+    pub(crate) fn tick_degraded_policy(&mut self) {
+        let watchdog = self.config.sensor_watchdog();
+        if watchdog.degraded_policy != DegradedPolicy::FreeExit || !self.is_degraded() {
+            return;
+        }
+        let ts = self.clock.epoch_ms();
+        if ts.saturating_sub(self.last_free_exit_pulse_ms) < watchdog.free_exit_interval_ms {
+            return;
+        }
+        self.last_free_exit_pulse_ms = ts;
+
+        let cmd = GateCmd { track_id: TrackId(0), enqueued_at: Instant::now() };
+        if let Err(e) = self.gate_cmd_tx.try_send(cmd) {
+            self.metrics.record_gate_cmd_dropped();
+            warn!(error = %e, "free_exit_pulse_dropped");
+            return;
+        }
+        self.metrics.record_free_exit_pulse();
+        info!("free_exit_pulse");
+        if let Some(ref sender) = self.egress_sender {
+            sender.send_gate_state(GateStatePayload::new(ts, "cmd_enqueued", None, "free_exit"));
+        }
+    }
+
     /// Determine the journey outcome when a track is deleted
     ///
     /// Priority:
@@ -1035,8 +1481,9 @@ impl Tracker {
             return (JourneyOutcome::Completed, false);
         }
 
-        // 2. Last zone was EXIT = gone
-        if journey.events.iter().rev().find_map(|e| e.z.as_deref()) == Some("EXIT") {
+        // 2. Last zone was an exit = gone
+        let last_zone = journey.events.iter().rev().find_map(|e| e.z.as_deref());
+        if last_zone.is_some_and(|z| self.config.zone_kind_by_name(z) == ZoneKind::Exit) {
             debug!(track_id = %track_id, "journey_completed_exit_zone");
             return (JourneyOutcome::Completed, false);
         }
@@ -1084,7 +1531,10 @@ impl Tracker {
 
         // 5. Only touched shallow zones (STORE/ENTRY) = never went deep into checkout
         let went_deep = journey.events.iter().filter_map(|e| e.z.as_deref()).any(|z| {
-            z.starts_with("POS_") || z.starts_with("GATE") || z == "APPROACH" || z == "EXIT"
+            matches!(
+                self.config.zone_kind_by_name(z),
+                ZoneKind::Pos | ZoneKind::Gate | ZoneKind::Approach | ZoneKind::Exit
+            )
         });
 
         if !went_deep {
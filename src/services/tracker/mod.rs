@@ -5,29 +5,41 @@
 //! - Journey lifecycle (creation, stitching, completion, egress)
 //! - Gate control (sending open commands when authorized)
 //! - Door correlation (matching gate commands to door opens)
+//! - State snapshots across restarts (`snapshot`)
+//! - Config hot reload (reloadable fields from `ConfigReloader`)
+//! - Shadow policy comparison (`shadow`)
 
 mod handlers;
+mod shadow;
+mod snapshot;
 #[cfg(test)]
 mod tests;
 
-use crate::domain::journey::Journey;
-use crate::domain::types::{DoorStatus, EventType, ParsedEvent, Person, TrackId};
+pub use snapshot::RestoredTracks;
+
+use crate::domain::journey::{Journey, JourneyOutcome};
+use crate::domain::types::{Direction, DoorStatus, EventType, ParsedEvent, Person, TrackId};
+use crate::infra::clock::SharedClock;
 use crate::infra::config::Config;
 use crate::infra::metrics::Metrics;
 use crate::io::EgressSender;
 use crate::services::acc_collector::AccCollector;
 use crate::services::door_correlator::DoorCorrelator;
+use crate::services::gate::GateController;
 use crate::services::gate_worker::GateCmd;
 use crate::services::journey_manager::JourneyManager;
 use crate::services::pos_occupancy::PosOccupancyState;
 use crate::services::reentry_detector::ReentryDetector;
-use crate::services::stitcher::Stitcher;
+use crate::services::shadow_policy::ShadowPolicy;
+use crate::services::stitcher::{LivePositions, Stitcher};
 use rustc_hash::FxHashMap;
+use smallvec::SmallVec;
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
 use tokio::time::{interval, Duration};
-use tracing::warn;
+use tracing::{info, warn};
 
 /// Central event processor for person tracking and journey management
 pub struct Tracker {
@@ -59,6 +71,30 @@ pub struct Tracker {
     pub(crate) door_rx: watch::Receiver<DoorStatus>,
     /// Last processed door status (to detect changes)
     pub(crate) last_door_status: DoorStatus,
+    /// Live track positions from ingest (multi-sensor handover only)
+    pub(crate) live_positions: Option<Arc<LivePositions>>,
+    /// Sensor watchdog state: true while any sensor is offline
+    pub(crate) degraded_rx: Option<watch::Receiver<bool>>,
+    /// Gate driven by the worker (dry run state of gate commands)
+    pub(crate) gate: Option<Arc<GateController>>,
+    /// When the last free-exit gate pulse was sent (epoch ms)
+    pub(crate) last_free_exit_pulse_ms: u64,
+    /// Members of Xovis GROUP tracks by group track ID (`[groups]`)
+    pub(crate) groups: FxHashMap<TrackId, SmallVec<[TrackId; 4]>>,
+    /// Reloaded configs from `ConfigReloader`
+    pub(crate) config_rx: Option<watch::Receiver<Config>>,
+    /// Shutdown signal: `run` finishes queued events and finalizes journeys when set
+    pub(crate) shutdown_rx: Option<watch::Receiver<bool>>,
+    /// Time source shared with the services above (system clock, or manual in tests and replay)
+    pub(crate) clock: SharedClock,
+    /// Candidate authorization policy compared with the live one (`[shadow]`)
+    pub(crate) shadow: Option<ShadowPolicy>,
+    /// Periodic snapshot write still running on the blocking pool (`[snapshot]`)
+    pub(crate) snapshot_write: Option<JoinHandle<()>>,
+    /// Restored tracks not yet seen again, marked by ingest and `process_event`
+    pub(crate) restored_tracks: Arc<RestoredTracks>,
+    /// When restored tracks still unseen are ended
+    pub(crate) restore_deadline: Option<Instant>,
 }
 
 impl Tracker {
@@ -71,6 +107,9 @@ impl Tracker {
     /// which handles file I/O asynchronously without blocking the tracker.
     ///
     /// The `door_rx` watch receiver provides lossless door state updates from RS485.
+    ///
+    /// All timing (dwell, stitch and re-entry windows, egress delay, journey
+    /// timestamps) reads `clock`.
     pub fn new(
         config: Config,
         gate_cmd_tx: mpsc::Sender<GateCmd>,
@@ -78,16 +117,20 @@ impl Tracker {
         metrics: Arc<Metrics>,
         egress_sender: Option<EgressSender>,
         door_rx: watch::Receiver<DoorStatus>,
+        clock: SharedClock,
     ) -> Self {
         let acc_collector = AccCollector::new(&config);
         let pos_occupancy =
             PosOccupancyState::new(config.pos_exit_grace_ms(), config.min_dwell_ms());
+        let mut stitcher = Stitcher::with_metrics(metrics.clone(), clock.clone());
+        stitcher.set_config(config.stitcher());
+        let shadow = config.shadow().enabled.then(|| ShadowPolicy::new(&config));
         Self {
             persons: FxHashMap::default(),
-            stitcher: Stitcher::with_metrics(metrics.clone()),
-            journey_manager: JourneyManager::new(),
-            door_correlator: DoorCorrelator::new(),
-            reentry_detector: ReentryDetector::new(),
+            stitcher,
+            journey_manager: JourneyManager::new(clock.clone()),
+            door_correlator: DoorCorrelator::new(clock.clone()),
+            reentry_detector: ReentryDetector::new(clock.clone()),
             pos_occupancy,
             acc_collector,
             config,
@@ -97,13 +140,71 @@ impl Tracker {
             egress_sender,
             door_rx,
             last_door_status: DoorStatus::Unknown,
+            live_positions: None,
+            degraded_rx: None,
+            gate: None,
+            last_free_exit_pulse_ms: 0,
+            groups: FxHashMap::default(),
+            config_rx: None,
+            shutdown_rx: None,
+            clock,
+            shadow,
+            snapshot_write: None,
+            restored_tracks: Arc::default(),
+            restore_deadline: None,
         }
     }
 
+    /// Apply `[sensor_watchdog].degraded_policy` while the watchdog reports a sensor offline
+    pub fn with_degraded_rx(mut self, degraded_rx: watch::Receiver<bool>) -> Self {
+        self.degraded_rx = Some(degraded_rx);
+        self
+    }
+
+    /// Tag gate commands enqueued while `gate` is in dry run
+    pub fn with_gate(mut self, gate: Arc<GateController>) -> Self {
+        self.gate = Some(gate);
+        self
+    }
+
+    /// Apply the reloadable fields of configs published by `ConfigReloader`
+    pub fn with_config_reload(mut self, config_rx: watch::Receiver<Config>) -> Self {
+        self.config_rx = Some(config_rx);
+        self
+    }
+
+    /// Stop `run` on the shutdown signal instead of waiting for the event channel to close
+    ///
+    /// Set the signal once ingress has stopped, so no in-flight event is cut off.
+    pub fn with_shutdown(mut self, shutdown_rx: watch::Receiver<bool>) -> Self {
+        self.shutdown_rx = Some(shutdown_rx);
+        self
+    }
+
+    /// Share the restored track set with ingest (before `restore_snapshot`)
+    pub fn with_restored_tracks(mut self, restored_tracks: Arc<RestoredTracks>) -> Self {
+        self.restored_tracks = restored_tracks;
+        self
+    }
+
+    /// Enable cross-sensor handover using live positions published by ingest
+    pub fn with_live_positions(mut self, live_positions: Arc<LivePositions>) -> Self {
+        self.live_positions = Some(live_positions);
+        self
+    }
+
     /// Start the tracker, consuming events from the channel
+    ///
+    /// Runs until the event channel closes or the shutdown signal is set, then
+    /// finishes queued events and finalizes journeys (see `shutdown`).
     pub async fn run(&mut self, mut event_rx: mpsc::Receiver<ParsedEvent>) {
         // Tick interval for journey egress (1 second as per requirements)
         let mut tick_interval = interval(Duration::from_secs(1));
+        let snapshots = self.config.snapshot().enabled;
+        let mut snapshot_interval =
+            interval(Duration::from_secs(self.config.snapshot().interval_secs.max(1)));
+        let mut shutdown_rx = self.shutdown_rx.take();
+        let mut config_rx = self.config_rx.take();
 
         loop {
             tokio::select! {
@@ -127,13 +228,89 @@ impl Tracker {
                 // Periodic tick for journey egress
                 _ = tick_interval.tick() => {
                     self.tick_and_egress();
+                    self.tick_degraded_policy();
+                    self.tick_shadow();
+                    self.tick_restored_tracks();
+                }
+                // Config hot reload
+                Some(config) = wait_config(&mut config_rx), if config_rx.is_some() => {
+                    self.apply_config(&config);
+                }
+                _ = wait_shutdown(&mut shutdown_rx), if shutdown_rx.is_some() => {
+                    info!("tracker_shutdown");
+                    break;
+                }
+                // Periodic state snapshot (`[snapshot]`)
+                _ = snapshot_interval.tick(), if snapshots => {
+                    self.spawn_snapshot_write();
                 }
             }
         }
+
+        self.shutdown(&mut event_rx).await;
+    }
+
+    /// Swap the reloadable fields of `config` into the running config
+    ///
+    /// Rebuilds the IP to POS mapping and updates the POS dwell and stitch
+    /// thresholds; persons, journeys, POS sessions and pending stitches are kept.
+    /// The shadow policy is started, updated or stopped with `[shadow]`.
+    pub fn apply_config(&mut self, config: &Config) {
+        self.config.apply_reloadable(config);
+        self.acc_collector = AccCollector::new(&self.config);
+        self.pos_occupancy
+            .set_thresholds(self.config.pos_exit_grace_ms(), self.config.min_dwell_ms());
+        self.stitcher.set_config(self.config.stitcher());
+        self.apply_shadow_config();
+        self.metrics.set_pos_zones(self.config.pos_zones());
+        info!(
+            min_dwell_ms = %self.config.min_dwell_ms(),
+            pos_zones = ?self.config.pos_zones(),
+            gate_zone = %self.config.gate_zone(),
+            "tracker_config_applied"
+        );
+    }
+
+    /// Ordered shutdown after ingress has stopped
+    ///
+    /// Processes events still queued, emits ended journeys without waiting for
+    /// the egress delay, then writes the final snapshot. Active journeys in a
+    /// written snapshot continue after the restart and are emitted once they
+    /// end; without one they are ended as `Interrupted` and emitted now.
+    async fn shutdown(&mut self, event_rx: &mut mpsc::Receiver<ParsedEvent>) {
+        event_rx.close();
+        let mut drained = 0usize;
+        while let Some(event) = event_rx.recv().await {
+            self.process_event(event);
+            drained += 1;
+        }
+
+        let ended = self.journey_manager.flush();
+        let ended_count = ended.len();
+        self.egress_all(ended).await;
+
+        self.finish_snapshot_write().await;
+        let snapshotted = self.config.snapshot().enabled && self.write_snapshot();
+        let (interrupted, snapshotted_count) = if snapshotted {
+            (0, self.journey_manager.active_count())
+        } else {
+            let interrupted = self.journey_manager.end_all(JourneyOutcome::Interrupted);
+            let remaining = self.journey_manager.flush();
+            self.egress_all(remaining).await;
+            (interrupted, 0)
+        };
+
+        info!(
+            drained_events = %drained,
+            ended_journeys = %ended_count,
+            interrupted_journeys = %interrupted,
+            snapshotted_journeys = %snapshotted_count,
+            "tracker_stopped"
+        );
     }
 
     /// Tick journey manager and send ready journeys to egress worker
-    fn tick_and_egress(&mut self) {
+    pub(crate) fn tick_and_egress(&mut self) {
         let ready_journeys = self.journey_manager.tick();
         for journey in ready_journeys {
             // Publish to MQTT (if enabled)
@@ -150,19 +327,39 @@ impl Tracker {
         }
     }
 
+    /// Send journeys to egress, waiting for queue space instead of dropping (shutdown)
+    async fn egress_all(&self, journeys: Vec<Journey>) {
+        for journey in journeys {
+            if let Some(ref sender) = self.egress_sender {
+                sender.send_journey(&journey);
+            }
+
+            self.metrics.record_journey_egress_received();
+            if let Err(e) = self.journey_tx.send(journey).await {
+                self.metrics.record_journey_egress_dropped();
+                warn!(jid = %e.0.jid, "journey_egress_closed");
+            }
+        }
+    }
+
     /// Process a single event, dispatching to the appropriate handler
     ///
     /// All handlers are synchronous - gate commands are enqueued to a worker task.
     pub fn process_event(&mut self, event: ParsedEvent) {
         let process_start = Instant::now();
+        self.restored_tracks.seen([event.track_id]);
 
         match event.event_type {
             EventType::TrackCreate => self.handle_track_create(&event),
             EventType::TrackDelete => self.handle_track_delete(&event),
             EventType::ZoneEntry => self.handle_zone_entry(&event),
             EventType::ZoneExit => self.handle_zone_exit(&event),
-            EventType::LineCrossForward => self.handle_line_cross(&event, "forward"),
-            EventType::LineCrossBackward => self.handle_line_cross(&event, "backward"),
+            EventType::LineCrossForward => self.handle_line_cross(&event, Direction::Forward),
+            EventType::LineCrossBackward => self.handle_line_cross(&event, Direction::Backward),
+            EventType::GateTrigger => self.handle_gate_trigger(&event),
+            EventType::GroupJoin(group) => self.handle_group_join(&event, group),
+            EventType::GroupLeave(group) => self.handle_group_leave(&event, group),
+            EventType::IngressGap(from_ms) => self.handle_ingress_gap(from_ms, event.event_time),
             EventType::AccEvent(ip) => self.handle_acc_event(&ip, event.received_at),
             EventType::AccEventSimulated(pos) => {
                 self.handle_acc_event_simulated(&pos, event.received_at)
@@ -170,6 +367,11 @@ impl Tracker {
             // Door state comes via watch channel, not event channel
             EventType::DoorStateChange(_) | EventType::Unknown(_) => {}
         }
+        if let Some(ref obj_type) = event.obj_type {
+            if let Some(person) = self.persons.get_mut(&event.track_id) {
+                person.obj_type = Some(obj_type.clone());
+            }
+        }
 
         let latency_us = process_start.elapsed().as_micros() as u64;
         self.metrics.record_event_processed(latency_us);
@@ -197,3 +399,25 @@ impl Tracker {
         self.journey_manager.tick()
     }
 }
+
+/// Next reloaded config (None if the reloader is gone)
+async fn wait_config(config_rx: &mut Option<watch::Receiver<Config>>) -> Option<Config> {
+    let rx = config_rx.as_mut()?;
+    match rx.changed().await {
+        Ok(()) => Some(rx.borrow_and_update().clone()),
+        Err(_) => {
+            *config_rx = None;
+            None
+        }
+    }
+}
+
+/// Resolves once the shutdown signal is set
+async fn wait_shutdown(shutdown_rx: &mut Option<watch::Receiver<bool>>) {
+    if let Some(rx) = shutdown_rx {
+        if rx.wait_for(|shutdown| *shutdown).await.is_ok() {
+            return;
+        }
+    }
+    std::future::pending().await
+}
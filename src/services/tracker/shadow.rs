@@ -0,0 +1,138 @@
+//! Shadow policy comparison for the Tracker (`[shadow]`)
+//!
+//! The handlers feed the shadow policy the same zone and ACC events as the
+//! live one. At each gate decision (GATE zone entry, virtual trigger, ACC for
+//! a track waiting in the GATE zone) both decisions are counted in the daily agreement
+//! metrics, and a difference is published to `[mqtt_egress].shadow_topic`.
+
+use super::Tracker;
+use crate::domain::types::TrackId;
+use crate::io::ShadowDiffPayload;
+use crate::services::shadow_policy::ShadowPolicy;
+use tracing::info;
+
+/// Tracks an ACC event could open the gate for, and the shadow's matches
+pub(crate) struct ShadowAcc {
+    /// Tracks in the GATE zone with a gate open left, before the live match
+    waiting: Vec<TrackId>,
+    /// Tracks the shadow policy authorized for this ACC
+    authorized: Vec<TrackId>,
+}
+
+impl Tracker {
+    /// Create, update or drop the shadow policy after a config change
+    pub(crate) fn apply_shadow_config(&mut self) {
+        if !self.config.shadow().enabled {
+            self.shadow = None;
+            return;
+        }
+        let shadow = match self.shadow {
+            Some(ref mut shadow) => {
+                shadow.apply_config(&self.config);
+                shadow
+            }
+            None => self.shadow.insert(ShadowPolicy::new(&self.config)),
+        };
+        info!(min_dwell_ms = %shadow.min_dwell_ms(), "shadow_policy_enabled");
+    }
+
+    /// Match an ACC event from `ip` under the shadow policy (before the live match)
+    pub(crate) fn shadow_acc_ip(&mut self, ip: &str) -> Option<ShadowAcc> {
+        let pos_zone = self.shadow.as_ref()?.pos_for_ip(ip).map(str::to_string);
+        self.shadow_acc(pos_zone.as_deref())
+    }
+
+    /// Match an ACC event at `pos_zone` under the shadow policy (before the live match)
+    ///
+    /// The shadow matches are extended to the payers' groups like the live ones.
+    pub(crate) fn shadow_acc(&mut self, pos_zone: Option<&str>) -> Option<ShadowAcc> {
+        self.shadow.as_ref()?;
+        let gate_zone = self.config.gate_zone();
+        let waiting: Vec<TrackId> = self
+            .persons
+            .values()
+            .filter(|p| p.current_zone == Some(gate_zone))
+            .map(|p| p.track_id)
+            .filter(|&tid| self.gate_can_open(tid))
+            .collect();
+
+        let now = self.clock.now();
+        let extends_to_group = self.config.groups().acc_extends_to_group;
+        let shadow = self.shadow.as_mut()?;
+        let mut authorized = pos_zone.map(|zone| shadow.acc(zone, now)).unwrap_or_default();
+        if extends_to_group {
+            let payers = authorized.clone();
+            for members in self.groups.values().filter(|m| m.iter().any(|t| payers.contains(t))) {
+                for &member in members {
+                    if !authorized.contains(&member) && self.persons.contains_key(&member) {
+                        shadow.authorize(member);
+                        authorized.push(member);
+                    }
+                }
+            }
+        }
+        Some(ShadowAcc { waiting, authorized })
+    }
+
+    /// Compare the ACC decisions for the tracks waiting at the gate
+    ///
+    /// `live` are the tracks the live policy authorized for the same event.
+    pub(crate) fn compare_shadow_acc(&self, acc: Option<ShadowAcc>, live: &[TrackId]) {
+        let Some(acc) = acc else {
+            return;
+        };
+        for track_id in acc.waiting {
+            if live.contains(&track_id) || acc.authorized.contains(&track_id) {
+                self.compare_shadow(track_id, "acc", live.contains(&track_id));
+            }
+        }
+    }
+
+    /// Count a gate decision under both policies and publish it if they differ
+    pub(crate) fn compare_shadow(&self, track_id: TrackId, point: &str, live_open: bool) {
+        let Some(ref shadow) = self.shadow else {
+            return;
+        };
+        let shadow_open = shadow.is_authorized(track_id);
+        let ts = self.clock.epoch_ms();
+        self.metrics.record_shadow_decision(ts, live_open, shadow_open);
+        if live_open == shadow_open {
+            return;
+        }
+
+        let decision = |open: bool| if open { "open" } else { "blocked" };
+        let dwell_ms = self.journey_manager.get_dwell(track_id);
+        let shadow_dwell_ms = shadow.dwell(track_id);
+        info!(
+            track_id = %track_id,
+            point = %point,
+            live = %decision(live_open),
+            shadow = %decision(shadow_open),
+            dwell_ms = %dwell_ms,
+            shadow_dwell_ms = %shadow_dwell_ms,
+            "shadow_policy_diff"
+        );
+        if let Some(ref sender) = self.egress_sender {
+            sender.send_shadow_diff(ShadowDiffPayload {
+                site: None,
+                ts,
+                tid: track_id.0,
+                point: point.to_string(),
+                live: decision(live_open).to_string(),
+                shadow: decision(shadow_open).to_string(),
+                dwell_ms,
+                shadow_dwell_ms,
+            });
+        }
+    }
+
+    /// Drop stale shadow tracks and roll the daily counters (1s tick)
+    pub(crate) fn tick_shadow(&mut self) {
+        let Some(ref mut shadow) = self.shadow else {
+            return;
+        };
+        let persons = &self.persons;
+        shadow.prune(|tid| persons.contains_key(&tid), self.clock.now());
+        self.metrics.roll_shadow_day(self.clock.epoch_ms());
+    }
+}
@@ -0,0 +1,244 @@
+//! Tracker state snapshots across restarts
+//!
+//! A restart wipes persons, journeys, POS occupancy and the stitch pool, so a
+//! customer who just paid would be blocked at the gate. With `[snapshot]`
+//! enabled that state is written to disk every `interval_secs` and on
+//! shutdown, and restored at startup if recent enough. Xovis keeps its track
+//! IDs while the gateway restarts, so restored persons continue on the
+//! sensor's next events.
+//!
+//! Instants are stored as ages at snapshot time and aged by the downtime on
+//! restore. Restored journeys carry `restored_at` ("restored" in egress).
+//!
+//! A track deleted during the downtime never gets its TRACK_DELETE. Ingest
+//! reports the restored tracks it sees in sensor frames (`RestoredTracks`);
+//! those not seen within `restore_timeout_secs` are ended as lost.
+
+use super::Tracker;
+use crate::domain::journey::JourneyOutcome;
+use crate::domain::types::{Person, TrackId};
+use crate::services::journey_manager::JourneysSnapshot;
+use crate::services::pos_occupancy::PosSessionSnapshot;
+use crate::services::stitcher::PendingTrackSnapshot;
+use anyhow::Context;
+use parking_lot::Mutex;
+use rustc_hash::FxHashSet;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Restored tracks not yet seen since the restore, shared with ingest
+#[derive(Debug, Default)]
+pub struct RestoredTracks {
+    unseen: Mutex<FxHashSet<TrackId>>,
+    /// Whether `unseen` is non-empty (skips the lock for every frame)
+    pending: AtomicBool,
+}
+
+impl RestoredTracks {
+    /// Record tracks present in a sensor frame or event
+    pub fn seen(&self, track_ids: impl IntoIterator<Item = TrackId>) {
+        if !self.pending.load(Ordering::Relaxed) {
+            return;
+        }
+        let mut unseen = self.unseen.lock();
+        for track_id in track_ids {
+            unseen.remove(&track_id);
+        }
+        self.pending.store(!unseen.is_empty(), Ordering::Relaxed);
+    }
+
+    fn expect(&self, track_ids: impl IntoIterator<Item = TrackId>) {
+        let mut unseen = self.unseen.lock();
+        unseen.extend(track_ids);
+        self.pending.store(!unseen.is_empty(), Ordering::Relaxed);
+    }
+
+    fn take_unseen(&self) -> Vec<TrackId> {
+        let mut unseen = self.unseen.lock();
+        self.pending.store(false, Ordering::Relaxed);
+        unseen.drain().collect()
+    }
+}
+
+/// Tracker state written to `[snapshot].path`
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct TrackerSnapshot {
+    /// When the snapshot was taken (epoch ms)
+    pub(crate) saved_at: u64,
+    persons: Vec<Person>,
+    journeys: JourneysSnapshot,
+    pos_occupancy: Vec<PosSessionSnapshot>,
+    pending_stitch: Vec<PendingTrackSnapshot>,
+}
+
+impl Tracker {
+    /// Capture the state that must survive a restart
+    pub(crate) fn snapshot(&self) -> TrackerSnapshot {
+        let now = self.clock.now();
+        TrackerSnapshot {
+            saved_at: self.clock.epoch_ms(),
+            persons: self.persons.values().cloned().collect(),
+            journeys: self.journey_manager.snapshot(now),
+            pos_occupancy: self.pos_occupancy.snapshot(now),
+            pending_stitch: self.stitcher.snapshot(now),
+        }
+    }
+
+    /// Restore a snapshot; tracks already seen since startup are kept
+    pub(crate) fn restore(&mut self, snapshot: TrackerSnapshot) {
+        let now = self.clock.now();
+        let restored_at = self.clock.epoch_ms();
+        let offline_ms = restored_at.saturating_sub(snapshot.saved_at);
+        let persons = snapshot.persons.len();
+        let authorized = snapshot.persons.iter().filter(|p| p.authorized).count();
+        let journeys = snapshot.journeys.active.len() + snapshot.journeys.pending_egress.len();
+
+        // The shadow policy is not snapshotted: it restarts from the live state
+        if let Some(ref mut shadow) = self.shadow {
+            for person in snapshot.persons.iter().filter(|p| p.authorized) {
+                shadow.authorize(person.track_id);
+            }
+            shadow.restore(snapshot.pos_occupancy.clone(), now, offline_ms);
+        }
+        let mut restored_tracks = Vec::with_capacity(snapshot.persons.len());
+        for person in snapshot.persons {
+            if let Entry::Vacant(entry) = self.persons.entry(person.track_id) {
+                restored_tracks.push(person.track_id);
+                entry.insert(person);
+            }
+        }
+        self.restored_tracks.expect(restored_tracks);
+        let timeout = Duration::from_secs(self.config.snapshot().restore_timeout_secs);
+        self.restore_deadline = Some(now + timeout);
+        self.journey_manager.restore(snapshot.journeys, now, restored_at);
+        self.pos_occupancy.restore(snapshot.pos_occupancy, now, offline_ms);
+        self.stitcher.restore(snapshot.pending_stitch, now, offline_ms);
+        self.metrics.set_active_tracks(self.active_tracks());
+        self.metrics.set_authorized_tracks(self.authorized_tracks());
+
+        info!(
+            persons = %persons,
+            authorized = %authorized,
+            journeys = %journeys,
+            offline_ms = %offline_ms,
+            "snapshot_restored"
+        );
+    }
+
+    /// End restored tracks that no sensor frame reported by the restore deadline (1s tick)
+    pub(crate) fn tick_restored_tracks(&mut self) {
+        if self.restore_deadline.is_none_or(|deadline| self.clock.now() < deadline) {
+            return;
+        }
+        self.restore_deadline = None;
+        let mut ended = 0usize;
+        for track_id in self.restored_tracks.take_unseen() {
+            if self.persons.remove(&track_id).is_none() {
+                continue;
+            }
+            self.journey_manager.end_journey(track_id, JourneyOutcome::Lost);
+            ended += 1;
+        }
+        if ended > 0 {
+            self.metrics.set_active_tracks(self.active_tracks());
+            self.metrics.set_authorized_tracks(self.authorized_tracks());
+            info!(tracks = %ended, "restored_tracks_lost");
+        }
+    }
+
+    /// Restore `[snapshot].path` if enabled, present and within `max_age_secs`
+    pub fn restore_snapshot(&mut self) {
+        let config = self.config.snapshot();
+        if !config.enabled {
+            return;
+        }
+        let path = config.path.clone();
+        match load(&path, config.max_age_secs * 1000, self.clock.epoch_ms()) {
+            Ok(Some(snapshot)) => self.restore(snapshot),
+            Ok(None) => {}
+            Err(e) => warn!(path = %path, error = %e, "snapshot_restore_failed"),
+        }
+    }
+
+    /// Write a snapshot without blocking the event loop (periodic)
+    ///
+    /// Skipped while the previous write is still running, so writes to the
+    /// shared temporary file never overlap.
+    pub(crate) fn spawn_snapshot_write(&mut self) {
+        if self.snapshot_write.as_ref().is_some_and(|w| !w.is_finished()) {
+            debug!("snapshot_write_skipped: previous write running");
+            return;
+        }
+        let path = self.config.snapshot().path.clone();
+        let json = match serde_json::to_vec(&self.snapshot()) {
+            Ok(json) => json,
+            Err(e) => {
+                warn!(error = %e, "snapshot_serialize_failed");
+                return;
+            }
+        };
+        self.snapshot_write = Some(tokio::task::spawn_blocking(move || {
+            if let Err(e) = write(&path, &json) {
+                warn!(path = %path, error = %e, "snapshot_write_failed");
+            }
+        }));
+    }
+
+    /// Wait for a running periodic write (before the final one on shutdown)
+    pub(crate) async fn finish_snapshot_write(&mut self) {
+        if let Some(write) = self.snapshot_write.take() {
+            if let Err(e) = write.await {
+                warn!(error = %e, "snapshot_write_failed");
+            }
+        }
+    }
+
+    /// Write a snapshot and wait for it (shutdown); returns whether it was written
+    pub(crate) fn write_snapshot(&self) -> bool {
+        let path = &self.config.snapshot().path;
+        let result = serde_json::to_vec(&self.snapshot())
+            .context("Failed to serialize snapshot")
+            .and_then(|json| write(path, &json));
+        match result {
+            Ok(()) => {
+                info!(path = %path, persons = %self.persons.len(), "snapshot_written");
+                true
+            }
+            Err(e) => {
+                warn!(path = %path, error = %e, "snapshot_write_failed");
+                false
+            }
+        }
+    }
+}
+
+/// Write via a temporary file so a crash never leaves a truncated snapshot
+fn write(path: &str, json: &[u8]) -> anyhow::Result<()> {
+    let tmp = format!("{path}.tmp");
+    std::fs::write(&tmp, json).with_context(|| format!("Failed to write {tmp}"))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to rename {tmp} to {path}"))
+}
+
+/// Read a snapshot; None if missing or older than `max_age_ms`
+pub(crate) fn load(
+    path: &str,
+    max_age_ms: u64,
+    now_ms: u64,
+) -> anyhow::Result<Option<TrackerSnapshot>> {
+    let json = match std::fs::read(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {path}")),
+    };
+    let snapshot: TrackerSnapshot =
+        serde_json::from_slice(&json).with_context(|| format!("Invalid snapshot {path}"))?;
+    let age_ms = now_ms.saturating_sub(snapshot.saved_at);
+    if age_ms > max_age_ms {
+        warn!(path = %path, age_ms = %age_ms, max_age_ms = %max_age_ms, "snapshot_too_old");
+        return Ok(None);
+    }
+    Ok(Some(snapshot))
+}
@@ -1,18 +1,25 @@
 //! Tests for the Tracker module
 
 use super::*;
-use crate::domain::journey::JourneyOutcome;
-use crate::domain::types::{EventType, GeometryId, TrackId};
-use crate::infra::config::Config;
+use crate::domain::journey::{JourneyEventType, JourneyOutcome};
+use crate::domain::types::{Direction, EventType, GeometryId, TrackId};
+use crate::infra::clock::{Clock, ManualClock};
+use crate::infra::config::{
+    Config, DegradedPolicy, GateMode, ObjectTypesConfig, ShadowConfig, SnapshotConfig,
+};
 use crate::infra::metrics::Metrics;
+use crate::io::egress_channel::EgressMessage;
 use crate::services::gate_worker::GateCmd;
+use crate::services::stitcher::LivePositions;
 use std::collections::HashMap;
 use tokio::time::Duration;
 
 /// Test harness that keeps channel receivers alive so `try_send` succeeds
+///
+/// The tracker runs on `clock`, which only moves when a test advances it.
 struct TestTracker {
     tracker: Tracker,
-    #[allow(dead_code)]
+    clock: Arc<ManualClock>,
     gate_cmd_rx: mpsc::Receiver<GateCmd>,
     #[allow(dead_code)]
     journey_rx: mpsc::Receiver<Journey>,
@@ -42,10 +49,15 @@ fn create_test_tracker_with_config(config: Config) -> TestTracker {
     let (journey_tx, journey_rx) = mpsc::channel::<Journey>(64);
     let (door_tx, door_rx) = watch::channel(DoorStatus::Unknown);
     let metrics = Arc::new(Metrics::new());
-    let tracker = Tracker::new(config, gate_cmd_tx, journey_tx, metrics, None, door_rx);
-    TestTracker { tracker, gate_cmd_rx, journey_rx, door_tx }
+    let clock = Arc::new(ManualClock::new(EVENT_TIME_MS));
+    let tracker =
+        Tracker::new(config, gate_cmd_tx, journey_tx, metrics, None, door_rx, clock.clone());
+    TestTracker { tracker, clock, gate_cmd_rx, journey_rx, door_tx }
 }
 
+/// Sensor frame time of test events, and the test clock's start
+const EVENT_TIME_MS: u64 = 1767617600000;
+
 fn millis(ms: u64) -> Duration {
     Duration::from_millis(ms)
 }
@@ -67,11 +79,20 @@ struct ParsedEventBuilder {
     track_id: i64,
     geometry_id: Option<i32>,
     position: Option<[f64; 3]>,
+    derived: bool,
+    obj_type: Option<&'static str>,
 }
 
 impl ParsedEventBuilder {
     fn new(event_type: EventType) -> Self {
-        Self { event_type, track_id: 0, geometry_id: None, position: None }
+        Self {
+            event_type,
+            track_id: 0,
+            geometry_id: None,
+            position: None,
+            derived: false,
+            obj_type: None,
+        }
     }
 
     fn with_track_id(mut self, track_id: i64) -> Self {
@@ -89,15 +110,27 @@ impl ParsedEventBuilder {
         self
     }
 
+    fn derived(mut self) -> Self {
+        self.derived = true;
+        self
+    }
+
+    fn with_obj_type(mut self, obj_type: &'static str) -> Self {
+        self.obj_type = Some(obj_type);
+        self
+    }
+
     fn build(self) -> ParsedEvent {
         ParsedEvent {
             event_type: self.event_type,
             track_id: TrackId(self.track_id),
             geometry_id: self.geometry_id.map(GeometryId),
             direction: None,
-            event_time: 1767617600000,
+            event_time: EVENT_TIME_MS,
             received_at: Instant::now(),
             position: self.position,
+            derived: self.derived,
+            obj_type: self.obj_type.map(Arc::from),
         }
     }
 }
@@ -147,7 +180,7 @@ async fn test_dwell_accumulation() {
     tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
 
     // Simulate time passing
-    tokio::time::sleep(millis(100)).await;
+    tracker.clock.advance(millis(100));
 
     // Exit POS zone
     tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1001)));
@@ -160,6 +193,32 @@ async fn test_dwell_accumulation() {
     assert!(!person.authorized);
 }
 
+#[tokio::test]
+async fn test_derived_zone_exit_closes_pos_dwell() {
+    let mut tracker = create_test_tracker();
+
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
+    tracker.clock.advance(millis(100));
+
+    // Sensor missed the exit; the zone engine synthesized it from positions
+    tracker.process_event(
+        ParsedEventBuilder::new(EventType::ZoneExit)
+            .with_track_id(100)
+            .with_geometry_id(1001)
+            .derived()
+            .build(),
+    );
+
+    let journey = tracker.journey_manager.get(TrackId(100)).unwrap();
+    assert!(journey.total_dwell_ms >= 100);
+    let exit = journey.events.last().unwrap();
+    assert_eq!(exit.t, JourneyEventType::ZoneExit);
+    assert!(exit.derived);
+    assert!(!journey.events.iter().any(|e| e.t == JourneyEventType::ZoneEntry && e.derived));
+    assert!(tracker.persons.get(&TrackId(100)).unwrap().current_zone.is_none());
+}
+
 #[tokio::test]
 async fn test_dwell_threshold_without_acc() {
     // Dwell alone no longer grants authorization - ACC match is required
@@ -168,7 +227,7 @@ async fn test_dwell_threshold_without_acc() {
 
     tracker.process_event(create_event(EventType::TrackCreate, 100, None));
     tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
-    tokio::time::sleep(millis(60)).await;
+    tracker.clock.advance(millis(60));
     tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1001)));
 
     // Dwell is now tracked in journey.total_dwell_ms (via PosOccupancyState)
@@ -190,7 +249,7 @@ async fn test_accumulated_dwell_across_zones() {
 
     // First POS zone visit (POS_1)
     tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
-    tokio::time::sleep(millis(60)).await;
+    tracker.clock.advance(millis(60));
     tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1001)));
 
     // Journey tracks total dwell across zones
@@ -201,7 +260,7 @@ async fn test_accumulated_dwell_across_zones() {
 
     // Second POS zone visit (POS_2)
     tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1002)));
-    tokio::time::sleep(millis(60)).await;
+    tracker.clock.advance(millis(60));
     tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1002)));
 
     // Journey tracks total dwell (for logging), but ACC matching uses per-zone dwell
@@ -218,14 +277,14 @@ async fn test_journey_complete_on_exit_line() {
 
     tracker.process_event(create_event(EventType::TrackCreate, 100, None));
     tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
-    tokio::time::sleep(millis(20)).await;
+    tracker.clock.advance(millis(20));
     tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1001)));
     tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1007)));
     assert_eq!(tracker.active_tracks(), 1);
 
     // Cross EXIT_1 line
     let mut exit_event = create_event(EventType::LineCrossForward, 100, Some(1006));
-    exit_event.direction = Some("forward".to_string());
+    exit_event.direction = Some(Direction::Forward);
     tracker.process_event(exit_event);
 
     // Person should be removed (journey complete)
@@ -244,7 +303,7 @@ async fn test_lost_in_exit_corridor_is_stitch_candidate() {
 
     // Enter POS zone for dwell
     tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
-    tokio::time::sleep(millis(20)).await;
+    tracker.clock.advance(millis(20));
     tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1001)));
 
     // Manually authorize (simulating ACC match)
@@ -255,7 +314,7 @@ async fn test_lost_in_exit_corridor_is_stitch_candidate() {
 
     // Cross APPROACH line forward
     let mut approach_event = create_event(EventType::LineCrossForward, 100, Some(1008));
-    approach_event.direction = Some("forward".to_string());
+    approach_event.direction = Some(Direction::Forward);
     tracker.process_event(approach_event);
 
     // Enter GATE zone
@@ -326,6 +385,77 @@ async fn test_stitch_transfers_state() {
     assert!(new_person.authorized);
 }
 
+#[tokio::test]
+async fn test_sensor_handover_continues_journey() {
+    let live = Arc::new(LivePositions::default());
+    let mut tracker = create_test_tracker();
+    tracker.live_positions = Some(live.clone());
+    let sensor_a = TrackId::namespaced(0, 100);
+    let sensor_b = TrackId::namespaced(1, 7);
+
+    tracker.process_event(create_event_with_pos(
+        EventType::TrackCreate,
+        sensor_a.0,
+        [4.0, 1.0, 1.70],
+    ));
+    tracker.persons.get_mut(&sensor_a).unwrap().authorized = true;
+
+    // Sensor B picks the person up in the overlap area
+    tracker.process_event(create_event_with_pos(
+        EventType::TrackCreate,
+        sensor_b.0,
+        [4.9, 1.0, 1.71],
+    ));
+    live.update(sensor_b, [5.1, 1.0, 1.71], tracker.clock.now());
+
+    // Sensor A loses the person at the edge of its view
+    tracker.process_event(create_event_with_pos(
+        EventType::TrackDelete,
+        sensor_a.0,
+        [5.0, 1.0, 1.70],
+    ));
+
+    assert_eq!(tracker.active_tracks(), 1);
+    assert!(tracker.persons.get(&sensor_b).unwrap().authorized);
+    let journey = tracker.journey_manager.get(sensor_b).unwrap();
+    assert_eq!(journey.tids.as_slice(), &[sensor_a, sensor_b]);
+}
+
+#[tokio::test]
+async fn test_acc_after_sensor_handover_finds_payer() {
+    let live = Arc::new(LivePositions::default());
+    let config = Config::default().with_min_dwell_ms(50).with_acc_ip_to_pos(acc_ip_mapping());
+    let mut tracker = create_test_tracker_with_config(config);
+    tracker.live_positions = Some(live.clone());
+    let sensor_a = TrackId::namespaced(0, 100);
+    let sensor_b = TrackId::namespaced(1, 7);
+
+    tracker.process_event(create_event_with_pos(
+        EventType::TrackCreate,
+        sensor_a.0,
+        [4.0, 1.0, 1.70],
+    ));
+    tracker.process_event(create_event(EventType::ZoneEntry, sensor_a.0, Some(1001)));
+    tracker.clock.advance(millis(100));
+    tracker.process_event(create_event(EventType::ZoneExit, sensor_a.0, Some(1001)));
+
+    tracker.process_event(create_event_with_pos(
+        EventType::TrackCreate,
+        sensor_b.0,
+        [4.9, 1.0, 1.71],
+    ));
+    live.update(sensor_b, [5.1, 1.0, 1.71], tracker.clock.now());
+    tracker.process_event(create_event_with_pos(
+        EventType::TrackDelete,
+        sensor_a.0,
+        [5.0, 1.0, 1.70],
+    ));
+
+    // The POS session moved with the journey, so the ACC matches the new track
+    send_acc_event(&mut tracker, "127.0.0.1");
+    assert!(is_authorized(&tracker, sensor_b.0));
+}
+
 #[tokio::test]
 async fn test_stitch_fails_too_late() {
     let mut tracker = create_test_tracker();
@@ -346,7 +476,7 @@ async fn test_stitch_fails_too_late() {
     assert_eq!(tracker.active_tracks(), 0);
 
     // Wait beyond stitch window (8s for POS zone, base is 4.5s)
-    tokio::time::sleep(millis(8100)).await;
+    tracker.clock.advance(millis(8100));
 
     // New track nearby - should NOT stitch (too late)
     tracker.process_event(create_event_with_pos(EventType::TrackCreate, 200, [1.05, 1.0, 1.71]));
@@ -430,7 +560,7 @@ async fn test_gate_opens_when_acc_after_gate_entry() {
 
     tracker.process_event(create_event(EventType::TrackCreate, 100, None));
     tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
-    tokio::time::sleep(millis(80)).await;
+    tracker.clock.advance(millis(80));
     tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1001)));
     tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1007)));
 
@@ -441,6 +571,208 @@ async fn test_gate_opens_when_acc_after_gate_entry() {
     assert!(tracker.journey_manager.get(TrackId(100)).unwrap().gate_cmd_at.is_some());
 }
 
+#[tokio::test]
+async fn test_dry_run_gate_cmd_skips_door_correlation() {
+    let mut tracker = create_test_tracker();
+    let gate = GateController::new(Config::default().with_gate_mode(GateMode::DryRun), None);
+    tracker.gate = Some(Arc::new(gate));
+
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.persons.get_mut(&TrackId(100)).unwrap().authorized = true;
+    tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1007)));
+
+    // The decision is made and recorded on the journey, tagged as dry run
+    assert_eq!(tracker.gate_cmd_rx.try_recv().unwrap().track_id, TrackId(100));
+    let journey = tracker.journey_manager.get(TrackId(100)).unwrap();
+    assert!(journey.gate_cmd_at.is_some());
+    let gate_cmd = journey.events.iter().find(|e| e.t == JourneyEventType::GateCmd).unwrap();
+    assert_eq!(gate_cmd.extra.as_deref(), Some("dry_run"));
+    // No door open can follow, so nothing waits for one
+    assert!(tracker.door_correlator.last_gate_cmd_track_id().is_none());
+}
+
+#[tokio::test]
+async fn test_shadow_policy_diff_never_opens_gate() {
+    // Candidate policy: 3s minimum dwell instead of the live 7s
+    let shadow = ShadowConfig { enabled: true, min_dwell_ms: Some(3000), ..Default::default() };
+    let config = Config::default()
+        .with_min_dwell_ms(7000)
+        .with_acc_ip_to_pos(acc_ip_mapping())
+        .with_shadow(shadow);
+    let mut tracker = create_test_tracker_with_config(config);
+    let (egress_tx, mut egress_rx) = mpsc::channel(64);
+    tracker.egress_sender = Some(EgressSender::new(egress_tx, "test".to_string()));
+
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
+    tracker.clock.advance(millis(4000));
+    tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1001)));
+    tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1007)));
+    tracker.process_event(create_event(EventType::AccEvent("127.0.0.1".to_string()), 0, None));
+
+    // Live blocks (4s < 7s); the shadow would have opened but sends nothing
+    assert!(tracker.gate_cmd_rx.try_recv().is_err());
+    let summary = tracker.metrics.report(tracker.active_tracks(), tracker.authorized_tracks());
+    assert_eq!(summary.gate_commands_sent, 0);
+    // Gate entry: both blocked. ACC while waiting: shadow opens.
+    assert_eq!(summary.shadow_agree_today, 1);
+    assert_eq!(summary.shadow_would_open_today, 1);
+    assert_eq!(summary.shadow_would_block_today, 0);
+
+    let mut diffs = Vec::new();
+    while let Ok(message) = egress_rx.try_recv() {
+        if let EgressMessage::ShadowDiff(diff) = message {
+            diffs.push(diff);
+        }
+    }
+    assert_eq!(diffs.len(), 1);
+    assert_eq!(diffs[0].tid, 100);
+    assert_eq!(diffs[0].point, "acc");
+    assert_eq!(diffs[0].live, "blocked");
+    assert_eq!(diffs[0].shadow, "open");
+    assert_eq!(diffs[0].shadow_dwell_ms, 4000);
+}
+
+#[tokio::test]
+async fn test_shadow_compares_trigger_driven_open_once() {
+    let shadow = ShadowConfig { enabled: true, ..Default::default() };
+    let config = Config::default().with_shadow(shadow);
+    let mut tracker = create_test_tracker_with_config(config);
+
+    // Authorized outside both policies' ACC matching: the shadow blocks
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.persons.get_mut(&TrackId(100)).unwrap().authorized = true;
+    tracker.process_event(create_event(EventType::GateTrigger, 100, Some(1007)));
+    tracker.clock.advance(millis(20));
+    tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1007)));
+
+    let summary = tracker.metrics.report(tracker.active_tracks(), tracker.authorized_tracks());
+    assert_eq!(summary.gate_trigger_opens_total, 1);
+    assert_eq!(summary.shadow_would_block_today, 1);
+    assert_eq!(summary.shadow_agree_today + summary.shadow_would_open_today, 0);
+}
+
+#[tokio::test]
+async fn test_derived_gate_entry_does_not_open_gate() {
+    let mut tracker = create_test_tracker();
+
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.persons.get_mut(&TrackId(100)).unwrap().authorized = true;
+    let mut entry = create_event(EventType::ZoneEntry, 100, Some(1007));
+    entry.derived = true;
+    tracker.process_event(entry);
+    assert!(tracker.gate_cmd_rx.try_recv().is_err());
+
+    // The sensor's own entry still opens it
+    tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1007)));
+    assert_eq!(tracker.gate_cmd_rx.try_recv().unwrap().track_id, TrackId(100));
+}
+
+#[tokio::test]
+async fn test_gate_trigger_opens_before_gate_entry() {
+    let mut tracker = create_test_tracker();
+
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.persons.get_mut(&TrackId(100)).unwrap().authorized = true;
+
+    tracker.process_event(create_event(EventType::GateTrigger, 100, Some(1007)));
+    let summary = tracker.metrics.report(tracker.active_tracks(), tracker.authorized_tracks());
+    assert_eq!(summary.gate_commands_sent, 1);
+    assert_eq!(summary.gate_trigger_opens_total, 1);
+
+    // The real GATE zone entry does not send a second command
+    tracker.clock.advance(millis(20));
+    tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1007)));
+    let summary = tracker.metrics.report(tracker.active_tracks(), tracker.authorized_tracks());
+    assert_eq!(summary.gate_commands_sent, 1);
+    assert_eq!(summary.gate_trigger_lead_buckets.iter().sum::<u64>(), 1);
+
+    let journey = tracker.journey_manager.get(TrackId(100)).unwrap();
+    let trigger_at = journey.gate_trigger_at.unwrap();
+    assert!(journey.gate_entry_at.unwrap() >= trigger_at);
+    assert!(journey.events.iter().any(|e| e.t == JourneyEventType::GateTrigger));
+    assert!(journey.events.iter().any(|e| e.t == JourneyEventType::ZoneEntry));
+}
+
+#[tokio::test]
+async fn test_gate_trigger_unauthorized_only_records() {
+    let mut tracker = create_test_tracker();
+
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.process_event(create_event(EventType::GateTrigger, 100, Some(1007)));
+
+    let summary = tracker.metrics.report(tracker.active_tracks(), tracker.authorized_tracks());
+    assert_eq!(summary.gate_commands_sent, 0);
+    assert_eq!(summary.gate_triggers_total, 1);
+    assert!(tracker.journey_manager.get(TrackId(100)).unwrap().gate_trigger_at.is_some());
+}
+
+#[tokio::test]
+async fn test_keep_locked_suppresses_gate_open_while_degraded() {
+    let config = Config::default().with_degraded_policy(DegradedPolicy::KeepLocked);
+    let mut tracker = create_test_tracker_with_config(config);
+    let (degraded_tx, degraded_rx) = watch::channel(true);
+    tracker.degraded_rx = Some(degraded_rx);
+
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.persons.get_mut(&TrackId(100)).unwrap().authorized = true;
+    tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1007)));
+
+    let summary = tracker.metrics.report(tracker.active_tracks(), tracker.authorized_tracks());
+    assert_eq!(summary.gate_commands_sent, 0);
+    assert_eq!(summary.gate_opens_suppressed_total, 1);
+    assert!(tracker.gate_cmd_rx.try_recv().is_err());
+
+    // Sensor back online: the next authorized person gets through
+    degraded_tx.send(false).unwrap();
+    tracker.process_event(create_event(EventType::TrackCreate, 200, None));
+    tracker.persons.get_mut(&TrackId(200)).unwrap().authorized = true;
+    tracker.process_event(create_event(EventType::ZoneEntry, 200, Some(1007)));
+    assert_eq!(tracker.gate_cmd_rx.try_recv().unwrap().track_id, TrackId(200));
+}
+
+#[tokio::test]
+async fn test_keep_locked_keeps_gate_chance_until_recovery() {
+    let config = Config::default().with_degraded_policy(DegradedPolicy::KeepLocked);
+    let mut tracker = create_test_tracker_with_config(config);
+    let (degraded_tx, degraded_rx) = watch::channel(true);
+    tracker.degraded_rx = Some(degraded_rx);
+
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.persons.get_mut(&TrackId(100)).unwrap().authorized = true;
+    tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1007)));
+    assert!(tracker.gate_cmd_rx.try_recv().is_err());
+    assert_eq!(tracker.journey_manager.get(TrackId(100)).unwrap().gate_open_count, 0);
+
+    // Sensor back online: the same customer re-enters the gate zone and gets through
+    degraded_tx.send(false).unwrap();
+    tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1007)));
+    tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1007)));
+    assert_eq!(tracker.gate_cmd_rx.try_recv().unwrap().track_id, TrackId(100));
+    assert_eq!(tracker.journey_manager.get(TrackId(100)).unwrap().gate_open_count, 1);
+}
+
+#[tokio::test]
+async fn test_free_exit_pulses_gate_while_degraded() {
+    let config = Config::default().with_degraded_policy(DegradedPolicy::FreeExit);
+    let mut tracker = create_test_tracker_with_config(config);
+
+    // Not degraded: no pulse
+    tracker.tick_degraded_policy();
+    assert!(tracker.gate_cmd_rx.try_recv().is_err());
+
+    let (_degraded_tx, degraded_rx) = watch::channel(true);
+    tracker.degraded_rx = Some(degraded_rx);
+    tracker.tick_degraded_policy();
+    assert_eq!(tracker.gate_cmd_rx.try_recv().unwrap().track_id, TrackId(0));
+
+    // Rate-limited by free_exit_interval_ms
+    tracker.tick_degraded_policy();
+    assert!(tracker.gate_cmd_rx.try_recv().is_err());
+    let summary = tracker.metrics.report(0, 0);
+    assert_eq!(summary.free_exit_pulses_total, 1);
+}
+
 #[tokio::test]
 async fn test_acc_authorization_survives_pending_and_stitch() {
     let config = Config::default().with_min_dwell_ms(50).with_acc_ip_to_pos(acc_ip_mapping());
@@ -448,7 +780,7 @@ async fn test_acc_authorization_survives_pending_and_stitch() {
 
     tracker.process_event(create_event_with_pos(EventType::TrackCreate, 100, [1.0, 1.0, 1.70]));
     tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
-    tokio::time::sleep(millis(80)).await;
+    tracker.clock.advance(millis(80));
     tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1001)));
 
     tracker.process_event(create_event_with_pos(EventType::TrackDelete, 100, [1.0, 1.0, 1.70]));
@@ -468,14 +800,14 @@ async fn test_acc_authorization_survives_pending_and_stitch() {
 // =============================================================================
 
 /// Simulate a POS zone visit with specified dwell time
-async fn visit_pos_zone(tracker: &mut TestTracker, track_id: i64, zone_id: i32, dwell_ms: u64) {
+fn visit_pos_zone(tracker: &mut TestTracker, track_id: i64, zone_id: i32, dwell_ms: u64) {
     tracker.process_event(create_event(EventType::ZoneEntry, track_id, Some(zone_id)));
-    tokio::time::sleep(millis(dwell_ms)).await;
+    tracker.clock.advance(millis(dwell_ms));
     tracker.process_event(create_event(EventType::ZoneExit, track_id, Some(zone_id)));
 }
 
 fn is_authorized(tracker: &TestTracker, track_id: i64) -> bool {
-    tracker.persons.get(&TrackId(track_id)).map_or(false, |p| p.authorized)
+    tracker.persons.get(&TrackId(track_id)).is_some_and(|p| p.authorized)
 }
 
 fn send_acc_event(tracker: &mut TestTracker, ip: &str) {
@@ -495,8 +827,8 @@ async fn test_per_zone_dwell_does_not_combine_across_zones() {
     let mut tracker = create_test_tracker_with_config(config);
 
     tracker.process_event(create_event(EventType::TrackCreate, 100, None));
-    visit_pos_zone(&mut tracker, 100, 1001, 3100).await; // POS_1: 3s (below 7s threshold)
-    visit_pos_zone(&mut tracker, 100, 1002, 5100).await; // POS_2: 5s (below 7s threshold)
+    visit_pos_zone(&mut tracker, 100, 1001, 3100); // POS_1: 3s (below 7s threshold)
+    visit_pos_zone(&mut tracker, 100, 1002, 5100); // POS_2: 5s (below 7s threshold)
 
     // ACC from POS_1 should NOT match (only 3s dwell at POS_1)
     send_acc_event(&mut tracker, "127.0.0.1");
@@ -515,7 +847,7 @@ async fn test_per_zone_dwell_qualifies_at_correct_zone_only() {
     let mut tracker = create_test_tracker_with_config(config);
 
     tracker.process_event(create_event(EventType::TrackCreate, 100, None));
-    visit_pos_zone(&mut tracker, 100, 1001, 8100).await; // POS_1: 8s (above 7s threshold)
+    visit_pos_zone(&mut tracker, 100, 1001, 8100); // POS_1: 8s (above 7s threshold)
     enter_gate_zone(&mut tracker, 100);
 
     send_acc_event(&mut tracker, "127.0.0.1");
@@ -525,6 +857,40 @@ async fn test_per_zone_dwell_qualifies_at_correct_zone_only() {
     assert_eq!(summary.gate_commands_sent, 1, "Gate should have opened");
 }
 
+#[tokio::test]
+async fn test_dwell_threshold_is_inclusive() {
+    let config =
+        Config::default().with_min_dwell_ms(7000).with_acc_ip_to_pos(acc_ip_mapping_multi_zone());
+    let mut tracker = create_test_tracker_with_config(config);
+
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.process_event(create_event(EventType::TrackCreate, 200, None));
+    visit_pos_zone(&mut tracker, 100, 1001, 6999);
+    visit_pos_zone(&mut tracker, 200, 1002, 7000);
+
+    send_acc_event(&mut tracker, "127.0.0.1");
+    assert!(!is_authorized(&tracker, 100), "1ms short of the threshold");
+    send_acc_event(&mut tracker, "127.0.0.2");
+    assert!(is_authorized(&tracker, 200), "exactly the threshold");
+}
+
+#[tokio::test]
+async fn test_journey_timestamps_follow_clock() {
+    let mut tracker = create_test_tracker();
+
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.clock.advance(millis(1500));
+    tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
+    tracker.clock.advance(millis(2500));
+    tracker.process_event(create_event(EventType::TrackDelete, 100, None));
+
+    let journey = tracker.journey_manager.get_any(TrackId(100)).unwrap();
+    assert_eq!(journey.started_at, EVENT_TIME_MS);
+    assert_eq!(journey.ended_at, Some(EVENT_TIME_MS + 4000));
+    let entry = journey.events.iter().find(|e| e.t == JourneyEventType::ZoneEntry).unwrap();
+    assert_eq!(entry.ts, EVENT_TIME_MS + 1500);
+}
+
 #[tokio::test]
 async fn test_per_zone_acc_from_wrong_zone_does_not_match() {
     // Customer at POS_1 for 8s, but ACC comes from POS_2 - no match
@@ -533,7 +899,7 @@ async fn test_per_zone_acc_from_wrong_zone_does_not_match() {
     let mut tracker = create_test_tracker_with_config(config);
 
     tracker.process_event(create_event(EventType::TrackCreate, 100, None));
-    visit_pos_zone(&mut tracker, 100, 1001, 8100).await; // POS_1: 8s (above threshold)
+    visit_pos_zone(&mut tracker, 100, 1001, 8100); // POS_1: 8s (above threshold)
 
     send_acc_event(&mut tracker, "127.0.0.2"); // ACC from POS_2 (wrong zone)
     assert!(!is_authorized(&tracker, 100), "No dwell at POS_2");
@@ -546,8 +912,8 @@ async fn test_acc_within_grace_window_matches() {
     let mut tracker = create_test_tracker_with_config(config);
 
     tracker.process_event(create_event(EventType::TrackCreate, 100, None));
-    visit_pos_zone(&mut tracker, 100, 1001, 100).await;
-    tokio::time::sleep(millis(4000)).await; // Wait 4s (within 5s grace window)
+    visit_pos_zone(&mut tracker, 100, 1001, 100);
+    tracker.clock.advance(millis(4000)); // Wait 4s (within 5s grace window)
     enter_gate_zone(&mut tracker, 100);
 
     send_acc_event(&mut tracker, "127.0.0.1");
@@ -564,8 +930,8 @@ async fn test_acc_beyond_grace_window_does_not_match() {
     let mut tracker = create_test_tracker_with_config(config);
 
     tracker.process_event(create_event(EventType::TrackCreate, 100, None));
-    visit_pos_zone(&mut tracker, 100, 1001, 100).await;
-    tokio::time::sleep(millis(6000)).await; // Wait 6s (beyond 5s grace window)
+    visit_pos_zone(&mut tracker, 100, 1001, 100);
+    tracker.clock.advance(millis(6000)); // Wait 6s (beyond 5s grace window)
 
     send_acc_event(&mut tracker, "127.0.0.1");
     assert!(!is_authorized(&tracker, 100), "Exit beyond grace window");
@@ -582,10 +948,10 @@ async fn test_acc_picks_highest_dwell_as_primary() {
 
     // Track 100 enters POS first (longer dwell)
     tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
-    tokio::time::sleep(millis(50)).await;
+    tracker.clock.advance(millis(50));
     // Track 200 enters POS later (shorter dwell)
     tracker.process_event(create_event(EventType::ZoneEntry, 200, Some(1001)));
-    tokio::time::sleep(millis(100)).await;
+    tracker.clock.advance(millis(100));
 
     // Both still in POS zone: track 100 ~150ms, track 200 ~100ms
     send_acc_event(&mut tracker, "127.0.0.1");
@@ -605,10 +971,8 @@ async fn test_acc_picks_highest_dwell_as_primary() {
 // Group tracks have the 0x80000000 bit set and were previously filtered.
 // Now they flow through all handlers like regular tracks.
 
-const XOVIS_GROUP_BIT: i64 = 0x80000000;
-
 fn group_track_id(base_id: i64) -> i64 {
-    base_id | XOVIS_GROUP_BIT
+    base_id | TrackId::GROUP_BIT
 }
 
 #[tokio::test]
@@ -633,7 +997,7 @@ async fn test_group_track_full_journey() {
 
     let gid = group_track_id(100);
     tracker.process_event(create_event(EventType::TrackCreate, gid, None));
-    visit_pos_zone(&mut tracker, gid, 1001, 100).await;
+    visit_pos_zone(&mut tracker, gid, 1001, 100);
     enter_gate_zone(&mut tracker, gid);
     send_acc_event(&mut tracker, "127.0.0.1");
 
@@ -654,6 +1018,173 @@ async fn test_group_track_full_journey() {
     assert!(journey.events.len() >= 4);
 }
 
+fn join_group(tracker: &mut TestTracker, track_id: i64, group: i64) {
+    let group = TrackId(group_track_id(group));
+    tracker.process_event(create_event(EventType::GroupJoin(group), track_id, None));
+}
+
+#[tokio::test]
+async fn test_acc_extends_to_group_members() {
+    // Parent pays at POS, child of the same Xovis group never visited the POS
+    let config = Config::default()
+        .with_min_dwell_ms(50)
+        .with_acc_ip_to_pos(acc_ip_mapping())
+        .with_groups(true);
+    let mut tracker = create_test_tracker_with_config(config);
+
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.process_event(create_event(EventType::TrackCreate, 200, None));
+    join_group(&mut tracker, 100, 7);
+    join_group(&mut tracker, 200, 7);
+    visit_pos_zone(&mut tracker, 100, 1001, 100);
+    enter_gate_zone(&mut tracker, 200);
+    send_acc_event(&mut tracker, "127.0.0.1");
+
+    assert!(is_authorized(&tracker, 100));
+    assert!(is_authorized(&tracker, 200));
+    let journey = tracker.journey_manager.get(TrackId(200)).unwrap();
+    assert!(journey.acc_matched);
+    assert_eq!(journey.group, Some(TrackId(group_track_id(7))));
+
+    let summary = tracker.metrics.report(tracker.active_tracks(), tracker.authorized_tracks());
+    assert_eq!(summary.acc_group_extensions_total, 1);
+    assert_eq!(summary.gate_commands_sent, 1);
+}
+
+#[tokio::test]
+async fn test_group_recorded_without_acc_extension() {
+    let config = Config::default()
+        .with_min_dwell_ms(50)
+        .with_acc_ip_to_pos(acc_ip_mapping())
+        .with_groups(false);
+    let mut tracker = create_test_tracker_with_config(config);
+
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.process_event(create_event(EventType::TrackCreate, 200, None));
+    join_group(&mut tracker, 100, 7);
+    join_group(&mut tracker, 200, 7);
+    visit_pos_zone(&mut tracker, 100, 1001, 100);
+    send_acc_event(&mut tracker, "127.0.0.1");
+
+    assert!(is_authorized(&tracker, 100));
+    assert!(!is_authorized(&tracker, 200));
+    let journey = tracker.journey_manager.get(TrackId(200)).unwrap();
+    assert_eq!(journey.group, Some(TrackId(group_track_id(7))));
+
+    // Leaving the group keeps the journey's group but drops the membership
+    let group = TrackId(group_track_id(7));
+    tracker.process_event(create_event(EventType::GroupLeave(group), 200, None));
+    assert_eq!(tracker.groups[&group].as_slice(), &[TrackId(100)]);
+}
+
+// =============================================================================
+// Object Type Policy Tests
+// =============================================================================
+
+fn enter_gate_zone_as(tracker: &mut TestTracker, track_id: i64, obj_type: &'static str, x: f64) {
+    let event = ParsedEventBuilder::new(EventType::ZoneEntry)
+        .with_track_id(track_id)
+        .with_geometry_id(1007)
+        .with_position([x, -0.3, 1.2])
+        .with_obj_type(obj_type)
+        .build();
+    tracker.process_event(event);
+}
+
+fn inherit_child_config() -> Config {
+    let object_types = ObjectTypesConfig {
+        inherit_authorization: vec!["CHILD".to_string()],
+        ..Default::default()
+    };
+    Config::default()
+        .with_min_dwell_ms(50)
+        .with_acc_ip_to_pos(acc_ip_mapping())
+        .with_object_types(object_types)
+}
+
+#[tokio::test]
+async fn test_child_inherits_authorization_from_adjacent_adult() {
+    let mut tracker = create_test_tracker_with_config(inherit_child_config());
+
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.process_event(create_event(EventType::TrackCreate, 200, None));
+    visit_pos_zone(&mut tracker, 100, 1001, 100);
+    send_acc_event(&mut tracker, "127.0.0.1");
+    enter_gate_zone_as(&mut tracker, 100, "PERSON", 2.0);
+    enter_gate_zone_as(&mut tracker, 200, "CHILD", 2.6);
+
+    assert!(is_authorized(&tracker, 200));
+    let summary = tracker.metrics.report(tracker.active_tracks(), tracker.authorized_tracks());
+    assert_eq!(summary.auth_inherited_total, 1);
+    assert_eq!(summary.gate_commands_sent, 2);
+}
+
+#[tokio::test]
+async fn test_child_far_from_adult_is_blocked() {
+    let mut tracker = create_test_tracker_with_config(inherit_child_config());
+
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.process_event(create_event(EventType::TrackCreate, 200, None));
+    visit_pos_zone(&mut tracker, 100, 1001, 100);
+    send_acc_event(&mut tracker, "127.0.0.1");
+    enter_gate_zone_as(&mut tracker, 100, "PERSON", 0.0);
+    enter_gate_zone_as(&mut tracker, 200, "CHILD", 3.0);
+
+    // Too far away, and adults never inherit
+    assert!(!is_authorized(&tracker, 200));
+    enter_gate_zone_as(&mut tracker, 300, "PERSON", 0.2);
+    assert!(!is_authorized(&tracker, 300));
+}
+
+#[tokio::test]
+async fn test_child_ignores_stale_adult_position() {
+    let mut tracker = create_test_tracker_with_config(inherit_child_config());
+
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.process_event(create_event(EventType::TrackCreate, 200, None));
+    visit_pos_zone(&mut tracker, 100, 1001, 100);
+    send_acc_event(&mut tracker, "127.0.0.1");
+    enter_gate_zone_as(&mut tracker, 100, "PERSON", 2.0);
+
+    // The adult's last position is older than inherit_max_position_age_ms
+    tracker.clock.advance(millis(1500));
+    enter_gate_zone_as(&mut tracker, 200, "CHILD", 2.6);
+    assert!(!is_authorized(&tracker, 200));
+}
+
+#[tokio::test]
+async fn test_gate_trigger_resolves_authorization_like_gate_entry() {
+    let config = inherit_child_config().with_groups(true);
+    let mut tracker = create_test_tracker_with_config(config);
+
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.process_event(create_event(EventType::TrackCreate, 200, None));
+    tracker.process_event(create_event(EventType::TrackCreate, 300, None));
+    visit_pos_zone(&mut tracker, 100, 1001, 100);
+    send_acc_event(&mut tracker, "127.0.0.1");
+    enter_gate_zone_as(&mut tracker, 100, "PERSON", 2.0);
+
+    // A child next to the adult is opened for by the virtual trigger
+    let trigger = ParsedEventBuilder::new(EventType::GateTrigger)
+        .with_track_id(200)
+        .with_geometry_id(1007)
+        .with_position([2.6, -0.3, 1.2])
+        .with_obj_type("CHILD")
+        .build();
+    tracker.process_event(trigger);
+    assert!(is_authorized(&tracker, 200));
+
+    // So is a member who joined the payer's group after the ACC
+    join_group(&mut tracker, 100, 7);
+    join_group(&mut tracker, 300, 7);
+    tracker.process_event(create_event(EventType::GateTrigger, 300, Some(1007)));
+    assert!(is_authorized(&tracker, 300));
+
+    let summary = tracker.metrics.report(tracker.active_tracks(), tracker.authorized_tracks());
+    assert_eq!(summary.gate_trigger_opens_total, 2);
+    assert_eq!(summary.gate_commands_sent, 3);
+}
+
 // =============================================================================
 // Position-Based Exit Detection Tests (Task 7)
 // =============================================================================
@@ -677,7 +1208,7 @@ async fn test_position_detected_exit() {
 
     // Enter POS zone to set has_zone_events = true
     tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
-    tokio::time::sleep(millis(60)).await;
+    tracker.clock.advance(millis(60));
     tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1001)));
 
     // Delete track at exit region position (no LINE_CROSS)
@@ -722,7 +1253,7 @@ async fn test_lost_low_y() {
 
     // Enter POS zone to set has_zone_events = true
     tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
-    tokio::time::sleep(millis(60)).await;
+    tracker.clock.advance(millis(60));
     tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1001)));
 
     // Delete track at low y position (not in exit region)
@@ -750,7 +1281,7 @@ async fn test_false_positive_prevention_store_zone() {
 
     // Enter POS zone to set has_zone_events = true
     tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
-    tokio::time::sleep(millis(60)).await;
+    tracker.clock.advance(millis(60));
     tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1001)));
 
     // Delete track at store zone position
@@ -774,7 +1305,7 @@ async fn test_false_positive_prevention_pos_zone() {
 
     // Enter POS zone to set has_zone_events = true
     tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
-    tokio::time::sleep(millis(60)).await;
+    tracker.clock.advance(millis(60));
     tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1001)));
 
     // Delete track at POS area position
@@ -798,7 +1329,7 @@ async fn test_correct_detection_exit_region() {
 
     // Enter POS zone to set has_zone_events = true
     tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
-    tokio::time::sleep(millis(60)).await;
+    tracker.clock.advance(millis(60));
     tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1001)));
 
     // Delete track at exit region position
@@ -831,7 +1362,7 @@ async fn test_y_threshold_boundary() {
             [2.0, 2.29, 1.70],
         ));
         tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
-        tokio::time::sleep(millis(60)).await;
+        tracker.clock.advance(millis(60));
         tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1001)));
         tracker.process_event(create_event_with_pos(
             EventType::TrackDelete,
@@ -858,7 +1389,7 @@ async fn test_y_threshold_boundary() {
             [2.0, 2.31, 1.70],
         ));
         tracker.process_event(create_event(EventType::ZoneEntry, 200, Some(1001)));
-        tokio::time::sleep(millis(60)).await;
+        tracker.clock.advance(millis(60));
         tracker.process_event(create_event(EventType::ZoneExit, 200, Some(1001)));
         tracker.process_event(create_event_with_pos(
             EventType::TrackDelete,
@@ -893,7 +1424,7 @@ async fn test_x_min_boundary() {
             [1.49, 2.5, 1.70],
         ));
         tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
-        tokio::time::sleep(millis(60)).await;
+        tracker.clock.advance(millis(60));
         tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1001)));
         tracker.process_event(create_event_with_pos(
             EventType::TrackDelete,
@@ -920,7 +1451,7 @@ async fn test_x_min_boundary() {
             [1.51, 2.5, 1.70],
         ));
         tracker.process_event(create_event(EventType::ZoneEntry, 200, Some(1001)));
-        tokio::time::sleep(millis(60)).await;
+        tracker.clock.advance(millis(60));
         tracker.process_event(create_event(EventType::ZoneExit, 200, Some(1001)));
         tracker.process_event(create_event_with_pos(
             EventType::TrackDelete,
@@ -955,7 +1486,7 @@ async fn test_x_max_boundary() {
             [2.99, 2.5, 1.70],
         ));
         tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
-        tokio::time::sleep(millis(60)).await;
+        tracker.clock.advance(millis(60));
         tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1001)));
         tracker.process_event(create_event_with_pos(
             EventType::TrackDelete,
@@ -983,7 +1514,7 @@ async fn test_x_max_boundary() {
             [3.01, 2.5, 1.70],
         ));
         tracker.process_event(create_event(EventType::ZoneEntry, 200, Some(1001)));
-        tokio::time::sleep(millis(60)).await;
+        tracker.clock.advance(millis(60));
         tracker.process_event(create_event(EventType::ZoneExit, 200, Some(1001)));
         tracker.process_event(create_event_with_pos(
             EventType::TrackDelete,
@@ -1052,7 +1583,7 @@ async fn test_has_zone_events_tracking() {
     assert!(person.has_zone_events, "has_zone_events should be true after ZoneEntry");
 
     // Exit the zone
-    tokio::time::sleep(millis(60)).await;
+    tracker.clock.advance(millis(60));
     tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1001)));
 
     // Verify has_zone_events is still true
@@ -1076,7 +1607,7 @@ async fn test_line_cross_overrides_position() {
 
     // Enter POS zone
     tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
-    tokio::time::sleep(millis(60)).await;
+    tracker.clock.advance(millis(60));
     tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1001)));
 
     // Enter GATE zone
@@ -1084,7 +1615,7 @@ async fn test_line_cross_overrides_position() {
 
     // Cross EXIT line forward (this should complete the journey regardless of position)
     let mut exit_event = create_event(EventType::LineCrossForward, 100, Some(1006));
-    exit_event.direction = Some("forward".to_string());
+    exit_event.direction = Some(Direction::Forward);
     tracker.process_event(exit_event);
 
     // Person should be removed (journey complete via line cross)
@@ -1107,7 +1638,7 @@ async fn test_no_last_position() {
 
     // Enter POS zone to set has_zone_events
     tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
-    tokio::time::sleep(millis(60)).await;
+    tracker.clock.advance(millis(60));
     tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1001)));
 
     // Delete track without position
@@ -1128,13 +1659,13 @@ async fn test_normal_exit_line_cross() {
 
     tracker.process_event(create_event_with_pos(EventType::TrackCreate, 100, [2.0, 2.0, 1.70]));
     tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
-    tokio::time::sleep(millis(60)).await;
+    tracker.clock.advance(millis(60));
     tracker.process_event(create_event(EventType::ZoneExit, 100, Some(1001)));
     tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1007)));
 
     // Cross EXIT line forward
     let mut exit_event = create_event(EventType::LineCrossForward, 100, Some(1006));
-    exit_event.direction = Some("forward".to_string());
+    exit_event.direction = Some(Direction::Forward);
     tracker.process_event(exit_event);
 
     // Person should be removed
@@ -1144,3 +1675,175 @@ async fn test_normal_exit_line_cross() {
     assert_eq!(journey.outcome, JourneyOutcome::Completed);
     assert!(!journey.exit_inferred, "exit_inferred should be false for line cross");
 }
+
+#[tokio::test]
+async fn test_snapshot_restores_paid_customer() {
+    let mut tracker = create_test_tracker();
+    tracker.process_event(create_event_with_pos(EventType::TrackCreate, 100, [1.0, 1.0, 1.70]));
+    tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
+    tracker.persons.get_mut(&TrackId(100)).unwrap().authorized = true;
+    tracker.journey_manager.get_mut(TrackId(100)).unwrap().authorized = true;
+    tracker.process_event(create_event_with_pos(EventType::TrackCreate, 200, [4.0, 4.0, 1.70]));
+    tracker.process_event(create_event(EventType::ZoneEntry, 200, Some(1002)));
+    tracker.process_event(create_event_with_pos(EventType::TrackDelete, 200, [4.0, 4.0, 1.70]));
+
+    // Through JSON, as written to disk
+    let json = serde_json::to_string(&tracker.snapshot()).unwrap();
+    let mut restarted = create_test_tracker();
+    restarted.restore(serde_json::from_str(&json).unwrap());
+
+    assert!(restarted.persons[&TrackId(100)].authorized);
+    let journey = restarted.journey_manager.get(TrackId(100)).unwrap();
+    assert!(journey.authorized);
+    assert!(journey.restored_at.is_some());
+    assert!(journey.to_json().contains("\"restored\""));
+    let candidates = restarted.pos_occupancy.get_candidates("POS_1", restarted.clock.now());
+    assert_eq!(candidates.first().map(|c| c.0), Some(TrackId(100)));
+
+    // The deleted track is still in the stitch pool
+    let stitched = restarted.stitcher.find_match(Some([4.1, 4.0, 1.70]));
+    assert_eq!(stitched.map(|m| m.person.track_id), Some(TrackId(200)));
+}
+
+#[tokio::test]
+async fn test_snapshot_older_than_max_age_is_ignored() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("snapshot.json");
+    let path = path.to_str().unwrap();
+    let mut tracker =
+        create_test_tracker_with_config(Config::default().with_snapshot(SnapshotConfig {
+            enabled: true,
+            path: path.to_string(),
+            ..Default::default()
+        }));
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.write_snapshot();
+
+    let saved_at =
+        snapshot::load(path, 60_000, tracker.clock.epoch_ms()).unwrap().unwrap().saved_at;
+    assert!(snapshot::load(path, 60_000, saved_at + 60_001).unwrap().is_none());
+    assert!(snapshot::load(&format!("{path}.missing"), 60_000, saved_at).unwrap().is_none());
+
+    let mut restarted = create_test_tracker_with_config(tracker.config.clone());
+    restarted.restore_snapshot();
+    assert!(restarted.persons.contains_key(&TrackId(100)));
+}
+
+#[tokio::test]
+async fn test_restored_track_not_seen_again_is_ended() {
+    let mut tracker = create_test_tracker();
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.process_event(create_event(EventType::TrackCreate, 200, None));
+    tracker.persons.get_mut(&TrackId(100)).unwrap().authorized = true;
+
+    let mut restarted = create_test_tracker();
+    restarted.restore(tracker.snapshot());
+    assert_eq!(restarted.active_tracks(), 2);
+
+    // Track 200 is still in the sensor's frames, track 100 was deleted while down
+    restarted.restored_tracks.seen([TrackId(200)]);
+    restarted.clock.advance(millis(9_000));
+    restarted.tick_restored_tracks();
+    assert_eq!(restarted.active_tracks(), 2);
+
+    restarted.clock.advance(millis(1_000));
+    restarted.tick_restored_tracks();
+    assert!(!restarted.persons.contains_key(&TrackId(100)));
+    assert!(restarted.persons.contains_key(&TrackId(200)));
+    assert_eq!(restarted.authorized_tracks(), 0);
+    assert!(restarted.journey_manager.get(TrackId(100)).is_none());
+    assert!(restarted.journey_manager.get(TrackId(200)).is_some());
+    assert_eq!(restarted.journey_manager.pending_count(), 1);
+}
+
+#[tokio::test]
+async fn test_periodic_snapshot_write_awaited_before_final() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("snapshot.json");
+    let path = path.to_str().unwrap();
+    let mut tracker =
+        create_test_tracker_with_config(Config::default().with_snapshot(SnapshotConfig {
+            enabled: true,
+            path: path.to_string(),
+            ..Default::default()
+        }));
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.spawn_snapshot_write();
+    assert!(tracker.snapshot_write.is_some());
+
+    tracker.finish_snapshot_write().await;
+    assert!(tracker.snapshot_write.is_none());
+    let saved = snapshot::load(path, 60_000, tracker.clock.epoch_ms()).unwrap();
+    assert!(saved.is_some());
+    assert!(!std::path::Path::new(&format!("{path}.tmp")).exists());
+}
+
+#[tokio::test]
+async fn test_shutdown_drains_events_and_interrupts_journeys() {
+    let mut tracker = create_test_tracker();
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    tracker.shutdown_rx = Some(shutdown_rx);
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.journey_manager.get_mut(TrackId(100)).unwrap().crossed_entry = true;
+
+    // Queued before the shutdown signal, so it must still be processed
+    let (event_tx, event_rx) = mpsc::channel(8);
+    event_tx.send(create_event(EventType::ZoneEntry, 100, Some(1001))).await.unwrap();
+    shutdown_tx.send(true).unwrap();
+    tracker.run(event_rx).await;
+
+    // Emitted without waiting for the egress delay
+    let journey = tracker.journey_rx.try_recv().expect("journey emitted on shutdown");
+    assert_eq!(journey.outcome, JourneyOutcome::Interrupted);
+    assert!(journey.events.iter().any(|e| e.t == JourneyEventType::ZoneEntry));
+    assert_eq!(tracker.journey_manager.active_count(), 0);
+    assert_eq!(tracker.journey_manager.pending_count(), 0);
+}
+
+#[tokio::test]
+async fn test_shutdown_with_snapshot_keeps_journeys_for_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("snapshot.json");
+    let mut tracker =
+        create_test_tracker_with_config(Config::default().with_snapshot(SnapshotConfig {
+            enabled: true,
+            path: path.to_str().unwrap().to_string(),
+            ..Default::default()
+        }));
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    tracker.shutdown_rx = Some(shutdown_rx);
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    shutdown_tx.send(true).unwrap();
+    let (_event_tx, event_rx) = mpsc::channel(8);
+    tracker.run(event_rx).await;
+
+    // Not emitted as Interrupted: the journey continues after the restart
+    assert!(tracker.journey_rx.try_recv().is_err());
+    let mut restarted = create_test_tracker_with_config(tracker.config.clone());
+    restarted.restore_snapshot();
+    assert!(restarted.journey_manager.get(TrackId(100)).is_some());
+}
+
+#[tokio::test]
+async fn test_apply_config_reloads_acc_mapping_and_dwell_keeping_state() {
+    let mut tracker = create_test_tracker();
+    tracker.process_event(create_event(EventType::TrackCreate, 100, None));
+    tracker.process_event(create_event(EventType::ZoneEntry, 100, Some(1001)));
+    assert_eq!(tracker.acc_collector.pos_for_ip("127.0.0.1"), None);
+
+    let reloaded = Config::default()
+        .with_acc_ip_to_pos(acc_ip_mapping())
+        .with_min_dwell_ms(1234)
+        .with_frame_reorder(500);
+    tracker.apply_config(&reloaded);
+
+    assert_eq!(tracker.acc_collector.pos_for_ip("127.0.0.1"), Some("POS_1"));
+    assert_eq!(tracker.pos_occupancy.min_dwell_ms(), 1234);
+    assert_eq!(tracker.config.min_dwell_ms(), 1234);
+    // Restart-only fields keep their running value
+    assert!(!tracker.config.frame_reorder().enabled);
+    // Live state survives the reload
+    assert!(tracker.persons.contains_key(&TrackId(100)));
+    let candidates = tracker.pos_occupancy.get_candidates("POS_1", tracker.clock.now());
+    assert_eq!(candidates.first().map(|c| c.0), Some(TrackId(100)));
+}
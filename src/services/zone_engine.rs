@@ -0,0 +1,360 @@
+//! Position-driven zone engine
+//!
+//! Fallback for zone events the sensor fails to report. Tracked object positions
+//! are tested against the POS and DWELL zone polygons from the geometry export
+//! (the zones that track dwell; the GATE zone is left to the sensor so a
+//! derived event never opens the gate); when a track's
+//! position disagrees with the zone membership implied by sensor events for
+//! longer than `confirm_ms`, a ZONE_ENTRY/ZONE_EXIT is synthesized with
+//! `derived = true`.
+//!
+//! De-duplication against the sensor:
+//! - Sensor zone events always update membership, so a real event arriving
+//!   inside the confirm window cancels the pending derived one
+//! - A sensor event repeating a transition already synthesized within
+//!   `dedupe_window_ms` is dropped
+
+use crate::domain::types::{EventType, GeometryId, ParsedEvent, TrackId, TrackedObject};
+use crate::infra::config::Config;
+use crate::infra::geometry::{Geometry, GeometryKind};
+use crate::infra::metrics::Metrics;
+use crate::services::stale_tracks::StalePruner;
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{debug, info, warn};
+
+/// Membership of one track in one zone
+#[derive(Debug, Default)]
+struct ZoneState {
+    /// Membership as last reported to the tracker (sensor or derived)
+    inside: bool,
+    /// When positions started disagreeing with `inside`
+    disagree_since: Option<u64>,
+    /// When the last derived transition was emitted
+    derived_at: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct TrackZones {
+    zones: FxHashMap<GeometryId, ZoneState>,
+    last_seen_ms: u64,
+}
+
+/// Synthesizes zone entry/exit events from tracked object positions
+pub struct ZoneEngine {
+    zones: Vec<Geometry>,
+    confirm_ms: u64,
+    margin_m: f64,
+    dedupe_window_ms: u64,
+    tracks: FxHashMap<TrackId, TrackZones>,
+    pruner: StalePruner,
+    metrics: Arc<Metrics>,
+}
+
+impl ZoneEngine {
+    /// Create an engine from `[zone_engine]` and the POS/DWELL zone polygons of the
+    /// geometry export
+    ///
+    /// Returns `None` when the engine is disabled or there are no polygons to test.
+    pub fn new(config: &Config, metrics: Arc<Metrics>) -> Option<Self> {
+        let engine_config = config.zone_engine();
+        if !engine_config.enabled {
+            return None;
+        }
+
+        let mut zones: Vec<Geometry> = config
+            .geometries()
+            .values()
+            .filter(|g| g.kind == GeometryKind::Zone && g.points.len() >= 3)
+            .filter(|g| config.zone_kind(GeometryId(g.id)).tracks_dwell())
+            .cloned()
+            .collect();
+        if zones.is_empty() {
+            warn!("zone_engine_disabled: no POS/DWELL zone polygons (set [zones].geometry_file)");
+            return None;
+        }
+        zones.sort_by_key(|g| g.id);
+
+        info!(
+            zones = %zones.len(),
+            confirm_ms = %engine_config.confirm_ms,
+            margin_m = %engine_config.margin_m,
+            "zone_engine_enabled"
+        );
+
+        Some(Self {
+            zones,
+            confirm_ms: engine_config.confirm_ms,
+            margin_m: engine_config.margin_m,
+            dedupe_window_ms: engine_config.dedupe_window_ms,
+            tracks: FxHashMap::default(),
+            pruner: StalePruner::default(),
+            metrics,
+        })
+    }
+
+    /// Process one sensor message
+    ///
+    /// Returns the sensor events that should be forwarded (duplicates removed)
+    /// followed by any derived events. `now_ms` is the epoch time used for the
+    /// confirm and dedupe windows and as `event_time` of derived events.
+    pub fn process(
+        &mut self,
+        events: Vec<ParsedEvent>,
+        objects: &[TrackedObject],
+        now_ms: u64,
+        received_at: Instant,
+    ) -> Vec<ParsedEvent> {
+        let mut out = Vec::with_capacity(events.len());
+        for event in events {
+            if self.observe_sensor_event(&event, now_ms) {
+                out.push(event);
+            }
+        }
+
+        // Exits first so the tracker's current zone ends up on the entered zone
+        let mut entries = Vec::new();
+        for obj in objects {
+            if TrackId(obj.track_id).is_group() || obj.position.len() < 2 {
+                continue;
+            }
+            let track_id = TrackId(obj.track_id);
+            let position = (obj.position.len() >= 3)
+                .then(|| [obj.position[0], obj.position[1], obj.position[2]]);
+            let (x, y) = (obj.position[0], obj.position[1]);
+
+            let track = self.tracks.entry(track_id).or_default();
+            track.last_seen_ms = now_ms;
+            for zone in &self.zones {
+                let geometry_id = GeometryId(zone.id);
+                let state = track.zones.entry(geometry_id).or_default();
+                let observed = if state.inside {
+                    zone.contains(x, y) || zone.distance_to_edge(x, y) <= self.margin_m
+                } else {
+                    zone.contains(x, y) && zone.distance_to_edge(x, y) > self.margin_m
+                };
+
+                if observed == state.inside {
+                    state.disagree_since = None;
+                    continue;
+                }
+                let since = *state.disagree_since.get_or_insert(now_ms);
+                if now_ms.saturating_sub(since) < self.confirm_ms {
+                    continue;
+                }
+
+                state.inside = observed;
+                state.disagree_since = None;
+                state.derived_at = Some(now_ms);
+                self.metrics.record_zone_event_derived();
+
+                let event_type = if observed { EventType::ZoneEntry } else { EventType::ZoneExit };
+                debug!(
+                    track_id = %track_id,
+                    zone = %zone.name,
+                    event_type = ?event_type,
+                    "zone_event_derived"
+                );
+                let event = ParsedEvent {
+                    event_type,
+                    track_id,
+                    geometry_id: Some(geometry_id),
+                    direction: None,
+                    event_time: now_ms,
+                    received_at,
+                    position,
+                    derived: true,
+                    obj_type: Some(obj.obj_type.clone()),
+                };
+                if observed {
+                    entries.push(event);
+                } else {
+                    out.push(event);
+                }
+            }
+        }
+        out.extend(entries);
+
+        self.pruner.retain(&mut self.tracks, now_ms, |t| t.last_seen_ms);
+        out
+    }
+
+    /// Update membership from a sensor event; returns false if it should be dropped
+    fn observe_sensor_event(&mut self, event: &ParsedEvent, now_ms: u64) -> bool {
+        let entering = match event.event_type {
+            EventType::ZoneEntry => true,
+            EventType::ZoneExit => false,
+            EventType::TrackDelete => {
+                self.tracks.remove(&event.track_id);
+                return true;
+            }
+            _ => return true,
+        };
+        let Some(geometry_id) = event.geometry_id else { return true };
+        if !self.zones.iter().any(|z| z.id == geometry_id.0) {
+            return true;
+        }
+
+        let track = self.tracks.entry(event.track_id).or_default();
+        track.last_seen_ms = now_ms;
+        let state = track.zones.entry(geometry_id).or_default();
+
+        let recently_derived =
+            state.derived_at.is_some_and(|t| now_ms.saturating_sub(t) <= self.dedupe_window_ms);
+        if state.inside == entering && recently_derived {
+            self.metrics.record_zone_event_deduped();
+            debug!(
+                track_id = %event.track_id,
+                geometry_id = %geometry_id.0,
+                event_type = ?event.event_type,
+                "zone_event_deduped"
+            );
+            return false;
+        }
+
+        state.inside = entering;
+        state.disagree_since = None;
+        state.derived_at = None;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use smallvec::smallvec;
+
+    const POS_1: i32 = 1001;
+
+    fn create_engine() -> (ZoneEngine, Arc<Metrics>) {
+        let square = Geometry {
+            id: POS_1,
+            kind: GeometryKind::Zone,
+            name: "POS_1".to_string(),
+            points: vec![[0.0, 0.0], [2.0, 0.0], [2.0, 2.0], [0.0, 2.0]],
+        };
+        let config = Config::default().with_zone_engine(vec![square]);
+        let metrics = Arc::new(Metrics::new());
+        (ZoneEngine::new(&config, metrics.clone()).unwrap(), metrics)
+    }
+
+    fn object(track_id: i64, x: f64, y: f64) -> TrackedObject {
+        TrackedObject { track_id, obj_type: Arc::from("PERSON"), position: smallvec![x, y, 1.7] }
+    }
+
+    fn sensor_event(event_type: EventType, track_id: i64) -> ParsedEvent {
+        ParsedEvent {
+            event_type,
+            track_id: TrackId(track_id),
+            geometry_id: Some(GeometryId(POS_1)),
+            direction: None,
+            event_time: 0,
+            received_at: Instant::now(),
+            position: None,
+            derived: false,
+            obj_type: None,
+        }
+    }
+
+    fn step(
+        engine: &mut ZoneEngine,
+        events: Vec<ParsedEvent>,
+        x: f64,
+        now_ms: u64,
+    ) -> Vec<ParsedEvent> {
+        engine.process(events, &[object(100, x, 1.0)], now_ms, Instant::now())
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        let metrics = Arc::new(Metrics::new());
+        assert!(ZoneEngine::new(&Config::default(), metrics).is_none());
+    }
+
+    #[test]
+    fn test_gate_zone_not_derived() {
+        let gate = Geometry {
+            id: 1007,
+            kind: GeometryKind::Zone,
+            name: "GATE_1".to_string(),
+            points: vec![[0.0, 0.0], [2.0, 0.0], [2.0, 2.0], [0.0, 2.0]],
+        };
+        let config = Config::default().with_zone_engine(vec![gate]);
+        assert!(ZoneEngine::new(&config, Arc::new(Metrics::new())).is_none());
+    }
+
+    #[test]
+    fn test_missed_exit_is_derived_after_confirm() {
+        let (mut engine, metrics) = create_engine();
+
+        // Sensor reports the entry, person dwells inside
+        let out = step(&mut engine, vec![sensor_event(EventType::ZoneEntry, 100)], 1.0, 0);
+        assert_eq!(out.len(), 1);
+        assert!(!out[0].derived);
+
+        // Person walks out, sensor never reports ZONE_EXIT
+        assert!(step(&mut engine, vec![], 3.0, 1000).is_empty());
+        assert!(step(&mut engine, vec![], 3.0, 1500).is_empty());
+        let out = step(&mut engine, vec![], 3.0, 2000);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].event_type, EventType::ZoneExit);
+        assert_eq!(out[0].geometry_id, Some(GeometryId(POS_1)));
+        assert!(out[0].derived);
+        assert_eq!(metrics.report(0, 0).zone_events_derived_total, 1);
+
+        // No repeat while the person stays outside
+        assert!(step(&mut engine, vec![], 3.0, 5000).is_empty());
+    }
+
+    #[test]
+    fn test_sensor_event_within_confirm_window_wins() {
+        let (mut engine, _) = create_engine();
+
+        // Positions say "inside", the sensor entry arrives before confirm_ms
+        assert!(step(&mut engine, vec![], 1.0, 0).is_empty());
+        let out = step(&mut engine, vec![sensor_event(EventType::ZoneEntry, 100)], 1.0, 500);
+        assert_eq!(out.len(), 1);
+        assert!(!out[0].derived);
+        assert!(step(&mut engine, vec![], 1.0, 3000).is_empty());
+    }
+
+    #[test]
+    fn test_late_sensor_event_is_deduped() {
+        let (mut engine, metrics) = create_engine();
+
+        step(&mut engine, vec![], 1.0, 0);
+        let out = step(&mut engine, vec![], 1.0, 1000);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].event_type, EventType::ZoneEntry);
+        assert!(out[0].derived);
+
+        // Sensor catches up with the same transition
+        let out = step(&mut engine, vec![sensor_event(EventType::ZoneEntry, 100)], 1.0, 1200);
+        assert!(out.is_empty());
+        assert_eq!(metrics.report(0, 0).zone_events_deduped_total, 1);
+
+        // A later sensor exit is a new transition and passes through
+        let out = step(&mut engine, vec![sensor_event(EventType::ZoneExit, 100)], 3.0, 1500);
+        assert_eq!(out.len(), 1);
+        assert!(!out[0].derived);
+    }
+
+    #[test]
+    fn test_margin_suppresses_edge_jitter() {
+        let (mut engine, _) = create_engine();
+        step(&mut engine, vec![sensor_event(EventType::ZoneEntry, 100)], 1.0, 0);
+
+        // Hovering just outside the edge (within margin_m) is still inside
+        assert!(step(&mut engine, vec![], 2.1, 1000).is_empty());
+        assert!(step(&mut engine, vec![], 2.1, 3000).is_empty());
+    }
+
+    #[test]
+    fn test_group_tracks_ignored() {
+        let (mut engine, _) = create_engine();
+        let group = object(100 | TrackId::GROUP_BIT, 1.0, 1.0);
+        engine.process(vec![], std::slice::from_ref(&group), 0, Instant::now());
+        assert!(engine.process(vec![], &[group], 5000, Instant::now()).is_empty());
+    }
+}
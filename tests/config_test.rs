@@ -1,6 +1,8 @@
 //! Integration tests for configuration loading
 
-use gateway::domain::types::GeometryId;
+use gateway::domain::types::{GeometryId, ZoneKind};
+use gateway::infra::config::DegradedPolicy;
+use gateway::infra::config_check;
 use gateway::infra::{Config, GateMode};
 use std::io::Write;
 use tempfile::NamedTempFile;
@@ -63,3 +65,672 @@ fn test_load_from_path_fallback() {
     assert_eq!(config.mqtt_port(), 1883);
     assert_eq!(config.gate_mode(), GateMode::Tcp);
 }
+
+#[test]
+fn test_load_strict_missing_file() {
+    let err = Config::load_strict("/nonexistent/config.toml").unwrap_err();
+    assert_eq!(err.to_string(), "config file /nonexistent/config.toml not found");
+}
+
+#[test]
+fn test_load_config_with_geometry_file() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    let geometry_file = concat!(env!("CARGO_MANIFEST_DIR"), "/config/geometries/netto.json");
+
+    let config_content = format!(
+        r##"
+[mqtt]
+host = "localhost"
+port = 1883
+topic = "#"
+
+[gate]
+mode = "tcp"
+tcp_addr = "127.0.0.1:8000"
+http_url = "http://localhost/open"
+timeout_ms = 2000
+
+[rs485]
+device = "/dev/null"
+baud = 19200
+poll_interval_ms = 250
+
+[zones]
+geometry_file = "{}"
+
+[metrics]
+interval_secs = 10
+
+[zone_engine]
+enabled = true
+confirm_ms = 800
+"##,
+        geometry_file
+    );
+
+    temp_file.write_all(config_content.as_bytes()).unwrap();
+    temp_file.flush().unwrap();
+
+    let config = Config::from_file(temp_file.path()).unwrap();
+
+    assert!(config.zone_engine().enabled);
+    assert_eq!(config.zone_engine().confirm_ms, 800);
+    assert_eq!(config.gate_zone(), GeometryId(1000));
+    assert_eq!(config.exit_line(), 1006);
+    assert_eq!(config.entry_line(), Some(1008));
+    assert_eq!(config.approach_line(), Some(1010));
+    assert_eq!(config.pos_zones(), &[1001, 1002, 1003, 1004, 1005]);
+    assert_eq!(&*config.zone_name(GeometryId(1004)), "POS_1");
+    assert_eq!(config.geometry(GeometryId(1009)).unwrap().name, "STORE");
+}
+
+#[test]
+fn test_load_config_with_unknown_geometry_id_fails() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    let geometry_file = concat!(env!("CARGO_MANIFEST_DIR"), "/config/geometries/netto.json");
+
+    let config_content = format!(
+        r##"
+[mqtt]
+host = "localhost"
+port = 1883
+topic = "#"
+
+[gate]
+mode = "tcp"
+tcp_addr = "127.0.0.1:8000"
+http_url = "http://localhost/open"
+timeout_ms = 2000
+
+[rs485]
+device = "/dev/null"
+baud = 19200
+poll_interval_ms = 250
+
+[zones]
+geometry_file = "{}"
+gate_zone = 1007
+
+[metrics]
+interval_secs = 10
+"##,
+        geometry_file
+    );
+
+    temp_file.write_all(config_content.as_bytes()).unwrap();
+    temp_file.flush().unwrap();
+
+    let err = Config::from_file(temp_file.path()).unwrap_err();
+    assert!(format!("{:#}", err).contains("[zones].gate_zone = 1007"));
+    assert!(Config::load_strict(temp_file.path().to_str().unwrap()).is_err());
+}
+
+#[test]
+fn test_zone_kinds_do_not_depend_on_names() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+
+    let config_content = r##"
+[mqtt]
+host = "localhost"
+port = 1883
+topic = "#"
+
+[gate]
+mode = "tcp"
+tcp_addr = "127.0.0.1:8000"
+http_url = "http://localhost/open"
+timeout_ms = 2000
+
+[rs485]
+device = "/dev/null"
+baud = 19200
+poll_interval_ms = 250
+
+[zones]
+pos_zones = [2001]
+dwell_zones = [2002]
+gate_zone = 2003
+exit_line = 2004
+store_zone = 2005
+
+[zones.names]
+2001 = "KASSE_1"
+2002 = "WARTEN"
+2003 = "SPERRE"
+2004 = "AUSGANG"
+2005 = "LADEN"
+
+[metrics]
+interval_secs = 10
+"##;
+
+    temp_file.write_all(config_content.as_bytes()).unwrap();
+    temp_file.flush().unwrap();
+
+    let config = Config::from_file(temp_file.path()).unwrap();
+
+    assert_eq!(config.zone_kind(GeometryId(2001)), ZoneKind::Pos);
+    assert_eq!(config.zone_kind(GeometryId(2002)), ZoneKind::Dwell);
+    assert_eq!(config.zone_kind(GeometryId(2003)), ZoneKind::Gate);
+    assert_eq!(config.zone_kind(GeometryId(2004)), ZoneKind::Exit);
+    assert_eq!(config.zone_kind(GeometryId(2005)), ZoneKind::Store);
+    assert_eq!(config.zone_kind(GeometryId(9999)), ZoneKind::Other);
+    assert_eq!(config.zone_kind_by_name("KASSE_1"), ZoneKind::Pos);
+    assert_eq!(config.zone_kind_by_name("POS_1"), ZoneKind::Other);
+}
+
+#[test]
+fn test_tls_settings_validated_at_load() {
+    let base = r##"
+[mqtt]
+host = "localhost"
+port = 8883
+topic = "#"
+
+[gate]
+mode = "tcp"
+tcp_addr = "127.0.0.1:8000"
+http_url = "http://localhost/open"
+timeout_ms = 2000
+
+[rs485]
+device = "/dev/null"
+baud = 19200
+poll_interval_ms = 250
+
+[zones]
+pos_zones = [2001]
+gate_zone = 2003
+exit_line = 2004
+
+[metrics]
+interval_secs = 10
+"##;
+    let load = |tls: &str| {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(format!("{base}\n{tls}").as_bytes()).unwrap();
+        temp_file.flush().unwrap();
+        Config::from_file(temp_file.path())
+    };
+
+    let config = load(
+        r#"
+[mqtt.tls]
+enabled = true
+ca_file = "/etc/gateway/ca.pem"
+cert_file = "/etc/gateway/client.pem"
+key_file = "/etc/gateway/client.key"
+"#,
+    )
+    .unwrap();
+    assert!(config.mqtt_tls().enabled);
+    assert_eq!(config.mqtt_tls().cert_file.as_deref(), Some("/etc/gateway/client.pem"));
+    assert!(!config.broker_tls().enabled);
+
+    let err = load(
+        r#"
+[mqtt_egress.tls]
+enabled = true
+ca_file = "ca.pem"
+cert_file = "client.pem"
+"#,
+    )
+    .unwrap_err();
+    assert!(format!("{:#}", err).contains("cert_file and key_file must be set together"));
+
+    let err = load("[broker.tls]\nenabled = true").unwrap_err();
+    assert!(format!("{:#}", err).contains("[broker.tls] needs cert_file and key_file"));
+
+    let mtls = r#"
+[broker.tls]
+enabled = true
+ca_file = "/etc/gateway/ca.pem"
+cert_file = "/etc/gateway/broker.pem"
+key_file = "/etc/gateway/broker.key"
+"#;
+    let err = load(mtls).unwrap_err();
+    assert!(format!("{:#}", err).contains("requires [[broker.users]]"));
+    let config = load(&format!(
+        "{mtls}\n[[broker.users]]\nusername = \"xovis\"\npassword = \"secret\"\npublish = [\"xovis/#\"]\n"
+    ))
+    .unwrap();
+    assert_eq!(config.broker_tls().ca_file.as_deref(), Some("/etc/gateway/ca.pem"));
+}
+
+#[test]
+fn test_broker_users_take_passwords_from_file() {
+    let mut passwd_file = NamedTempFile::new().unwrap();
+    passwd_file.write_all(b"# comment\nxovis:sensor-secret\n").unwrap();
+    passwd_file.flush().unwrap();
+
+    let load = |users: &str| {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let config_content = format!(
+            r##"
+[mqtt]
+host = "localhost"
+port = 1883
+topic = "xovis/#"
+
+[gate]
+mode = "tcp"
+tcp_addr = "127.0.0.1:8000"
+http_url = "http://localhost/open"
+timeout_ms = 2000
+
+[rs485]
+device = "/dev/null"
+baud = 19200
+poll_interval_ms = 250
+
+[zones]
+pos_zones = [2001]
+gate_zone = 2003
+exit_line = 2004
+
+[metrics]
+interval_secs = 10
+
+[broker]
+password_file = "{}"
+{users}
+"##,
+            passwd_file.path().display()
+        );
+        temp_file.write_all(config_content.as_bytes()).unwrap();
+        temp_file.flush().unwrap();
+        Config::from_file(temp_file.path())
+    };
+
+    let config = load(
+        r##"
+[[broker.users]]
+username = "xovis"
+publish = ["xovis/#"]
+
+[[broker.users]]
+username = "gateway"
+password = "gateway-secret"
+publish = ["gateway/#"]
+subscribe = ["xovis/#"]
+"##,
+    )
+    .unwrap();
+    let users = config.broker_users();
+    assert_eq!(users.len(), 2);
+    assert_eq!(users[0].password.as_deref(), Some("sensor-secret"));
+    assert_eq!(users[1].subscribe, vec!["xovis/#"]);
+
+    let err = load("[[broker.users]]\nusername = \"tui\"").unwrap_err();
+    assert!(format!("{:#}", err).contains("tui has no password"));
+
+    let err =
+        load("[[broker.users]]\nusername = \"xovis\"\npublish = [\"xovis/#/x\"]").unwrap_err();
+    assert!(format!("{:#}", err).contains("invalid topic filter"));
+}
+
+#[test]
+fn test_http_ingress_replaces_mqtt() {
+    let load = |ingress: &str| {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let config_content = format!(
+            r##"
+[mqtt]
+enabled = false
+host = "localhost"
+port = 1883
+topic = "xovis/#"
+
+[gate]
+mode = "tcp"
+tcp_addr = "127.0.0.1:8000"
+http_url = "http://localhost/open"
+timeout_ms = 2000
+
+[rs485]
+device = "/dev/null"
+baud = 19200
+poll_interval_ms = 250
+
+[zones]
+pos_zones = [2001]
+gate_zone = 2003
+exit_line = 2004
+
+[metrics]
+interval_secs = 10
+
+{ingress}
+"##
+        );
+        temp_file.write_all(config_content.as_bytes()).unwrap();
+        temp_file.flush().unwrap();
+        Config::from_file(temp_file.path())
+    };
+
+    let config = load(
+        "[http_ingress]\nenabled = true\nport = 9000\nauth_token = \"secret\"\nallowed_ips = [\"10.0.0.5\"]",
+    )
+    .unwrap();
+    assert!(!config.mqtt_enabled());
+    assert!(config.http_ingress().enabled);
+    assert_eq!(config.http_ingress().port, 9000);
+    assert_eq!(config.http_ingress().bind_address, "0.0.0.0");
+    assert_eq!(config.http_ingress().auth_token.as_deref(), Some("secret"));
+    assert_eq!(
+        config.http_ingress().allowed_ips,
+        vec!["10.0.0.5".parse::<std::net::IpAddr>().unwrap()]
+    );
+
+    // Pushed frames can open the gate: an open listener is refused
+    let err = load("[http_ingress]\nenabled = true").unwrap_err();
+    assert!(format!("{:#}", err).contains("requires auth_token or allowed_ips"));
+
+    let err = load("").unwrap_err();
+    assert!(format!("{:#}", err).contains("No sensor ingress"));
+}
+
+#[test]
+fn test_check_config_reports_every_semantic_problem() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    let config_content = r#"
+[mqtt]
+host = "localhost"
+port = 1883
+topic = "xovis/#"
+
+[gate]
+mode = "tcp"
+tcp_addr = "127.0.0.1:8000"
+http_url = "http://localhost/open"
+timeout_ms = 2000
+
+[rs485]
+device = "/dev/null"
+baud = 19200
+poll_interval_ms = 250
+
+[zones]
+pos_zones = [2001, 2003]
+dwell_zones = [2001]
+gate_zone = 2003
+exit_line = 2004
+entry_line = 2004
+
+[zones.names]
+"2001" = "POS_1"
+
+[acc.ip_to_pos]
+"10.0.0.1" = "POS_1"
+"10.0.0.2" = "POS_2"
+
+[metrics]
+interval_secs = 10
+"#;
+    temp_file.write_all(config_content.as_bytes()).unwrap();
+    temp_file.flush().unwrap();
+
+    let problems = config_check::check_file(temp_file.path().to_str().unwrap()).unwrap_err();
+    let paths: Vec<&str> = problems.iter().map(|p| p.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "zones.pos_zones[1]",
+            "zones.dwell_zones[0]",
+            "zones.entry_line",
+            "acc.ip_to_pos.\"10.0.0.2\"",
+        ]
+    );
+
+    // Structural errors carry the file path
+    let problems = config_check::check_file("/nonexistent/gateway.toml").unwrap_err();
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].path, "/nonexistent/gateway.toml");
+}
+
+#[test]
+fn test_dry_run_gate_mode() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    let config_content = r##"
+[mqtt]
+host = "localhost"
+port = 1883
+topic = "#"
+
+[gate]
+mode = "dry_run"
+tcp_addr = "127.0.0.1:8000"
+http_url = "http://localhost/open"
+timeout_ms = 2000
+
+[rs485]
+device = "/dev/null"
+baud = 19200
+poll_interval_ms = 250
+
+[zones]
+pos_zones = [2001]
+gate_zone = 2003
+exit_line = 2004
+
+[metrics]
+interval_secs = 10
+"##;
+    temp_file.write_all(config_content.as_bytes()).unwrap();
+    temp_file.flush().unwrap();
+
+    let config = Config::from_file(temp_file.path()).unwrap();
+    assert_eq!(config.gate_mode(), GateMode::DryRun);
+}
+
+#[test]
+fn test_mqtt_persistent_session() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    let config_content = r##"
+[mqtt]
+host = "localhost"
+port = 1883
+topic = "#"
+qos = 1
+client_id = "gateway-test-site"
+persistent_session = true
+
+[gate]
+mode = "tcp"
+tcp_addr = "127.0.0.1:8000"
+http_url = "http://localhost/open"
+timeout_ms = 2000
+
+[rs485]
+device = "/dev/null"
+baud = 19200
+poll_interval_ms = 250
+
+[zones]
+pos_zones = [2001]
+gate_zone = 2003
+exit_line = 2004
+
+[metrics]
+interval_secs = 10
+"##;
+    temp_file.write_all(config_content.as_bytes()).unwrap();
+    temp_file.flush().unwrap();
+
+    let config = Config::from_file(temp_file.path()).unwrap();
+    assert_eq!(config.mqtt_qos(), 1);
+    assert_eq!(config.mqtt_client_id(), "gateway-test-site");
+    assert!(config.mqtt_persistent_session());
+
+    let defaults = Config::load_from_path("/nonexistent/config.toml");
+    assert_eq!(defaults.mqtt_qos(), 0);
+    assert!(!defaults.mqtt_persistent_session());
+}
+
+#[test]
+fn test_sensor_watchdog_config() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    let config_content = r##"
+[mqtt]
+host = "localhost"
+port = 1883
+topic = "#"
+
+[gate]
+mode = "tcp"
+tcp_addr = "127.0.0.1:8000"
+http_url = "http://localhost/open"
+timeout_ms = 2000
+
+[rs485]
+device = "/dev/null"
+baud = 19200
+poll_interval_ms = 250
+
+[zones]
+pos_zones = [2001]
+gate_zone = 2003
+exit_line = 2004
+
+[metrics]
+interval_secs = 10
+
+[sensor_watchdog]
+frame_timeout_ms = 3000
+degraded_policy = "keep_locked"
+"##;
+    temp_file.write_all(config_content.as_bytes()).unwrap();
+    temp_file.flush().unwrap();
+
+    let config = Config::from_file(temp_file.path()).unwrap();
+    assert!(config.sensor_watchdog().enabled);
+    assert_eq!(config.sensor_watchdog().frame_timeout_ms, 3000);
+    assert_eq!(config.sensor_watchdog().degraded_policy, DegradedPolicy::KeepLocked);
+}
+
+#[test]
+fn test_frame_reorder_config() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    let config_content = r##"
+[mqtt]
+host = "localhost"
+port = 1883
+topic = "#"
+
+[gate]
+mode = "tcp"
+tcp_addr = "127.0.0.1:8000"
+http_url = "http://localhost/open"
+timeout_ms = 2000
+
+[rs485]
+device = "/dev/null"
+baud = 19200
+poll_interval_ms = 250
+
+[zones]
+pos_zones = [2001]
+gate_zone = 2003
+exit_line = 2004
+
+[metrics]
+interval_secs = 10
+
+[frame_reorder]
+enabled = true
+window_ms = 150
+"##;
+    temp_file.write_all(config_content.as_bytes()).unwrap();
+    temp_file.flush().unwrap();
+
+    let config = Config::from_file(temp_file.path()).unwrap();
+    assert!(config.frame_reorder().enabled);
+    assert_eq!(config.frame_reorder().window_ms, 150);
+}
+
+#[test]
+fn test_stitcher_config() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    let config_content = r##"
+[mqtt]
+host = "localhost"
+port = 1883
+topic = "#"
+
+[gate]
+mode = "tcp"
+tcp_addr = "127.0.0.1:8000"
+http_url = "http://localhost/open"
+timeout_ms = 2000
+
+[rs485]
+device = "/dev/null"
+baud = 19200
+poll_interval_ms = 250
+
+[zones]
+pos_zones = [2001]
+gate_zone = 2003
+exit_line = 2004
+
+[metrics]
+interval_secs = 10
+
+[stitcher]
+max_time_ms = 5000
+max_distance_cm = 200.0
+"##;
+    temp_file.write_all(config_content.as_bytes()).unwrap();
+    temp_file.flush().unwrap();
+
+    let config = Config::from_file(temp_file.path()).unwrap();
+    assert_eq!(config.stitcher().max_time_ms, 5000);
+    assert_eq!(config.stitcher().max_distance_cm, 200.0);
+    assert_eq!(config.stitcher().max_time_pos_zone_ms, 8000);
+}
+
+#[test]
+fn test_shadow_config() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    let config_content = r##"
+[mqtt]
+host = "localhost"
+port = 1883
+topic = "#"
+
+[gate]
+mode = "tcp"
+tcp_addr = "127.0.0.1:8000"
+http_url = "http://localhost/open"
+timeout_ms = 2000
+
+[rs485]
+device = "/dev/null"
+baud = 19200
+poll_interval_ms = 250
+
+[zones]
+pos_zones = [2001]
+gate_zone = 2003
+exit_line = 2004
+
+[metrics]
+interval_secs = 10
+
+[shadow]
+enabled = true
+min_dwell_ms = 3000
+
+[mqtt_egress]
+shadow_topic = "test/shadow"
+"##;
+    temp_file.write_all(config_content.as_bytes()).unwrap();
+    temp_file.flush().unwrap();
+
+    let config = Config::from_file(temp_file.path()).unwrap();
+    assert!(config.shadow().enabled);
+    assert_eq!(config.shadow().min_dwell_ms, Some(3000));
+    assert_eq!(config.shadow().exit_grace_ms, None);
+    assert_eq!(config.shadow().apply(&config).min_dwell_ms(), 3000);
+    assert_eq!(config.mqtt_egress_shadow_topic(), "test/shadow");
+}